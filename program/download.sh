@@ -1,5 +1,7 @@
 #!/bin/bash
-# Download Metaplex Token Metadata program from mainnet
+# Download Metaplex programs from mainnet
 echo "Downloading Metaplex Token Metadata program..."
 solana program dump -u mainnet-beta metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s deps/mpl_token_metadata.so
+echo "Downloading Metaplex Core program..."
+solana program dump -u mainnet-beta CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d deps/mpl_core.so
 echo "Download complete!"
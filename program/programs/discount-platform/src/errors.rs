@@ -46,4 +46,92 @@ pub enum CouponError {
     LocationNotSupported,
     #[msg("Invalid input: string exceeds maximum length")]
     InvalidInput,
+    #[msg("Marketplace is paused")]
+    MarketplacePaused,
+    #[msg("Fee exceeds the maximum allowed")]
+    InvalidFee,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Not the pending marketplace authority")]
+    NotPendingAuthority,
+    #[msg("Insufficient treasury balance")]
+    InsufficientTreasuryBalance,
+    #[msg("Payment mint is not accepted by the marketplace")]
+    PaymentMintNotAccepted,
+    #[msg("Payment mint account does not match")]
+    InvalidPaymentMint,
+    #[msg("Payment token account does not match")]
+    InvalidPaymentAccount,
+    #[msg("Coupon is held in escrow")]
+    CouponEscrowed,
+    #[msg("Revealed bid does not match the commitment")]
+    InvalidBidReveal,
+    #[msg("Not within the bid reveal window")]
+    RevealWindowClosed,
+    #[msg("Bid has already been settled")]
+    BidAlreadySettled,
+    #[msg("Bid is still winning and cannot be withdrawn")]
+    BidStillWinning,
+    #[msg("Invalid Dutch auction price curve")]
+    InvalidPriceCurve,
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+    #[msg("Promotion is already active")]
+    PromotionAlreadyActive,
+    #[msg("Promotion can only be closed once expired")]
+    PromotionStillLive,
+    #[msg("Merchant is inactive")]
+    MerchantInactive,
+    #[msg("Merchant was suspended by the marketplace authority")]
+    MerchantSuspended,
+    #[msg("Signer is not an approved merchant verifier")]
+    NotVerifier,
+    #[msg("Staff role does not permit this action")]
+    StaffRoleNotPermitted,
+    #[msg("Revenue shares must be unique, non-zero and total 10000 basis points")]
+    InvalidRevenueSplit,
+    #[msg("Revenue config or recipient accounts do not match the merchant's split")]
+    RevenueSplitMismatch,
+    #[msg("Metadata URI, NFT name or symbol is empty or too long")]
+    InvalidNftMetadata,
+    #[msg("Coupon has not expired yet")]
+    CouponNotExpired,
+    #[msg("Promotion does not issue coupons in this mode")]
+    WrongIssuanceMode,
+    #[msg("Account is not a valid Metaplex Core asset")]
+    InvalidCoreAsset,
+    #[msg("Coupons from this promotion are not transferable")]
+    CouponNotTransferable,
+    #[msg("Coupons from this promotion can't be resold")]
+    ResaleNotAllowed,
+    #[msg("Royalty exceeds the maximum allowed basis points")]
+    InvalidRoyalty,
+    #[msg("Resale price exceeds the promotion's cap")]
+    ResalePriceTooHigh,
+    #[msg("Missing or mismatched ed25519 signature for the redemption ticket")]
+    InvalidTicketSignature,
+    #[msg("Signed redemption ticket has expired")]
+    TicketExpired,
+    #[msg("Ticket validity is outside the allowed range")]
+    InvalidTicketValidity,
+    #[msg("Redemption ticket is still valid")]
+    TicketStillValid,
+    #[msg("Geofence radius is outside the allowed range")]
+    InvalidGeofenceRadius,
+    #[msg("Invalid redemption model")]
+    InvalidRedemptionModel,
+    #[msg("Redemption amount exceeds what is left on the coupon")]
+    InvalidRedemptionAmount,
+    #[msg("This redemption path only supports single-use coupons")]
+    UnsupportedRedemptionModel,
+    #[msg("Royalty can't change once coupons have been minted")]
+    RoyaltyLocked,
+    #[msg("Price is above the buyer's maximum")]
+    PriceAboveMax,
+    #[msg("Core coupons must stay transferable, resellable and royalty-free")]
+    CoreTermsUnsupported,
+    #[msg("Promotion still has coupons in listings, auctions or stakes")]
+    PromotionHasOpenEscrows,
+    #[msg("Transfer policy can't be tightened once coupons have been minted")]
+    TransferPolicyLocked,
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 
 // Re-export enums from state for use in events
-pub use crate::state::{BadgeType, DealSource, AuctionType};  
+pub use crate::state::{BadgeType, DealSource, AuctionType, VerificationTier, StaffRole, RevenueShare, CouponMetadataState, IssuanceMode, RedemptionModel};  
 
 #[event]
 pub struct MarketplaceInitialized {
@@ -11,6 +11,51 @@ pub struct MarketplaceInitialized {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct MarketplaceConfigUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub old_fee_basis_points: u16,
+    pub new_fee_basis_points: u16,
+    pub is_paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketplaceAuthorityProposed {
+    pub marketplace: Pubkey,
+    pub current_authority: Pubkey,
+    pub proposed_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketplaceAuthorityTransferred {
+    pub marketplace: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentMintUpdated {
+    pub marketplace: Pubkey,
+    pub mint: Pubkey,
+    pub accepted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub marketplace: Pubkey,
+    pub treasury: Pubkey,
+    pub destination: Pubkey,
+    pub payment_mint: Option<Pubkey>,
+    pub amount: u64,
+    pub remaining_balance: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct MerchantRegistered {
     pub merchant: Pubkey,
@@ -20,6 +65,107 @@ pub struct MerchantRegistered {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct MerchantUpdated {
+    pub merchant: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+    pub category: String,
+    pub has_physical_location: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MerchantStatusChanged {
+    pub merchant: Pubkey,
+    pub is_active: bool,
+    pub suspended_by_marketplace: bool,
+    pub changed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MerchantAuthorityTransferred {
+    pub merchant: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MerchantVerified {
+    pub merchant: Pubkey,
+    pub tier: VerificationTier,
+    pub attestation_hash: [u8; 32],
+    pub expires_at: i64,
+    pub verifier: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MerchantVerificationRevoked {
+    pub merchant: Pubkey,
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RevenueConfigUpdated {
+    pub merchant: Pubkey,
+    pub recipients: Vec<RevenueShare>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RevenueConfigClosed {
+    pub merchant: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RevenueSplitPaid {
+    pub merchant: Pubkey,
+    pub source: Pubkey,             // Group deal, promotion, ... the payment came from
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StaffAdded {
+    pub merchant: Pubkey,
+    pub staff: Pubkey,
+    pub role: StaffRole,
+    pub added_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StaffUpdated {
+    pub merchant: Pubkey,
+    pub staff: Pubkey,
+    pub role: StaffRole,
+    pub is_active: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StaffRemoved {
+    pub merchant: Pubkey,
+    pub staff: Pubkey,
+    pub removed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VerifierUpdated {
+    pub marketplace: Pubkey,
+    pub verifier: Pubkey,
+    pub is_verifier: bool,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct PromotionCreated {
     pub promotion: Pubkey,
@@ -28,6 +174,69 @@ pub struct PromotionCreated {
     pub max_supply: u32,
     pub expiry_timestamp: i64,
     pub price: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub issuance_mode: IssuanceMode,
+    pub redemption_model: RedemptionModel,
+    pub merchant_verification: VerificationTier,
+}
+
+#[event]
+pub struct PromotionUpdated {
+    pub promotion: Pubkey,
+    pub merchant: Pubkey,
+    pub max_supply: u32,
+    pub expiry_timestamp: i64,
+    pub price: u64,
+    pub transferable: bool,
+    pub resale_allowed: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ResaleTermsUpdated {
+    pub promotion: Pubkey,
+    pub merchant: Pubkey,
+    pub royalty_basis_points: u16,
+    pub max_resale_bps: Option<u16>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TicketValidityUpdated {
+    pub promotion: Pubkey,
+    pub merchant: Pubkey,
+    pub ticket_validity_seconds: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DiscountCapUpdated {
+    pub promotion: Pubkey,
+    pub merchant: Pubkey,
+    pub max_discount_cap: Option<u64>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PromotionPaused {
+    pub promotion: Pubkey,
+    pub merchant: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PromotionResumed {
+    pub promotion: Pubkey,
+    pub merchant: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PromotionClosed {
+    pub promotion: Pubkey,
+    pub merchant: Pubkey,
+    pub coupons_minted: u32,
+    pub timestamp: i64,
 }
 
 #[event]
@@ -38,6 +247,28 @@ pub struct CouponMinted {
     pub recipient: Pubkey,
     pub merchant: Pubkey,
     pub discount_percentage: u8,
+    pub merchant_verification: VerificationTier,
+}
+
+#[event]
+pub struct CouponPurchased {
+    pub coupon: Pubkey,
+    pub promotion: Pubkey,
+    pub buyer: Pubkey,
+    pub merchant: Pubkey,
+    pub price: u64,
+    pub marketplace_fee: u64,
+    pub payment_mint: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouponMetadataUpdated {
+    pub coupon: Pubkey,
+    pub nft_mint: Pubkey,
+    pub state: CouponMetadataState,
+    pub uri: String,
+    pub timestamp: i64,
 }
 
 #[event]
@@ -57,6 +288,28 @@ pub struct CouponRedeemed {
     pub merchant: Pubkey,
     pub discount_percentage: u8,
     pub redemption_code: String,
+    pub approved_by: Pubkey,        // Merchant authority or staff key that co-signed
+    pub order_amount: u64,          // Bill before the discount, in the promotion's payment units
+    // Same units as order_amount. UserStats and Merchant savings totals only
+    // count SOL, so per-mint totals for SPL promotions come from these events.
+    pub savings: u64,
+    pub payment_mint: Option<Pubkey>, // None = SOL
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CouponPartiallyRedeemed {
+    pub coupon: Pubkey,
+    pub nft_mint: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    pub amount: u64,                // Uses, or value for stored-value coupons
+    pub uses_remaining: u32,
+    pub balance_remaining: u64,
+    pub approved_by: Pubkey,
+    pub order_amount: u64,
+    pub savings: u64,               // Units of payment_mint, as in CouponRedeemed
+    pub payment_mint: Option<Pubkey>,
     pub timestamp: i64,
 }
 
@@ -67,6 +320,7 @@ pub struct CouponListed {
     pub nft_mint: Pubkey,
     pub seller: Pubkey,
     pub price: u64,
+    pub payment_mint: Option<Pubkey>,
 }
 
 #[event]
@@ -86,6 +340,8 @@ pub struct CouponSold {
     pub buyer: Pubkey,
     pub price: u64,
     pub marketplace_fee: u64,
+    pub royalty: u64,
+    pub payment_mint: Option<Pubkey>,
 }
 
 #[event]
@@ -170,6 +426,30 @@ pub struct TicketRedeemed {
     pub nft_mint: Pubkey,
     pub user: Pubkey,
     pub merchant: Pubkey,
+    pub approved_by: Pubkey,
+    pub redeemed_at: i64,
+}
+
+#[event]
+pub struct TicketClosed {
+    pub ticket: Pubkey,
+    pub coupon: Pubkey,
+    pub user: Pubkey,
+    pub was_consumed: bool,
+    pub closed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SignedTicketRedeemed {
+    pub coupon: Pubkey,
+    pub nft_mint: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    pub ticket_hash: [u8; 32],
+    pub nonce: u64,
+    pub expires_at: i64,
+    pub approved_by: Pubkey,
     pub redeemed_at: i64,
 }
 
@@ -182,6 +462,7 @@ pub struct GroupDealCreated {
     pub target_participants: u32,
     pub base_price: u64,
     pub deadline: i64,
+    pub payment_mint: Option<Pubkey>,
 }
 
 #[event]
@@ -219,6 +500,9 @@ pub struct AuctionCreated {
     pub starting_price: u64,
     pub reserve_price: u64,
     pub end_time: i64,
+    pub reveal_end_time: i64,
+    pub buy_now_price: Option<u64>,
+    pub payment_mint: Option<Pubkey>,
 }
 
 #[event]
@@ -230,11 +514,40 @@ pub struct BidPlaced {
     pub new_end_time: i64,
 }
 
+#[event]
+pub struct BidCommitted {
+    pub auction: Pubkey,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub deposit: u64,
+    pub bid_count: u32,
+}
+
+#[event]
+pub struct BidRevealed {
+    pub auction: Pubkey,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub is_highest: bool,
+}
+
+#[event]
+pub struct BidWithdrawn {
+    pub auction: Pubkey,
+    pub bid: Pubkey,
+    pub bidder: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub forfeited: bool,
+}
+
 #[event]
 pub struct AuctionFinalized {
     pub auction: Pubkey,
     pub winner: Pubkey,
     pub final_price: u64,
+    pub royalty: u64,
     pub auction_type: AuctionType,
     pub finalized_at: i64,
 }
@@ -53,6 +53,7 @@ pub fn handler(
         user_stats.total_ratings_given = 0;
         user_stats.total_comments = 0;
         user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
         user_stats.reputation_score = 0;
         user_stats.tier = ReputationTier::Bronze;
         user_stats.badges_earned = Vec::new();
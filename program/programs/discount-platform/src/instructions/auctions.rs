@@ -1,19 +1,31 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program::{transfer, Transfer};
-use anchor_spl::token::{Token, TokenAccount, Mint};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Token, transfer, Transfer, close_account, CloseAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::{
     CouponAuction,
     Bid,
     AuctionType,
+    AuctionTerms,
+    DutchCurve,
+    UnrevealedBidPolicy,
     Coupon,
+    Promotion,
+    IssuanceMode,
+    Merchant,
+    RevenueConfig,
     Marketplace,
     UserStats,
     ReputationTier,
 };
 use crate::errors::CouponError;
+use crate::utils::{PaymentRail, ResaleRoyalty};
 use crate::events::{
     AuctionCreated,
     BidPlaced,
+    BidCommitted,
+    BidRevealed,
+    BidWithdrawn,
     AuctionFinalized,
     AuctionCancelled,
 };
@@ -35,11 +47,51 @@ pub struct CreateAuction<'info> {
     pub auction: Account<'info, CouponAuction>,
     
     #[account(
+        mut,
         constraint = coupon.owner == seller.key() @ CouponError::NotCouponOwner,
         constraint = !coupon.is_redeemed @ CouponError::CouponAlreadyRedeemed,
         constraint = coupon.expiry_timestamp > Clock::get()?.unix_timestamp @ CouponError::CouponExpired
     )]
     pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon,
+        constraint = promotion.resale_allowed @ CouponError::ResaleNotAllowed,
+        constraint = promotion.issuance_mode == IssuanceMode::TokenMetadata @ CouponError::WrongIssuanceMode
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, token::Mint>,
+
+    /// CHECK: Seller's token account
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == nft_mint.key(),
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, token::TokenAccount>,
+
+    /// CHECK: Auction vault token account (PDA)
+    #[account(
+        init,
+        payer = seller,
+        token::mint = nft_mint,
+        token::authority = auction_vault,
+        seeds = [b"auction_vault", auction.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: Account<'info, token::TokenAccount>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
     
     #[account(
         init_if_needed,
@@ -52,6 +104,7 @@ pub struct CreateAuction<'info> {
     
     #[account(mut)]
     pub seller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
@@ -62,11 +115,21 @@ pub fn create_auction(
     starting_price: u64,
     reserve_price: u64,
     duration_seconds: i64,
-    auto_extend: bool,
-    min_bid_increment: u64,
+    terms: AuctionTerms,
 ) -> Result<()> {
+    let AuctionTerms {
+        min_bid_increment,
+        anti_sniping,
+        payment_mint,
+        sealed_bid_config,
+        buy_now_price,
+        dutch_curve,
+    } = terms;
+
+    ctx.accounts.marketplace.require_payment_mint(payment_mint)?;
+
     let auction = &mut ctx.accounts.auction;
-    let coupon = &ctx.accounts.coupon;
+    let coupon = &mut ctx.accounts.coupon;
     let clock = Clock::get()?;
     
     // Validation
@@ -75,26 +138,64 @@ pub fn create_auction(
     require!(duration_seconds >= 300, CouponError::InvalidExpiry); // Min 5 minutes
     require!(duration_seconds <= 604800, CouponError::InvalidExpiry); // Max 7 days
     
+    if let Some(config) = anti_sniping {
+        require!(config.extension_window_seconds > 0, CouponError::InvalidExpiry);
+        require!(config.extension_window_seconds <= duration_seconds, CouponError::InvalidExpiry);
+        require!(config.extension_seconds > 0, CouponError::InvalidExpiry);
+        require!(
+            config.max_total_extension_seconds >= config.extension_seconds,
+            CouponError::InvalidExpiry
+        );
+        require!(config.max_total_extension_seconds <= 604800, CouponError::InvalidExpiry); // Max 7 days
+    }
+    
     match auction_type {
         AuctionType::English => {
             require!(
                 reserve_price <= starting_price,
                 CouponError::InvalidPrice
             );
+            if let Some(price) = buy_now_price {
+                require!(price > starting_price, CouponError::InvalidPrice);
+            }
         },
         AuctionType::Dutch => {
             require!(
                 reserve_price < starting_price,
                 CouponError::InvalidPrice
             );
+            if let Some(curve) = &dutch_curve {
+                curve.validate(starting_price, reserve_price, duration_seconds)?;
+            }
         },
         AuctionType::SealedBid => {
             require!(
                 min_bid_increment > 0,
                 CouponError::InvalidPrice
             );
+            let config = sealed_bid_config.ok_or(CouponError::InvalidInput)?;
+            require!(config.reveal_duration_seconds >= 300, CouponError::InvalidExpiry); // Min 5 minutes
+            require!(config.reveal_duration_seconds <= 604800, CouponError::InvalidExpiry); // Max 7 days
         },
     }
+    require!(
+        buy_now_price.is_none() || auction_type == AuctionType::English,
+        CouponError::InvalidInput
+    );
+    require!(
+        dutch_curve.is_none() || auction_type == AuctionType::Dutch,
+        CouponError::InvalidInput
+    );
+    require!(!coupon.is_escrowed, CouponError::CouponEscrowed);
+
+    // The merchant's resale cap bounds every price the auction can clear at
+    let max_price = ctx.accounts.promotion.max_resale_price();
+    if max_price.is_some() {
+        ctx.accounts.promotion.require_resale_price(starting_price)?;
+        if let Some(price) = buy_now_price {
+            ctx.accounts.promotion.require_resale_price(price)?;
+        }
+    }
     
     // Initialize auction
     auction.coupon = coupon.key();
@@ -113,9 +214,53 @@ pub fn create_auction(
     auction.bid_count = 0;
     auction.is_active = true;
     auction.is_finalized = false;
-    auction.auto_extend = auto_extend;
-    auction.extension_seconds = 300; // 5 minute extension
+    match anti_sniping {
+        Some(config) => {
+            auction.auto_extend = true;
+            auction.extension_window_seconds = config.extension_window_seconds;
+            auction.extension_seconds = config.extension_seconds;
+            auction.max_total_extension_seconds = config.max_total_extension_seconds;
+        },
+        None => {
+            auction.auto_extend = false;
+            auction.extension_window_seconds = 0;
+            auction.extension_seconds = 0;
+            auction.max_total_extension_seconds = 0;
+        },
+    }
+    auction.total_extension_seconds = 0;
+    auction.buy_now_price = buy_now_price;
+    auction.dutch_curve = dutch_curve.unwrap_or(DutchCurve::Linear);
     auction.min_bid_increment = min_bid_increment;
+    auction.payment_mint = payment_mint;
+    auction.second_highest_bid = 0;
+    auction.winning_bid = None;
+    auction.max_price = max_price;
+    auction.royalty_basis_points = ctx.accounts.promotion.royalty_basis_points;
+    match sealed_bid_config {
+        Some(config) if auction_type == AuctionType::SealedBid => {
+            auction.reveal_end_time = auction.end_time + config.reveal_duration_seconds;
+            auction.is_vickrey = config.is_vickrey;
+            auction.unrevealed_policy = config.unrevealed_policy;
+        },
+        _ => {
+            auction.reveal_end_time = auction.end_time;
+            auction.is_vickrey = false;
+            auction.unrevealed_policy = UnrevealedBidPolicy::Refund;
+        },
+    }
+
+    // Take custody of the NFT for the duration of the auction
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.seller_token_account.to_account_info(),
+        to: ctx.accounts.auction_vault.to_account_info(),
+        authority: ctx.accounts.seller.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    transfer(cpi_ctx, 1)?;
+    coupon.is_escrowed = true;
+    ctx.accounts.promotion.open_escrow();
     
     // Update user stats
     let user_stats = &mut ctx.accounts.user_stats;
@@ -126,6 +271,7 @@ pub fn create_auction(
         user_stats.total_ratings_given = 0;
         user_stats.total_comments = 0;
         user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
         user_stats.reputation_score = 0;
         user_stats.tier = ReputationTier::Bronze;
         user_stats.badges_earned = Vec::new();
@@ -144,19 +290,22 @@ pub fn create_auction(
         starting_price,
         reserve_price,
         end_time: auction.end_time,
+        reveal_end_time: auction.reveal_end_time,
+        buy_now_price,
+        payment_mint,
     });
     
     // Fixed: Remove Debug format for auction_type
     msg!("✅ Auction created!");
     msg!("✅ Type: English/Dutch/SealedBid");
     msg!("✅ Duration: {} seconds", duration_seconds);
-    msg!("✅ Starting price: {} lamports", starting_price);
+    msg!("✅ Starting price: {}", starting_price);
     
     Ok(())
 }
 
 // ============================================================================
-// Place Bid (English & Sealed Bid Auctions)
+// Place Bid (English Auctions)
 // ============================================================================
 
 #[derive(Accounts)]
@@ -178,7 +327,7 @@ pub struct PlaceBid<'info> {
     #[account(
         mut,
         constraint = auction.is_active @ CouponError::ListingInactive,
-        constraint = auction.auction_type != AuctionType::Dutch @ CouponError::InvalidInput
+        constraint = auction.auction_type == AuctionType::English @ CouponError::InvalidInput
     )]
     pub auction: Account<'info, CouponAuction>,
     
@@ -190,10 +339,6 @@ pub struct PlaceBid<'info> {
     )]
     pub escrow: SystemAccount<'info>,
     
-    /// CHECK: Previous highest bidder (for refund)
-    #[account(mut)]
-    pub previous_bidder: UncheckedAccount<'info>,
-    
     #[account(
         init_if_needed,
         payer = bidder,
@@ -205,6 +350,22 @@ pub struct PlaceBid<'info> {
     
     #[account(mut)]
     pub bidder: Signer<'info>,
+
+    // SPL payment accounts, only required when the auction has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub bidder_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = payment_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = payment_token_program
+    )]
+    pub escrow_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
@@ -220,79 +381,48 @@ pub fn place_bid(
     require!(!auction.is_expired(clock.unix_timestamp), CouponError::CouponExpired);
     
     // Validate bid amount
-    match auction.auction_type {
-        AuctionType::English => {
-            let min_bid = auction.current_bid + auction.min_bid_increment;
-            require!(bid_amount >= min_bid, CouponError::InvalidPrice);
-        },
-        AuctionType::SealedBid => {
-            require!(bid_amount >= auction.starting_price, CouponError::InvalidPrice);
-        },
-        AuctionType::Dutch => {
-            return Err(CouponError::InvalidInput.into());
-        },
-    }
-    
-    // Escrow the bid
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.bidder.to_account_info(),
-                to: ctx.accounts.escrow.to_account_info(),
-            },
-        ),
-        bid_amount,
+    let min_bid = auction.current_bid + auction.min_bid_increment;
+    require!(bid_amount >= min_bid, CouponError::InvalidPrice);
+    require!(auction.within_price_cap(bid_amount), CouponError::ResalePriceTooHigh);
+    
+    let rail = PaymentRail::resolve(
+        auction.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
     )?;
+    let bidder_info = ctx.accounts.bidder.to_account_info();
+    let escrow_info = ctx.accounts.escrow.to_account_info();
+    let escrow_endpoint = rail.endpoint(&escrow_info, &ctx.accounts.escrow_payment_account)?;
     
-    // For English auctions, refund previous bidder
-    if auction.auction_type == AuctionType::English {
-        if let Some(_prev_bidder) = auction.highest_bidder {
-            if auction.current_bid > 0 {
-                // FIX: Store auction key before creating seeds
-                let auction_key = auction.key();
-                let escrow_seeds = &[
-                    b"auction_escrow",
-                    auction_key.as_ref(),
-                    &[ctx.bumps.escrow],
-                ];
-                let signer_seeds = &[&escrow_seeds[..]];
-                
-                transfer(
-                    CpiContext::new_with_signer(
-                        ctx.accounts.system_program.to_account_info(),
-                        Transfer {
-                            from: ctx.accounts.escrow.to_account_info(),
-                            to: ctx.accounts.previous_bidder.to_account_info(),
-                        },
-                        signer_seeds,
-                    ),
-                    auction.current_bid,
-                )?;
-                
-                msg!("✅ Refunded previous bidder: {} lamports", auction.current_bid);
-            }
-        }
-        
-        // Update auction state
-        auction.current_bid = bid_amount;
-        auction.highest_bidder = Some(ctx.accounts.bidder.key());
-    }
+    // Escrow the bid; the bid it outbids stays escrowed until withdraw_bid
+    let from = rail.endpoint(&bidder_info, &ctx.accounts.bidder_payment_account)?;
+    rail.transfer(&from, &escrow_endpoint, &bidder_info, bid_amount, &[])?;
+    
+    // Update auction state
+    auction.current_bid = bid_amount;
+    auction.highest_bidder = Some(ctx.accounts.bidder.key());
     
     // Initialize bid record
     bid.auction = auction.key();
     bid.bidder = ctx.accounts.bidder.key();
     bid.amount = bid_amount;
     bid.timestamp = clock.unix_timestamp;
-    bid.is_winning = auction.auction_type == AuctionType::English;
+    bid.is_winning = true;
     bid.is_refunded = false;
+    bid.deposit = bid_amount;
+    bid.commitment = [0u8; 32];
+    bid.is_revealed = true;
+    auction.winning_bid = Some(bid.key());
     
     auction.bid_count += 1;
     
     // Auto-extend if enabled and near end
-    if auction.should_extend(clock.unix_timestamp) {
-        auction.end_time += auction.extension_seconds;
-        msg!("⏰ Auction extended by {} seconds", auction.extension_seconds);
+    let extension = auction.extension_for(clock.unix_timestamp);
+    if extension > 0 {
+        auction.end_time += extension;
+        auction.total_extension_seconds += extension;
+        msg!("⏰ Auction extended by {} seconds", extension);
     }
     
     // Update user stats
@@ -304,6 +434,7 @@ pub fn place_bid(
         user_stats.total_ratings_given = 0;
         user_stats.total_comments = 0;
         user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
         user_stats.reputation_score = 0;
         user_stats.tier = ReputationTier::Bronze;
         user_stats.badges_earned = Vec::new();
@@ -321,22 +452,23 @@ pub fn place_bid(
         new_end_time: auction.end_time,
     });
     
-    msg!("✅ Bid placed: {} lamports", bid_amount);
+    msg!("✅ Bid placed: {}", bid_amount);
     msg!("✅ Total bids: {}", auction.bid_count);
     
     Ok(())
 }
 
 // ============================================================================
-// Buy Now (Dutch Auction)
+// Buy Now (English Auctions with a buy-now price)
 // ============================================================================
 
 #[derive(Accounts)]
-pub struct BuyDutchAuction<'info> {
+pub struct BuyNow<'info> {
     #[account(
         mut,
         constraint = auction.is_active @ CouponError::ListingInactive,
-        constraint = auction.auction_type == AuctionType::Dutch @ CouponError::InvalidInput
+        constraint = auction.auction_type == AuctionType::English @ CouponError::InvalidInput,
+        constraint = auction.buy_now_price.is_some() @ CouponError::InvalidInput
     )]
     pub auction: Account<'info, CouponAuction>,
     
@@ -345,9 +477,49 @@ pub struct BuyDutchAuction<'info> {
         constraint = coupon.key() == auction.coupon @ CouponError::WrongCoupon
     )]
     pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Box<Account<'info, Promotion>>,
+
+    #[account(
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+
+    /// CHECK: Merchant receives the royalty when no revenue split is configured
+    #[account(
+        mut,
+        constraint = merchant.authority == merchant_authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant_authority: UncheckedAccount<'info>,
+
+    /// Required when the merchant has a revenue split; recipient endpoints
+    /// follow in remaining accounts
+    #[account(
+        seeds = [b"revenue_config", merchant.key().as_ref()],
+        bump
+    )]
+    pub revenue_config: Option<Account<'info, RevenueConfig>>,
     
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = !marketplace.is_paused @ CouponError::MarketplacePaused
+    )]
     pub marketplace: Account<'info, Marketplace>,
     
+    /// CHECK: Escrow account holding the current high bid
+    #[account(
+        mut,
+        seeds = [b"auction_escrow", auction.key().as_ref()],
+        bump
+    )]
+    pub escrow: SystemAccount<'info>,
+    
     /// CHECK: Seller receiving payment
     #[account(
         mut,
@@ -355,12 +527,20 @@ pub struct BuyDutchAuction<'info> {
     )]
     pub seller: SystemAccount<'info>,
     
-    /// CHECK: Marketplace authority
+    /// CHECK: Fee treasury PDA
     #[account(
         mut,
-        constraint = marketplace.authority == marketplace_authority.key() @ CouponError::NotMarketplaceAuthority
+        seeds = [b"treasury"],
+        bump
     )]
-    pub marketplace_authority: SystemAccount<'info>,
+    pub treasury: SystemAccount<'info>,
+    
+    // Current high bid and its bidder, required once the auction has bids
+    #[account(mut)]
+    pub winning_bid: Option<Account<'info, Bid>>,
+    /// CHECK: Checked against winning_bid.bidder
+    #[account(mut)]
+    pub previous_bidder: Option<UncheckedAccount<'info>>,
     
     #[account(
         init_if_needed,
@@ -373,59 +553,142 @@ pub struct BuyDutchAuction<'info> {
     
     #[account(mut)]
     pub buyer: Signer<'info>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, token::Mint>,
+
+    /// CHECK: Auction vault token account (PDA)
+    #[account(
+        mut,
+        seeds = [b"auction_vault", auction.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: Account<'info, token::TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
+    )]
+    pub buyer_token_account: Account<'info, token::TokenAccount>,
+
+    // SPL payment accounts, only required when the auction has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub buyer_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub seller_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub merchant_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub treasury_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub escrow_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub previous_bidder_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn buy_dutch_auction(ctx: Context<BuyDutchAuction>) -> Result<()> {
+pub fn buy_now<'info>(ctx: Context<'_, '_, '_, 'info, BuyNow<'info>>) -> Result<()> {
     let auction = &mut ctx.accounts.auction;
     let coupon = &mut ctx.accounts.coupon;
     let clock = Clock::get()?;
     
-    // Check not expired
     require!(!auction.is_expired(clock.unix_timestamp), CouponError::CouponExpired);
     
-    // Calculate current Dutch price
-    let current_price = auction.calculate_dutch_price(clock.unix_timestamp);
-    
-    msg!("✅ Current Dutch auction price: {} lamports", current_price);
-    
-    // Calculate fees
-    let marketplace = &ctx.accounts.marketplace;
-    let marketplace_fee = (current_price * marketplace.fee_basis_points as u64) / 10000;
-    let seller_amount = current_price - marketplace_fee;
-    
-    // Pay seller
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.buyer.to_account_info(),
-                to: ctx.accounts.seller.to_account_info(),
-            },
-        ),
-        seller_amount,
-    )?;
+    let price = auction.buy_now_price.ok_or(CouponError::InvalidInput)?;
+    let has_bids = auction.winning_bid.is_some();
     
-    // Pay marketplace fee
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.buyer.to_account_info(),
-                to: ctx.accounts.marketplace_authority.to_account_info(),
-            },
-        ),
-        marketplace_fee,
+    // Buy now is gone once bidding reaches it
+    require!(!has_bids || auction.current_bid < price, CouponError::InvalidPrice);
+    
+    let rail = PaymentRail::resolve(
+        auction.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
     )?;
+    let auction_key = auction.key();
+    
+    // Refund the current high bidder and close their bid
+    if has_bids {
+        let winning_bid = ctx.accounts.winning_bid.as_ref().ok_or(CouponError::InvalidInput)?;
+        let previous_bidder = ctx.accounts.previous_bidder.as_ref().ok_or(CouponError::InvalidInput)?;
+        require!(auction.winning_bid == Some(winning_bid.key()), CouponError::InvalidInput);
+        require_keys_eq!(winning_bid.bidder, previous_bidder.key(), CouponError::InvalidInput);
+        
+        let escrow_seeds = &[
+            b"auction_escrow",
+            auction_key.as_ref(),
+            &[ctx.bumps.escrow],
+        ];
+        let signer_seeds = &[&escrow_seeds[..]];
+        
+        let escrow_info = ctx.accounts.escrow.to_account_info();
+        let from = rail.endpoint(&escrow_info, &ctx.accounts.escrow_payment_account)?;
+        let refund_to = rail.endpoint(
+            &previous_bidder.to_account_info(),
+            &ctx.accounts.previous_bidder_payment_account,
+        )?;
+        rail.transfer(&from, &refund_to, &escrow_info, winning_bid.deposit, signer_seeds)?;
+        winning_bid.close(previous_bidder.to_account_info())?;
+        
+        msg!("✅ Refunded high bidder: {}", auction.current_bid);
+    }
+    
+    let buyer_info = ctx.accounts.buyer.to_account_info();
+    let from = rail.endpoint(&buyer_info, &ctx.accounts.buyer_payment_account)?;
+    let seller_to = rail.endpoint(&ctx.accounts.seller.to_account_info(), &ctx.accounts.seller_payment_account)?;
+    let treasury_to = rail.endpoint(&ctx.accounts.treasury.to_account_info(), &ctx.accounts.treasury_payment_account)?;
+    
+    let royalty = ResaleRoyalty {
+        merchant: &ctx.accounts.merchant,
+        merchant_authority: ctx.accounts.merchant_authority.to_account_info(),
+        merchant_payment_account: &ctx.accounts.merchant_payment_account,
+        revenue_config: ctx.accounts.revenue_config.as_deref(),
+        recipient_accounts: ctx.remaining_accounts,
+        promotion: ctx.accounts.promotion.key(),
+        basis_points: auction.royalty_basis_points,
+    };
     
+    // Pay seller, marketplace fee and merchant royalty
+    let (seller_amount, royalty_amount) = SaleProceeds {
+        royalty,
+        seller_to: &seller_to,
+        treasury_to: &treasury_to,
+    }
+    .pay(&rail, &mut ctx.accounts.marketplace, &from, &buyer_info, price, &[])?;
+    
+    // Deliver the NFT to the buyer
+    release_auction_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.auction_vault,
+        &ctx.accounts.buyer_token_account,
+        &ctx.accounts.seller.to_account_info(),
+        auction_key,
+        ctx.bumps.auction_vault,
+    )?;
+
     // Transfer coupon ownership
     coupon.owner = ctx.accounts.buyer.key();
+    coupon.is_escrowed = false;
+    ctx.accounts.promotion.close_escrow();
     
     // Finalize auction
     auction.is_active = false;
     auction.is_finalized = true;
-    auction.current_bid = current_price;
+    auction.current_bid = price;
     auction.highest_bidder = Some(ctx.accounts.buyer.key());
+    auction.winning_bid = None;
     
     // Update user stats
     let user_stats = &mut ctx.accounts.user_stats;
@@ -436,6 +699,7 @@ pub fn buy_dutch_auction(ctx: Context<BuyDutchAuction>) -> Result<()> {
         user_stats.total_ratings_given = 0;
         user_stats.total_comments = 0;
         user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
         user_stats.reputation_score = 0;
         user_stats.tier = ReputationTier::Bronze;
         user_stats.badges_earned = Vec::new();
@@ -447,39 +711,44 @@ pub fn buy_dutch_auction(ctx: Context<BuyDutchAuction>) -> Result<()> {
     user_stats.last_activity = clock.unix_timestamp;
     
     emit!(AuctionFinalized {
-        auction: auction.key(),
+        auction: auction_key,
         winner: ctx.accounts.buyer.key(),
-        final_price: current_price,
-        auction_type: AuctionType::Dutch,
+        final_price: price,
+        royalty: royalty_amount,
+        auction_type: AuctionType::English,
         finalized_at: clock.unix_timestamp,
     });
     
-    msg!("✅ Dutch auction completed at {} lamports", current_price);
+    msg!("✅ Auction bought now at {}", price);
+    msg!("✅ Seller received: {}", seller_amount);
     
     Ok(())
 }
 
 // ============================================================================
-// Finalize Auction (English & Sealed Bid)
+// Commit Bid (Sealed Bid Auctions)
 // ============================================================================
 
 #[derive(Accounts)]
-pub struct FinalizeAuction<'info> {
+pub struct CommitBid<'info> {
     #[account(
-        mut,
-        constraint = auction.can_finalize(Clock::get()?.unix_timestamp) @ CouponError::InvalidExpiry
+        init,
+        payer = bidder,
+        space = 8 + Bid::INIT_SPACE,
+        // One sealed commitment per bidder per auction
+        seeds = [b"bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump
     )]
-    pub auction: Account<'info, CouponAuction>,
+    pub bid: Account<'info, Bid>,
     
     #[account(
         mut,
-        constraint = coupon.key() == auction.coupon @ CouponError::WrongCoupon
+        constraint = auction.is_active @ CouponError::ListingInactive,
+        constraint = auction.auction_type == AuctionType::SealedBid @ CouponError::InvalidInput
     )]
-    pub coupon: Account<'info, Coupon>,
-    
-    pub marketplace: Account<'info, Marketplace>,
+    pub auction: Account<'info, CouponAuction>,
     
-    /// CHECK: Escrow account
+    /// CHECK: Escrow account for deposits (PDA)
     #[account(
         mut,
         seeds = [b"auction_escrow", auction.key().as_ref()],
@@ -487,155 +756,808 @@ pub struct FinalizeAuction<'info> {
     )]
     pub escrow: SystemAccount<'info>,
     
-    /// CHECK: Seller receiving payment
     #[account(
-        mut,
-        constraint = auction.seller == seller.key() @ CouponError::NotListingSeller
+        init_if_needed,
+        payer = bidder,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", bidder.key().as_ref()],
+        bump
     )]
-    pub seller: SystemAccount<'info>,
+    pub user_stats: Account<'info, UserStats>,
     
-    /// CHECK: Winner receiving coupon
     #[account(mut)]
-    pub winner: UncheckedAccount<'info>,
-    
-    /// CHECK: Marketplace authority
-    #[account(
-        mut,
-        constraint = marketplace.authority == marketplace_authority.key() @ CouponError::NotMarketplaceAuthority
-    )]
-    pub marketplace_authority: SystemAccount<'info>,
-    
+    pub bidder: Signer<'info>,
+
+    // SPL payment accounts, only required when the auction has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub bidder_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-        mut,
-        seeds = [b"user_stats", winner.key().as_ref()],
-        bump
+        init_if_needed,
+        payer = bidder,
+        associated_token::mint = payment_mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = payment_token_program
     )]
-    pub winner_stats: Account<'info, UserStats>,
-    
-    pub authority: Signer<'info>,
+    pub escrow_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn finalize_auction(ctx: Context<FinalizeAuction>) -> Result<()> {
+pub fn commit_bid(
+    ctx: Context<CommitBid>,
+    deposit: u64,
+    commitment: [u8; 32],
+) -> Result<()> {
     let auction = &mut ctx.accounts.auction;
-    let coupon = &mut ctx.accounts.coupon;
+    let bid = &mut ctx.accounts.bid;
     let clock = Clock::get()?;
     
-    // Check if reserve price met
-    if auction.current_bid < auction.reserve_price {
-        msg!("⚠️ Reserve price not met. Auction cancelled.");
-        
-        // Refund highest bidder if exists
-        if let Some(_bidder) = auction.highest_bidder {
-            if auction.current_bid > 0 {
-                // FIX: Store auction key before creating seeds
-                let auction_key = auction.key();
-                let escrow_seeds = &[
-                    b"auction_escrow",
-                    auction_key.as_ref(),
-                    &[ctx.bumps.escrow],
-                ];
-                let signer_seeds = &[&escrow_seeds[..]];
-                
-                transfer(
-                    CpiContext::new_with_signer(
-                        ctx.accounts.system_program.to_account_info(),
-                        Transfer {
-                            from: ctx.accounts.escrow.to_account_info(),
-                            to: ctx.accounts.winner.to_account_info(),
-                        },
-                        signer_seeds,
-                    ),
-                    auction.current_bid,
-                )?;
-                
-                msg!("✅ Refunded bidder: {} lamports", auction.current_bid);
-            }
-        }
-        
-        auction.is_active = false;
-        auction.is_finalized = true;
-        
-        emit!(AuctionCancelled {
-            auction: auction.key(),
-            reason: "Reserve price not met".to_string(),
-            timestamp: clock.unix_timestamp,
-        });
-        
-        return Ok(());
-    }
+    // Commits close when the bidding phase ends
+    require!(!auction.is_expired(clock.unix_timestamp), CouponError::CouponExpired);
     
-    // Reserve price met - complete sale
-    let winner_key = auction.highest_bidder.ok_or(CouponError::InvalidInput)?;
-    require!(winner_key == ctx.accounts.winner.key(), CouponError::NotCouponOwner);
+    // The deposit caps the bid that can later be revealed
+    require!(deposit >= auction.starting_price, CouponError::InvalidPrice);
     
-    let final_price = auction.current_bid;
+    let rail = PaymentRail::resolve(
+        auction.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
+    )?;
+    let bidder_info = ctx.accounts.bidder.to_account_info();
+    let escrow_info = ctx.accounts.escrow.to_account_info();
+    let escrow_endpoint = rail.endpoint(&escrow_info, &ctx.accounts.escrow_payment_account)?;
     
-    // Calculate fees
-    let marketplace = &ctx.accounts.marketplace;
-    let marketplace_fee = (final_price * marketplace.fee_basis_points as u64) / 10000;
-    let seller_amount = final_price - marketplace_fee;
+    // Escrow the deposit
+    let from = rail.endpoint(&bidder_info, &ctx.accounts.bidder_payment_account)?;
+    rail.transfer(&from, &escrow_endpoint, &bidder_info, deposit, &[])?;
     
-    // FIX: Store auction key before creating seeds
-    let auction_key = auction.key();
-    let escrow_seeds = &[
-        b"auction_escrow",
-        auction_key.as_ref(),
-        &[ctx.bumps.escrow],
-    ];
-    let signer_seeds = &[&escrow_seeds[..]];
+    // Initialize bid record; the amount stays hidden until reveal
+    bid.auction = auction.key();
+    bid.bidder = ctx.accounts.bidder.key();
+    bid.amount = 0;
+    bid.timestamp = clock.unix_timestamp;
+    bid.is_winning = false;
+    bid.is_refunded = false;
+    bid.deposit = deposit;
+    bid.commitment = commitment;
+    bid.is_revealed = false;
     
-    // Pay seller
-    transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow.to_account_info(),
-                to: ctx.accounts.seller.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        seller_amount,
-    )?;
+    auction.bid_count += 1;
     
-    // Pay marketplace fee
-    transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow.to_account_info(),
-                to: ctx.accounts.marketplace_authority.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        marketplace_fee,
-    )?;
+    // Update user stats
+    let user_stats = &mut ctx.accounts.user_stats;
+    if user_stats.user == Pubkey::default() {
+        user_stats.user = ctx.accounts.bidder.key();
+        user_stats.total_purchases = 0;
+        user_stats.total_redemptions = 0;
+        user_stats.total_ratings_given = 0;
+        user_stats.total_comments = 0;
+        user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
+        user_stats.reputation_score = 0;
+        user_stats.tier = ReputationTier::Bronze;
+        user_stats.badges_earned = Vec::new();
+        user_stats.joined_at = clock.unix_timestamp;
+    }
     
-    // Transfer coupon to winner
-    coupon.owner = winner_key;
+    user_stats.add_reputation(2); // Reputation for participating
+    user_stats.last_activity = clock.unix_timestamp;
     
-    // Update winner stats
-    let winner_stats = &mut ctx.accounts.winner_stats;
-    winner_stats.total_purchases += 1;
-    winner_stats.add_reputation(5);
-    winner_stats.last_activity = clock.unix_timestamp;
+    emit!(BidCommitted {
+        auction: auction.key(),
+        bid: bid.key(),
+        bidder: ctx.accounts.bidder.key(),
+        deposit,
+        bid_count: auction.bid_count,
+    });
     
-    // Finalize auction
-    auction.is_active = false;
-    auction.is_finalized = true;
+    msg!("✅ Sealed bid committed");
+    msg!("✅ Deposit: {}", deposit);
+    msg!("✅ Total bids: {}", auction.bid_count);
+    
+    Ok(())
+}
+
+// ============================================================================
+// Reveal Bid (Sealed Bid Auctions)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct RevealBid<'info> {
+    #[account(
+        mut,
+        constraint = bid.auction == auction.key() @ CouponError::InvalidInput,
+        constraint = bid.bidder == bidder.key() @ CouponError::InvalidInput,
+        constraint = !bid.is_revealed @ CouponError::BidAlreadySettled
+    )]
+    pub bid: Account<'info, Bid>,
+    
+    #[account(
+        mut,
+        constraint = auction.is_active @ CouponError::ListingInactive,
+        constraint = auction.auction_type == AuctionType::SealedBid @ CouponError::InvalidInput
+    )]
+    pub auction: Account<'info, CouponAuction>,
+    
+    pub bidder: Signer<'info>,
+}
+
+pub fn reveal_bid(
+    ctx: Context<RevealBid>,
+    amount: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let bid = &mut ctx.accounts.bid;
+    let clock = Clock::get()?;
+    
+    require!(auction.is_reveal_window(clock.unix_timestamp), CouponError::RevealWindowClosed);
+    require!(
+        Bid::commitment_hash(amount, &salt, &bid.bidder, &auction.key()) == bid.commitment,
+        CouponError::InvalidBidReveal
+    );
+    require!(amount >= auction.starting_price, CouponError::InvalidPrice);
+    require!(amount <= bid.deposit, CouponError::InvalidPrice);
+    require!(auction.within_price_cap(amount), CouponError::ResalePriceTooHigh);
+    
+    bid.amount = amount;
+    bid.is_revealed = true;
+    
+    // Ties go to the earliest reveal
+    let is_highest = amount > auction.current_bid;
+    if is_highest {
+        auction.second_highest_bid = auction.current_bid;
+        auction.current_bid = amount;
+        auction.highest_bidder = Some(bid.bidder);
+        auction.winning_bid = Some(bid.key());
+    } else if amount > auction.second_highest_bid {
+        auction.second_highest_bid = amount;
+    }
+    
+    emit!(BidRevealed {
+        auction: auction.key(),
+        bid: bid.key(),
+        bidder: bid.bidder,
+        amount,
+        is_highest,
+    });
+    
+    msg!("✅ Bid revealed: {}", amount);
+    
+    Ok(())
+}
+
+// ============================================================================
+// Withdraw Bid (outbid English bids, sealed deposits after finalization)
+// ============================================================================
+
+/// Permissionless crank: anyone may settle a bid, the refund always goes to
+/// `bid.bidder` (forfeits to the seller) and the bid's rent to the bidder.
+#[derive(Accounts)]
+pub struct WithdrawBid<'info> {
+    #[account(
+        mut,
+        constraint = bid.auction == auction.key() @ CouponError::InvalidInput,
+        constraint = !bid.is_refunded @ CouponError::BidAlreadySettled,
+        close = bidder
+    )]
+    pub bid: Account<'info, Bid>,
+    
+    #[account(
+        constraint = auction.auction_type != AuctionType::Dutch @ CouponError::InvalidInput
+    )]
+    pub auction: Account<'info, CouponAuction>,
+    
+    /// CHECK: Escrow account for deposits (PDA)
+    #[account(
+        mut,
+        seeds = [b"auction_escrow", auction.key().as_ref()],
+        bump
+    )]
+    pub escrow: SystemAccount<'info>,
+    
+    /// CHECK: Bidder receiving the refund
+    #[account(
+        mut,
+        constraint = bid.bidder == bidder.key() @ CouponError::InvalidInput
+    )]
+    pub bidder: UncheckedAccount<'info>,
+    
+    /// CHECK: Seller receiving forfeited deposits
+    #[account(
+        mut,
+        constraint = auction.seller == seller.key() @ CouponError::NotListingSeller
+    )]
+    pub seller: SystemAccount<'info>,
+
+    // SPL payment accounts, only required when the auction has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub escrow_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub bidder_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub seller_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn withdraw_bid(ctx: Context<WithdrawBid>) -> Result<()> {
+    let auction = &ctx.accounts.auction;
+    let bid = &mut ctx.accounts.bid;
+    let is_winner = auction.winning_bid == Some(bid.key());
+    
+    // Sealed bids can only be ranked once the reveal window has closed;
+    // English bids are free as soon as they are outbid
+    match auction.auction_type {
+        AuctionType::SealedBid => {
+            require!(auction.is_finalized, CouponError::InvalidExpiry);
+        },
+        _ => {
+            require!(auction.is_finalized || !is_winner, CouponError::BidStillWinning);
+        },
+    }
+    
+    // The winner gets back whatever the clearing price left of the deposit;
+    // unrevealed deposits follow the auction's policy
+    let forfeited = !bid.is_revealed && auction.unrevealed_policy == UnrevealedBidPolicy::Forfeit;
+    let amount = if is_winner {
+        bid.deposit.saturating_sub(auction.current_bid)
+    } else {
+        bid.deposit
+    };
+    
+    let rail = PaymentRail::resolve(
+        auction.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
+    )?;
+    let escrow_info = ctx.accounts.escrow.to_account_info();
+    let from = rail.endpoint(&escrow_info, &ctx.accounts.escrow_payment_account)?;
+    let (recipient, to) = if forfeited {
+        (
+            ctx.accounts.seller.key(),
+            rail.endpoint(&ctx.accounts.seller.to_account_info(), &ctx.accounts.seller_payment_account)?,
+        )
+    } else {
+        (
+            ctx.accounts.bidder.key(),
+            rail.endpoint(&ctx.accounts.bidder.to_account_info(), &ctx.accounts.bidder_payment_account)?,
+        )
+    };
+    
+    let auction_key = auction.key();
+    let escrow_seeds = &[
+        b"auction_escrow",
+        auction_key.as_ref(),
+        &[ctx.bumps.escrow],
+    ];
+    let signer_seeds = &[&escrow_seeds[..]];
+    
+    rail.transfer(&from, &to, &escrow_info, amount, signer_seeds)?;
+    
+    bid.is_winning = is_winner;
+    bid.is_refunded = true;
+    
+    emit!(BidWithdrawn {
+        auction: auction_key,
+        bid: bid.key(),
+        bidder: bid.bidder,
+        recipient,
+        amount,
+        forfeited,
+    });
+    
+    if forfeited {
+        msg!("✅ Unrevealed deposit forfeited to seller: {}", amount);
+    } else {
+        msg!("✅ Deposit returned to bidder: {}", amount);
+    }
+    
+    Ok(())
+}
+
+// ============================================================================
+// Buy Now (Dutch Auction)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct BuyDutchAuction<'info> {
+    #[account(
+        mut,
+        constraint = auction.is_active @ CouponError::ListingInactive,
+        constraint = auction.auction_type == AuctionType::Dutch @ CouponError::InvalidInput
+    )]
+    pub auction: Account<'info, CouponAuction>,
+    
+    #[account(
+        mut,
+        constraint = coupon.key() == auction.coupon @ CouponError::WrongCoupon
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Box<Account<'info, Promotion>>,
+
+    #[account(
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+
+    /// CHECK: Merchant receives the royalty when no revenue split is configured
+    #[account(
+        mut,
+        constraint = merchant.authority == merchant_authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant_authority: UncheckedAccount<'info>,
+
+    /// Required when the merchant has a revenue split; recipient endpoints
+    /// follow in remaining accounts
+    #[account(
+        seeds = [b"revenue_config", merchant.key().as_ref()],
+        bump
+    )]
+    pub revenue_config: Option<Account<'info, RevenueConfig>>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = !marketplace.is_paused @ CouponError::MarketplacePaused
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    /// CHECK: Seller receiving payment
+    #[account(
+        mut,
+        constraint = auction.seller == seller.key() @ CouponError::NotListingSeller
+    )]
+    pub seller: SystemAccount<'info>,
+    
+    /// CHECK: Fee treasury PDA
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", buyer.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+    
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, token::Mint>,
+
+    /// CHECK: Auction vault token account (PDA)
+    #[account(
+        mut,
+        seeds = [b"auction_vault", auction.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: Account<'info, token::TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
+    )]
+    pub buyer_token_account: Account<'info, token::TokenAccount>,
+
+    // SPL payment accounts, only required when the auction has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub buyer_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub seller_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub merchant_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub treasury_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn buy_dutch_auction<'info>(ctx: Context<'_, '_, '_, 'info, BuyDutchAuction<'info>>) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let coupon = &mut ctx.accounts.coupon;
+    let clock = Clock::get()?;
+    
+    // Check not expired
+    require!(!auction.is_expired(clock.unix_timestamp), CouponError::CouponExpired);
+    
+    // Calculate current Dutch price
+    let current_price = auction.calculate_dutch_price(clock.unix_timestamp)?;
+    
+    msg!("✅ Current Dutch auction price: {}", current_price);
+    
+    let rail = PaymentRail::resolve(
+        auction.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
+    )?;
+    let buyer_info = ctx.accounts.buyer.to_account_info();
+    let from = rail.endpoint(&buyer_info, &ctx.accounts.buyer_payment_account)?;
+    let seller_to = rail.endpoint(&ctx.accounts.seller.to_account_info(), &ctx.accounts.seller_payment_account)?;
+    let treasury_to = rail.endpoint(&ctx.accounts.treasury.to_account_info(), &ctx.accounts.treasury_payment_account)?;
+    
+    let royalty = ResaleRoyalty {
+        merchant: &ctx.accounts.merchant,
+        merchant_authority: ctx.accounts.merchant_authority.to_account_info(),
+        merchant_payment_account: &ctx.accounts.merchant_payment_account,
+        revenue_config: ctx.accounts.revenue_config.as_deref(),
+        recipient_accounts: ctx.remaining_accounts,
+        promotion: ctx.accounts.promotion.key(),
+        basis_points: auction.royalty_basis_points,
+    };
+    
+    // Pay seller, marketplace fee and merchant royalty
+    let (_, royalty_amount) = SaleProceeds {
+        royalty,
+        seller_to: &seller_to,
+        treasury_to: &treasury_to,
+    }
+    .pay(&rail, &mut ctx.accounts.marketplace, &from, &buyer_info, current_price, &[])?;
+    
+    // Deliver the NFT to the buyer
+    release_auction_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.auction_vault,
+        &ctx.accounts.buyer_token_account,
+        &ctx.accounts.seller.to_account_info(),
+        auction.key(),
+        ctx.bumps.auction_vault,
+    )?;
+
+    // Transfer coupon ownership
+    coupon.owner = ctx.accounts.buyer.key();
+    coupon.is_escrowed = false;
+    ctx.accounts.promotion.close_escrow();
+    
+    // Finalize auction
+    auction.is_active = false;
+    auction.is_finalized = true;
+    auction.current_bid = current_price;
+    auction.highest_bidder = Some(ctx.accounts.buyer.key());
+    
+    // Update user stats
+    let user_stats = &mut ctx.accounts.user_stats;
+    if user_stats.user == Pubkey::default() {
+        user_stats.user = ctx.accounts.buyer.key();
+        user_stats.total_purchases = 0;
+        user_stats.total_redemptions = 0;
+        user_stats.total_ratings_given = 0;
+        user_stats.total_comments = 0;
+        user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
+        user_stats.reputation_score = 0;
+        user_stats.tier = ReputationTier::Bronze;
+        user_stats.badges_earned = Vec::new();
+        user_stats.joined_at = clock.unix_timestamp;
+    }
+    
+    user_stats.total_purchases += 1;
+    user_stats.add_reputation(5);
+    user_stats.last_activity = clock.unix_timestamp;
+    
+    emit!(AuctionFinalized {
+        auction: auction.key(),
+        winner: ctx.accounts.buyer.key(),
+        final_price: current_price,
+        royalty: royalty_amount,
+        auction_type: AuctionType::Dutch,
+        finalized_at: clock.unix_timestamp,
+    });
+    
+    msg!("✅ Dutch auction completed at {}", current_price);
+    
+    Ok(())
+}
+
+// ============================================================================
+// Get Dutch Price (read-only view)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct GetDutchPrice<'info> {
+    #[account(
+        constraint = auction.auction_type == AuctionType::Dutch @ CouponError::InvalidInput
+    )]
+    pub auction: Account<'info, CouponAuction>,
+}
+
+pub fn get_dutch_price(ctx: Context<GetDutchPrice>) -> Result<u64> {
+    let clock = Clock::get()?;
+    ctx.accounts.auction.calculate_dutch_price(clock.unix_timestamp)
+}
+
+// ============================================================================
+// Finalize Auction (English & Sealed Bid)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct FinalizeAuction<'info> {
+    #[account(
+        mut,
+        constraint = auction.can_finalize(Clock::get()?.unix_timestamp) @ CouponError::InvalidExpiry
+    )]
+    pub auction: Account<'info, CouponAuction>,
+    
+    #[account(
+        mut,
+        constraint = coupon.key() == auction.coupon @ CouponError::WrongCoupon
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Box<Account<'info, Promotion>>,
+
+    #[account(
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+
+    /// CHECK: Merchant receives the royalty when no revenue split is configured
+    #[account(
+        mut,
+        constraint = merchant.authority == merchant_authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant_authority: UncheckedAccount<'info>,
+
+    /// Required when the merchant has a revenue split; recipient endpoints
+    /// follow in remaining accounts
+    #[account(
+        seeds = [b"revenue_config", merchant.key().as_ref()],
+        bump
+    )]
+    pub revenue_config: Option<Account<'info, RevenueConfig>>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = !marketplace.is_paused @ CouponError::MarketplacePaused
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    /// CHECK: Escrow account
+    #[account(
+        mut,
+        seeds = [b"auction_escrow", auction.key().as_ref()],
+        bump
+    )]
+    pub escrow: SystemAccount<'info>,
+    
+    /// CHECK: Seller receiving payment
+    #[account(
+        mut,
+        constraint = auction.seller == seller.key() @ CouponError::NotListingSeller
+    )]
+    pub seller: SystemAccount<'info>,
+    
+    /// CHECK: Winner receiving coupon
+    #[account(mut)]
+    pub winner: UncheckedAccount<'info>,
+    
+    /// CHECK: Fee treasury PDA
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", winner.key().as_ref()],
+        bump
+    )]
+    pub winner_stats: Account<'info, UserStats>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, token::Mint>,
+
+    /// CHECK: Auction vault token account (PDA)
+    #[account(
+        mut,
+        seeds = [b"auction_vault", auction.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: Account<'info, token::TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = nft_mint,
+        associated_token::authority = winner,
+        associated_token::token_program = token_program
+    )]
+    pub winner_token_account: Account<'info, token::TokenAccount>,
+
+    /// CHECK: Seller's token account (NFT returned here if there is no sale)
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == nft_mint.key(),
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, token::TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // SPL payment accounts, only required when the auction has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub escrow_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub seller_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub merchant_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub treasury_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn finalize_auction<'info>(ctx: Context<'_, '_, '_, 'info, FinalizeAuction<'info>>) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let coupon = &mut ctx.accounts.coupon;
+    let clock = Clock::get()?;
+
+    let rail = PaymentRail::resolve(
+        auction.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
+    )?;
+    let escrow_info = ctx.accounts.escrow.to_account_info();
+    
+    // Check if reserve price met
+    if auction.current_bid < auction.reserve_price {
+        msg!("⚠️ Reserve price not met. Auction cancelled.");
+        
+        // Bids stay escrowed; every bidder reclaims theirs via withdraw_bid
+
+        // Return the NFT to the seller
+        release_auction_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.auction_vault,
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.seller.to_account_info(),
+            auction.key(),
+            ctx.bumps.auction_vault,
+        )?;
+        coupon.is_escrowed = false;
+        ctx.accounts.promotion.close_escrow();
+        
+        auction.is_active = false;
+        auction.is_finalized = true;
+        auction.winning_bid = None;
+        
+        emit!(AuctionCancelled {
+            auction: auction.key(),
+            reason: "Reserve price not met".to_string(),
+            timestamp: clock.unix_timestamp,
+        });
+        
+        return Ok(());
+    }
+    
+    // Reserve price met - complete sale
+    let winner_key = auction.highest_bidder.ok_or(CouponError::InvalidInput)?;
+    require!(winner_key == ctx.accounts.winner.key(), CouponError::NotCouponOwner);
+    
+    let final_price = if auction.auction_type == AuctionType::SealedBid {
+        auction.sealed_clearing_price()
+    } else {
+        auction.current_bid
+    };
+    
+    // FIX: Store auction key before creating seeds
+    let auction_key = auction.key();
+    let escrow_seeds = &[
+        b"auction_escrow",
+        auction_key.as_ref(),
+        &[ctx.bumps.escrow],
+    ];
+    let signer_seeds = &[&escrow_seeds[..]];
+    
+    let from = rail.endpoint(&escrow_info, &ctx.accounts.escrow_payment_account)?;
+    let seller_to = rail.endpoint(&ctx.accounts.seller.to_account_info(), &ctx.accounts.seller_payment_account)?;
+    let treasury_to = rail.endpoint(&ctx.accounts.treasury.to_account_info(), &ctx.accounts.treasury_payment_account)?;
+    
+    let royalty = ResaleRoyalty {
+        merchant: &ctx.accounts.merchant,
+        merchant_authority: ctx.accounts.merchant_authority.to_account_info(),
+        merchant_payment_account: &ctx.accounts.merchant_payment_account,
+        revenue_config: ctx.accounts.revenue_config.as_deref(),
+        recipient_accounts: ctx.remaining_accounts,
+        promotion: ctx.accounts.promotion.key(),
+        basis_points: auction.royalty_basis_points,
+    };
+    
+    // Pay seller, marketplace fee and merchant royalty
+    let (seller_amount, royalty_amount) = SaleProceeds {
+        royalty,
+        seller_to: &seller_to,
+        treasury_to: &treasury_to,
+    }
+    .pay(&rail, &mut ctx.accounts.marketplace, &from, &escrow_info, final_price, signer_seeds)?;
+    
+    // Deliver the NFT to the winner
+    release_auction_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.auction_vault,
+        &ctx.accounts.winner_token_account,
+        &ctx.accounts.seller.to_account_info(),
+        auction_key,
+        ctx.bumps.auction_vault,
+    )?;
+
+    // Transfer coupon to winner
+    coupon.owner = winner_key;
+    coupon.is_escrowed = false;
+    ctx.accounts.promotion.close_escrow();
+    
+    // Update winner stats
+    let winner_stats = &mut ctx.accounts.winner_stats;
+    winner_stats.total_purchases += 1;
+    winner_stats.add_reputation(5);
+    winner_stats.last_activity = clock.unix_timestamp;
+    
+    // Finalize auction; current_bid now records what the winner paid
+    auction.is_active = false;
+    auction.is_finalized = true;
+    auction.current_bid = final_price;
     
     emit!(AuctionFinalized {
         auction: auction.key(),
         winner: winner_key,
         final_price,
+        royalty: royalty_amount,
         auction_type: auction.auction_type,
         finalized_at: clock.unix_timestamp,
     });
     
     msg!("✅ Auction finalized!");
     msg!("✅ Winner: {}", winner_key);
-    msg!("✅ Final price: {} lamports", final_price);
-    msg!("✅ Seller received: {} lamports", seller_amount);
+    msg!("✅ Final price: {}", final_price);
+    msg!("✅ Seller received: {}", seller_amount);
     
     Ok(())
 }
@@ -654,13 +1576,54 @@ pub struct CancelAuction<'info> {
         close = seller
     )]
     pub auction: Account<'info, CouponAuction>,
+
+    #[account(
+        mut,
+        constraint = coupon.key() == auction.coupon @ CouponError::WrongCoupon
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    /// CHECK: Auction vault token account (PDA)
+    #[account(
+        mut,
+        seeds = [b"auction_vault", auction.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: Account<'info, token::TokenAccount>,
+
+    /// CHECK: Seller's token account
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == auction_vault.mint,
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, token::TokenAccount>,
     
     #[account(mut)]
     pub seller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
 pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
     let auction = &ctx.accounts.auction;
+
+    // Return the NFT to the seller
+    release_auction_vault(
+        &ctx.accounts.token_program,
+        &ctx.accounts.auction_vault,
+        &ctx.accounts.seller_token_account,
+        &ctx.accounts.seller.to_account_info(),
+        auction.key(),
+        ctx.bumps.auction_vault,
+    )?;
+    ctx.accounts.coupon.is_escrowed = false;
+    ctx.accounts.promotion.close_escrow();
     
     emit!(AuctionCancelled {
         auction: auction.key(),
@@ -673,6 +1636,84 @@ pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
     Ok(())
 }
 
+// ============================================================================
+// Settlement Helpers
+// ============================================================================
+
+/// Everyone paid out of an auction sale: the seller, the fee treasury and
+/// the merchant's royalty. Shared by every path that closes an auction with
+/// a sale.
+struct SaleProceeds<'a, 'info> {
+    royalty: ResaleRoyalty<'a, 'info>,
+    seller_to: &'a AccountInfo<'info>,
+    treasury_to: &'a AccountInfo<'info>,
+}
+
+impl<'a, 'info> SaleProceeds<'a, 'info> {
+    /// Splits `price` out of `from` and returns the seller's share and the
+    /// royalty paid.
+    fn pay(
+        &self,
+        rail: &PaymentRail<'info>,
+        marketplace: &mut Account<'info, Marketplace>,
+        from: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        price: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<(u64, u64)> {
+        let marketplace_fee = marketplace.calculate_fee(price);
+        let seller_amount = price - marketplace_fee - self.royalty.calculate(price);
+
+        // Pay seller
+        rail.transfer(from, self.seller_to, authority, seller_amount, signer_seeds)?;
+
+        // Pay marketplace fee into the treasury
+        rail.transfer(from, self.treasury_to, authority, marketplace_fee, signer_seeds)?;
+        if !rail.is_token() {
+            marketplace.record_fee(marketplace_fee);
+        }
+
+        // Pay the merchant's royalty
+        let royalty_amount = self.royalty.pay(rail, from, authority, price, signer_seeds)?;
+
+        Ok((seller_amount, royalty_amount))
+    }
+}
+
+/// Moves the escrowed NFT out of the auction vault and closes the vault,
+/// returning its rent to the seller.
+fn release_auction_vault<'info>(
+    token_program: &Program<'info, Token>,
+    auction_vault: &Account<'info, token::TokenAccount>,
+    destination: &Account<'info, token::TokenAccount>,
+    seller: &AccountInfo<'info>,
+    auction_key: Pubkey,
+    vault_bump: u8,
+) -> Result<()> {
+    let vault_seeds = &[
+        b"auction_vault",
+        auction_key.as_ref(),
+        &[vault_bump],
+    ];
+    let vault_signer = &[&vault_seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: auction_vault.to_account_info(),
+        to: destination.to_account_info(),
+        authority: auction_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, vault_signer);
+    transfer(cpi_ctx, 1)?;
+
+    let cpi_accounts = CloseAccount {
+        account: auction_vault.to_account_info(),
+        destination: seller.clone(),
+        authority: auction_vault.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, vault_signer);
+    close_account(cpi_ctx)
+}
+
 // ============================================================================
 // Handlers for lib.rs
 // ============================================================================
@@ -684,8 +1725,7 @@ pub fn handler_create_auction(
     starting_price: u64,
     reserve_price: u64,
     duration_seconds: i64,
-    auto_extend: bool,
-    min_bid_increment: u64,
+    terms: AuctionTerms,
 ) -> Result<()> {
     create_auction(
         ctx,
@@ -694,8 +1734,7 @@ pub fn handler_create_auction(
         starting_price,
         reserve_price,
         duration_seconds,
-        auto_extend,
-        min_bid_increment,
+        terms,
     )
 }
 
@@ -706,11 +1745,39 @@ pub fn handler_place_bid(
     place_bid(ctx, bid_amount)
 }
 
-pub fn handler_buy_dutch_auction(ctx: Context<BuyDutchAuction>) -> Result<()> {
+pub fn handler_buy_now<'info>(ctx: Context<'_, '_, '_, 'info, BuyNow<'info>>) -> Result<()> {
+    buy_now(ctx)
+}
+
+pub fn handler_commit_bid(
+    ctx: Context<CommitBid>,
+    deposit: u64,
+    commitment: [u8; 32],
+) -> Result<()> {
+    commit_bid(ctx, deposit, commitment)
+}
+
+pub fn handler_reveal_bid(
+    ctx: Context<RevealBid>,
+    amount: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    reveal_bid(ctx, amount, salt)
+}
+
+pub fn handler_withdraw_bid(ctx: Context<WithdrawBid>) -> Result<()> {
+    withdraw_bid(ctx)
+}
+
+pub fn handler_buy_dutch_auction<'info>(ctx: Context<'_, '_, '_, 'info, BuyDutchAuction<'info>>) -> Result<()> {
     buy_dutch_auction(ctx)
 }
 
-pub fn handler_finalize_auction(ctx: Context<FinalizeAuction>) -> Result<()> {
+pub fn handler_get_dutch_price(ctx: Context<GetDutchPrice>) -> Result<u64> {
+    get_dutch_price(ctx)
+}
+
+pub fn handler_finalize_auction<'info>(ctx: Context<'_, '_, '_, 'info, FinalizeAuction<'info>>) -> Result<()> {
     finalize_auction(ctx)
 }
 
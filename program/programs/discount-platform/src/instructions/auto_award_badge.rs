@@ -1,10 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::associated_token::AssociatedToken;
 use mpl_token_metadata::instructions::CreateV1CpiBuilder;
 use mpl_token_metadata::types::{TokenStandard, PrintSupply};
 use crate::state::{BadgeNFT, BadgeType, UserStats};
 use crate::errors::CouponError;
 use crate::events::BadgeEarned;
+use crate::utils::mint_soulbound_badge;
 
 const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
 const SYSVAR_INSTRUCTIONS_ID: Pubkey = pubkey!("Sysvar1nstructions1111111111111111111111111");
@@ -36,11 +38,20 @@ pub struct AutoAwardBadge<'info> {
         init,
         payer = payer,
         mint::decimals = 0,
-        mint::authority = authority,
-        mint::freeze_authority = authority
+        mint::authority = badge_authority,
+        mint::freeze_authority = badge_authority
     )]
     pub mint: Account<'info, Mint>,
 
+    /// Holder's badge token account, frozen once the badge is minted
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
     /// CHECK: Metadata account
     #[account(mut)]
     pub metadata: UncheckedAccount<'info>,
@@ -52,8 +63,14 @@ pub struct AutoAwardBadge<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     
-    pub authority: Signer<'info>,
+    /// CHECK: Program PDA that holds mint, freeze and update authority over badge NFTs
+    #[account(
+        seeds = [b"badge_authority"],
+        bump
+    )]
+    pub badge_authority: UncheckedAccount<'info>,
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
 
     /// CHECK: Metaplex Token Metadata Program
     #[account(address = METADATA_PROGRAM_ID)]
@@ -83,9 +100,9 @@ pub fn handler(ctx: Context<AutoAwardBadge>, badge_type: BadgeType) -> Result<()
         BadgeType::TenRedemptions => user_stats.total_redemptions >= 10,
         BadgeType::FiftyRedemptions => user_stats.total_redemptions >= 50,
         BadgeType::TopReviewer => user_stats.total_ratings_given >= 20,
-        BadgeType::EarlyAdopter => true, // Manually awarded
-        BadgeType::MerchantPartner => true, // Manually awarded
-        BadgeType::CommunityModerator => true, // Manually awarded
+        BadgeType::SavedOneSol => user_stats.total_savings >= UserStats::SAVED_ONE_SOL_LAMPORTS,
+        // Manual badges go through mint_badge, signed by the marketplace authority
+        BadgeType::EarlyAdopter | BadgeType::MerchantPartner | BadgeType::CommunityModerator => false,
     };
 
     require!(qualifies, CouponError::InvalidInput);
@@ -97,26 +114,30 @@ pub fn handler(ctx: Context<AutoAwardBadge>, badge_type: BadgeType) -> Result<()
     badge.metadata = ctx.accounts.metadata.key();
     badge.earned_at = Clock::get()?.unix_timestamp;
     
-    let badge_name = match badge_type {
-        BadgeType::FirstPurchase => "First Purchase Badge",
-        BadgeType::TenRedemptions => "10 Redemptions Badge",
-        BadgeType::FiftyRedemptions => "50 Redemptions Badge",
-        BadgeType::TopReviewer => "Top Reviewer Badge",
-        BadgeType::EarlyAdopter => "Early Adopter Badge",
-        BadgeType::MerchantPartner => "Merchant Partner Badge",
-        BadgeType::CommunityModerator => "Community Moderator Badge",
-    };
+    let badge_name = badge_type.display_name();
     
     badge.metadata_uri = format!("https://api.dealdiscovery.com/badges/{}.json", badge_type as u8);
 
-    // Create Metaplex metadata
+    // Mint and freeze the badge, then create Metaplex metadata, all signed
+    // by the program's badge authority
+    let authority_seeds: &[&[u8]] = &[b"badge_authority", &[ctx.bumps.badge_authority]];
+    let authority_info = ctx.accounts.badge_authority.to_account_info();
+
+    mint_soulbound_badge(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.token_account.to_account_info(),
+        &authority_info,
+        &[authority_seeds],
+    )?;
+
     CreateV1CpiBuilder::new(&ctx.accounts.token_metadata_program.to_account_info())
         .metadata(&ctx.accounts.metadata.to_account_info())
         .master_edition(Some(&ctx.accounts.master_edition.to_account_info()))
         .mint(&ctx.accounts.mint.to_account_info(), true)
-        .authority(&ctx.accounts.authority.to_account_info())
+        .authority(&authority_info)
         .payer(&ctx.accounts.payer.to_account_info())
-        .update_authority(&ctx.accounts.authority.to_account_info(), true)
+        .update_authority(&authority_info, true)
         .system_program(&ctx.accounts.system_program.to_account_info())
         .sysvar_instructions(&ctx.accounts.sysvar_instructions.to_account_info())
         .spl_token_program(Some(&ctx.accounts.token_program.to_account_info()))
@@ -126,21 +147,13 @@ pub fn handler(ctx: Context<AutoAwardBadge>, badge_type: BadgeType) -> Result<()
         .seller_fee_basis_points(0)
         .token_standard(TokenStandard::NonFungible)
         .print_supply(PrintSupply::Zero)
-        .invoke()?;
+        .invoke_signed(&[authority_seeds])?;
 
     // Update user stats
     user_stats.add_badge(badge_type as u8);
     
     // Award reputation points
-    let reputation_points = match badge_type {
-        BadgeType::FirstPurchase => 10,
-        BadgeType::TenRedemptions => 50,
-        BadgeType::FiftyRedemptions => 200,
-        BadgeType::TopReviewer => 100,
-        BadgeType::EarlyAdopter => 500,
-        BadgeType::MerchantPartner => 300,
-        BadgeType::CommunityModerator => 1000,
-    };
+    let reputation_points = badge_type.reputation_points();
     user_stats.add_reputation(reputation_points);
 
     emit!(BadgeEarned {
@@ -1,10 +1,17 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program::{transfer, Transfer};
-use crate::state::{Coupon, Listing, Marketplace};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Token, TokenAccount, Mint, transfer, Transfer, close_account, CloseAccount};
+use anchor_spl::token_interface::{
+    Mint as PaymentMint,
+    TokenAccount as PaymentTokenAccount,
+    TokenInterface,
+};
+use crate::state::{Coupon, Promotion, Merchant, RevenueConfig, Listing, Marketplace};
 use crate::errors::CouponError;
 use crate::events::CouponSold;
+use crate::utils::{PaymentRail, ResaleRoyalty};
 
-pub fn handler(ctx: Context<BuyListedCoupon>) -> Result<()> {
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, BuyListedCoupon<'info>>) -> Result<()> {
     let listing = &mut ctx.accounts.listing;
     require!(listing.is_active, CouponError::ListingInactive);
 
@@ -12,33 +19,43 @@ pub fn handler(ctx: Context<BuyListedCoupon>) -> Result<()> {
     require!(!coupon.is_redeemed, CouponError::CouponAlreadyRedeemed);
     require!(coupon.expiry_timestamp > Clock::get()?.unix_timestamp, CouponError::CouponExpired);
 
-    let marketplace = &ctx.accounts.marketplace;
-    let marketplace_fee = (listing.price * marketplace.fee_basis_points as u64) / 10000;
-    let seller_amount = listing.price - marketplace_fee;
+    let marketplace = &mut ctx.accounts.marketplace;
+    let marketplace_fee = marketplace.calculate_fee(listing.price);
 
-    // Transfer payment to seller
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.buyer.to_account_info(),
-                to: ctx.accounts.seller.to_account_info(),
-            },
-        ),
-        seller_amount,
-    )?;
+    let royalty = ResaleRoyalty {
+        merchant: &ctx.accounts.merchant,
+        merchant_authority: ctx.accounts.merchant_authority.to_account_info(),
+        merchant_payment_account: &ctx.accounts.merchant_payment_account,
+        revenue_config: ctx.accounts.revenue_config.as_deref(),
+        recipient_accounts: ctx.remaining_accounts,
+        promotion: ctx.accounts.promotion.key(),
+        basis_points: listing.royalty_basis_points,
+    };
+    let royalty_amount = royalty.calculate(listing.price);
+    let seller_amount = listing.price - marketplace_fee - royalty_amount;
 
-    // Transfer marketplace fee
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.buyer.to_account_info(),
-                to: ctx.accounts.marketplace_authority.to_account_info(),
-            },
-        ),
-        marketplace_fee,
+    let rail = PaymentRail::resolve(
+        listing.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
     )?;
+    let buyer_info = ctx.accounts.buyer.to_account_info();
+    let from = rail.endpoint(&buyer_info, &ctx.accounts.buyer_payment_account)?;
+    let seller_to = rail.endpoint(&ctx.accounts.seller.to_account_info(), &ctx.accounts.seller_payment_account)?;
+    let treasury_to = rail.endpoint(&ctx.accounts.treasury.to_account_info(), &ctx.accounts.treasury_payment_account)?;
+
+    // Transfer payment to seller
+    rail.transfer(&from, &seller_to, &buyer_info, seller_amount, &[])?;
+
+    // Transfer marketplace fee to the treasury
+    rail.transfer(&from, &treasury_to, &buyer_info, marketplace_fee, &[])?;
+    if !rail.is_token() {
+        marketplace.record_fee(marketplace_fee);
+    }
+
+    // Merchant royalty, through its revenue split when it has one
+    royalty.pay(&rail, &from, &buyer_info, listing.price, &[])?;
 
     emit!(CouponSold {
         listing: listing.key(),
@@ -48,10 +65,41 @@ pub fn handler(ctx: Context<BuyListedCoupon>) -> Result<()> {
         buyer: ctx.accounts.buyer.key(),
         price: listing.price,
         marketplace_fee,
+        royalty: royalty_amount,
+        payment_mint: listing.payment_mint,
     });
 
-    // Update coupon owner and deactivate listing
+    // Deliver the escrowed NFT to the buyer and close the vault
+    let nft_mint_key = ctx.accounts.nft_mint.key();
+    let vault_seeds = &[
+        b"listing_vault",
+        nft_mint_key.as_ref(),
+        &[ctx.bumps.listing_vault],
+    ];
+    let vault_signer = &[&vault_seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.listing_vault.to_account_info(),
+        to: ctx.accounts.buyer_token_account.to_account_info(),
+        authority: ctx.accounts.listing_vault.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, vault_signer);
+    transfer(cpi_ctx, 1)?;
+
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.listing_vault.to_account_info(),
+        destination: ctx.accounts.seller.to_account_info(),
+        authority: ctx.accounts.listing_vault.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, vault_signer);
+    close_account(cpi_ctx)?;
+
+    // Hand the coupon to the buyer; the listing account is closed to the seller
     coupon.owner = ctx.accounts.buyer.key();
+    coupon.is_escrowed = false;
+    ctx.accounts.promotion.close_escrow();
     listing.is_active = false;
 
     Ok(())
@@ -61,6 +109,7 @@ pub fn handler(ctx: Context<BuyListedCoupon>) -> Result<()> {
 pub struct BuyListedCoupon<'info> {
     #[account(
         mut,
+        close = seller,
         constraint = listing.is_active @ CouponError::ListingInactive
     )]
     pub listing: Account<'info, Listing>,
@@ -69,6 +118,34 @@ pub struct BuyListedCoupon<'info> {
         constraint = coupon.key() == listing.coupon @ CouponError::WrongCoupon
     )]
     pub coupon: Account<'info, Coupon>,
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Box<Account<'info, Promotion>>,
+    #[account(
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+    /// CHECK: Merchant receives the royalty when no revenue split is configured
+    #[account(
+        mut,
+        constraint = merchant.authority == merchant_authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant_authority: UncheckedAccount<'info>,
+    /// Required when the merchant has a revenue split; recipient endpoints
+    /// follow in remaining accounts
+    #[account(
+        seeds = [b"revenue_config", merchant.key().as_ref()],
+        bump
+    )]
+    pub revenue_config: Option<Account<'info, RevenueConfig>>,
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = !marketplace.is_paused @ CouponError::MarketplacePaused
+    )]
     pub marketplace: Account<'info, Marketplace>,
     #[account(
         mut,
@@ -78,11 +155,49 @@ pub struct BuyListedCoupon<'info> {
     pub seller: UncheckedAccount<'info>,
     #[account(mut)]
     pub buyer: Signer<'info>,
+    /// CHECK: Fee treasury PDA
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Listing vault token account (PDA)
     #[account(
         mut,
-        constraint = marketplace.authority == marketplace_authority.key() @ CouponError::NotMarketplaceAuthority
+        seeds = [b"listing_vault", nft_mint.key().as_ref()],
+        bump
     )]
-    /// CHECK: Marketplace authority for fee collection
-    pub marketplace_authority: UncheckedAccount<'info>,
+    pub listing_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+
+    // SPL payment accounts, only required when the listing has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, PaymentMint>>,
+    #[account(mut)]
+    pub buyer_payment_account: Option<InterfaceAccount<'info, PaymentTokenAccount>>,
+    #[account(mut)]
+    pub seller_payment_account: Option<InterfaceAccount<'info, PaymentTokenAccount>>,
+    #[account(mut)]
+    pub merchant_payment_account: Option<InterfaceAccount<'info, PaymentTokenAccount>>,
+    #[account(mut)]
+    pub treasury_payment_account: Option<InterfaceAccount<'info, PaymentTokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
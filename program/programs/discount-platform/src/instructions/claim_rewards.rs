@@ -1,9 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer as system_transfer, Transfer as SystemTransfer};
-use anchor_spl::token::{Token, TokenAccount, Mint, transfer, Transfer};
-use crate::state::{StakingPool, StakeAccount};
+use anchor_spl::token::{Token, TokenAccount, Mint, transfer, Transfer, close_account, CloseAccount};
+use crate::state::{Coupon, Promotion, StakingPool, StakeAccount, CouponMetadataState};
 use crate::errors::CouponError;
 use crate::events::RewardsClaimed;
+use crate::utils::CouponMetadataCpi;
 
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
@@ -23,12 +24,36 @@ pub struct ClaimRewards<'info> {
     )]
     pub staking_pool: Account<'info, StakingPool>,
 
+    #[account(
+        mut,
+        constraint = coupon.key() == stake_account.coupon @ CouponError::WrongCoupon
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Account<'info, Promotion>,
+
     /// CHECK: NFT Mint
     #[account(
         constraint = nft_mint.key() == stake_account.nft_mint
     )]
     pub nft_mint: Account<'info, Mint>,
 
+    /// CHECK: Metadata account, validated by the Token Metadata program.
+    /// Optional: only needed to refresh the NFT's wallet-facing state.
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Program PDA that holds update authority over coupon NFTs
+    #[account(
+        seeds = [b"coupon_authority"],
+        bump
+    )]
+    pub coupon_authority: Option<UncheckedAccount<'info>>,
+
     /// CHECK: Staking vault token account (PDA)
     #[account(
         mut,
@@ -56,6 +81,15 @@ pub struct ClaimRewards<'info> {
     pub reward_pool: SystemAccount<'info>,
 
     pub token_program: Program<'info, Token>,
+
+    /// CHECK: Metaplex Token Metadata Program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Sysvar Instructions
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -95,6 +129,38 @@ pub fn handler(ctx: Context<ClaimRewards>) -> Result<()> {
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, vault_signer);
     transfer(cpi_ctx, 1)?;
+    ctx.accounts.coupon.is_escrowed = false;
+    ctx.accounts.promotion.close_escrow();
+
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.stake_vault.to_account_info(),
+        destination: ctx.accounts.user.to_account_info(),
+        authority: ctx.accounts.stake_vault.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, vault_signer);
+    close_account(cpi_ctx)?;
+
+    // Back in the user's wallet, show the coupon as active again. The NFT is
+    // already returned, so a caller without the metadata accounts can skip this.
+    if let (Some(token_metadata_program), Some(metadata), Some(coupon_authority), Some(sysvar_instructions)) = (
+        &ctx.accounts.token_metadata_program,
+        &ctx.accounts.metadata,
+        &ctx.accounts.coupon_authority,
+        &ctx.accounts.sysvar_instructions,
+    ) {
+        let metadata_cpi = CouponMetadataCpi {
+            token_metadata_program: token_metadata_program.to_account_info(),
+            metadata: metadata.to_account_info(),
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            coupon_authority: coupon_authority.to_account_info(),
+            coupon_authority_bump: ctx.bumps.coupon_authority.ok_or(CouponError::InvalidInput)?,
+            payer: ctx.accounts.user.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            sysvar_instructions: sysvar_instructions.to_account_info(),
+        };
+        metadata_cpi.set_state(&mut ctx.accounts.coupon, &ctx.accounts.promotion, CouponMetadataState::Active)?;
+    }
 
     // Transfer rewards (SOL) to user
     let cpi_accounts = SystemTransfer {
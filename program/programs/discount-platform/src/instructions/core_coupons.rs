@@ -0,0 +1,370 @@
+// src/instructions/core_coupons.rs
+use anchor_lang::prelude::*;
+use crate::state::{
+    Coupon,
+    Promotion,
+    Merchant,
+    MerchantStaff,
+    StaffRole,
+    Marketplace,
+    UserStats,
+    BadgeType,
+    ReputationTier,
+    CouponMetadataState,
+    IssuanceMode,
+};
+use crate::errors::CouponError;
+use crate::utils::{MPL_CORE_ID, core_asset_owner, CoreAssetCpi, burn_core_asset};
+use crate::events::{CouponMinted, CouponRedeemed, CouponPartiallyRedeemed};
+
+// ============================================================================
+// Mint Core Coupon (one Metaplex Core asset instead of mint + ATA + metadata)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct MintCoreCoupon<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Coupon::INIT_SPACE,
+        seeds = [b"coupon", promotion.key().as_ref(), &promotion.current_supply.to_le_bytes()],
+        bump
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    /// New Core asset keypair; the account is created by the Core program
+    #[account(mut)]
+    pub asset: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant,
+        constraint = promotion.issuance_mode == IssuanceMode::Core @ CouponError::WrongIssuanceMode
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    #[account(
+        mut,
+        constraint = merchant.is_active @ CouponError::MerchantInactive
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = !marketplace.is_paused @ CouponError::MarketplacePaused
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    /// Present when a staff key rather than the merchant authority signs
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub staff: Option<Account<'info, MerchantStaff>>,
+
+    /// CHECK: Program PDA set as the Core asset's update authority
+    #[account(
+        seeds = [b"coupon_authority"],
+        bump
+    )]
+    pub coupon_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Owner of the new asset
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", recipient.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Merchant authority or a staff key allowed to manage promotions
+    pub authority: Signer<'info>,
+
+    /// CHECK: Metaplex Core program
+    #[account(address = MPL_CORE_ID)]
+    pub mpl_core_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn mint_core_coupon(ctx: Context<MintCoreCoupon>, coupon_id: u64) -> Result<()> {
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.staff.as_deref(),
+        StaffRole::can_manage_promotions,
+    )?;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let promotion = &mut ctx.accounts.promotion;
+    require!(promotion.is_active, CouponError::PromotionInactive);
+    require!(promotion.current_supply < promotion.max_supply, CouponError::SupplyExhausted);
+    require!(promotion.expiry_timestamp > current_time, CouponError::PromotionExpired);
+
+    // Initialize UserStats if first time
+    let user_stats = &mut ctx.accounts.user_stats;
+    if user_stats.user == Pubkey::default() {
+        user_stats.user = ctx.accounts.recipient.key();
+        user_stats.total_purchases = 0;
+        user_stats.total_redemptions = 0;
+        user_stats.total_ratings_given = 0;
+        user_stats.total_comments = 0;
+        user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
+        user_stats.reputation_score = 0;
+        user_stats.tier = ReputationTier::Bronze;
+        user_stats.badges_earned = Vec::new();
+        user_stats.joined_at = current_time;
+        user_stats.last_activity = current_time;
+    }
+
+    user_stats.total_purchases += 1;
+    user_stats.add_reputation(5); // 5 points per purchase
+    user_stats.last_activity = current_time;
+
+    if user_stats.total_purchases == 1 && !user_stats.has_badge(BadgeType::FirstPurchase as u8) {
+        msg!("🏆 User eligible for FirstPurchase badge! Total purchases: {}", user_stats.total_purchases);
+    }
+
+    let coupon = &mut ctx.accounts.coupon;
+    coupon.id = coupon_id;
+    coupon.promotion = promotion.key();
+    coupon.owner = ctx.accounts.recipient.key();
+    coupon.merchant = ctx.accounts.merchant.key();
+    coupon.discount_percentage = promotion.discount_percentage;
+    coupon.expiry_timestamp = promotion.expiry_timestamp;
+    coupon.is_redeemed = false;
+    coupon.redeemed_at = 0;
+    coupon.fill(promotion.redemption_model);
+    coupon.created_at = current_time;
+    coupon.mint = Some(ctx.accounts.asset.key());
+    coupon.is_escrowed = false;
+    coupon.serial_number = promotion.current_supply + 1;
+    coupon.metadata_uri = promotion.coupon_uri(coupon.serial_number, CouponMetadataState::Active);
+
+    let authority_seeds: &[&[u8]] = &[b"coupon_authority", &[ctx.bumps.coupon_authority]];
+    CoreAssetCpi {
+        core_program: ctx.accounts.mpl_core_program.to_account_info(),
+        asset: ctx.accounts.asset.to_account_info(),
+        authority: ctx.accounts.coupon_authority.to_account_info(),
+        payer: ctx.accounts.payer.to_account_info(),
+        owner: ctx.accounts.recipient.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    }
+    .create(promotion.nft_name.clone(), coupon.metadata_uri.clone(), &[authority_seeds])?;
+
+    promotion.current_supply += 1;
+    ctx.accounts.merchant.total_coupons_created += 1;
+    ctx.accounts.marketplace.total_coupons += 1;
+
+    emit!(CouponMinted {
+        coupon: coupon.key(),
+        nft_mint: ctx.accounts.asset.key(),
+        promotion: promotion.key(),
+        recipient: coupon.owner,
+        merchant: coupon.merchant,
+        discount_percentage: coupon.discount_percentage,
+        merchant_verification: ctx.accounts.merchant.verification_status(current_time),
+    });
+
+    msg!("✅ Core coupon minted: #{} of {}", coupon.serial_number, promotion.max_supply);
+
+    Ok(())
+}
+
+// ============================================================================
+// Redeem Core Coupon (owner checked on the asset, burned through Core)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct RedeemCoreCoupon<'info> {
+    #[account(
+        mut,
+        constraint = coupon.merchant == merchant.key() @ CouponError::WrongMerchant,
+        constraint = coupon.mint == Some(asset.key()) @ CouponError::WrongCoupon,
+        constraint = !coupon.is_escrowed @ CouponError::CouponEscrowed
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    /// CHECK: Core asset behind the coupon; ownership is read from its data
+    #[account(mut)]
+    pub asset: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = merchant.is_active @ CouponError::MerchantInactive
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    /// Present when a staff key rather than the merchant authority co-signs
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), merchant_authority.key().as_ref()],
+        bump
+    )]
+    pub staff: Option<Account<'info, MerchantStaff>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Merchant authority or a staff key with an active entry
+    pub merchant_authority: Signer<'info>,
+
+    /// CHECK: Metaplex Core program
+    #[account(address = MPL_CORE_ID)]
+    pub mpl_core_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn redeem_core_coupon(ctx: Context<RedeemCoreCoupon>, amount: u64, order_amount: u64) -> Result<()> {
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.merchant_authority.key(),
+        ctx.accounts.staff.as_deref(),
+        StaffRole::can_redeem,
+    )?;
+
+    // Core assets can change hands outside this program, so the asset is
+    // the source of truth for ownership
+    let owner = core_asset_owner(&ctx.accounts.asset.to_account_info())?;
+    require_keys_eq!(owner, ctx.accounts.user.key(), CouponError::NotCouponOwner);
+
+    let current_time = Clock::get()?.unix_timestamp;
+    let coupon = &mut ctx.accounts.coupon;
+    require!(!coupon.is_redeemed, CouponError::CouponAlreadyRedeemed);
+    require!(coupon.expiry_timestamp > current_time, CouponError::CouponExpired);
+    // Location-based promotions go through redemption tickets, which record where the holder is
+    require!(!ctx.accounts.promotion.is_location_based, CouponError::LocationNotSupported);
+
+    // Initialize UserStats if first time
+    let user_stats = &mut ctx.accounts.user_stats;
+    if user_stats.user == Pubkey::default() {
+        user_stats.user = ctx.accounts.user.key();
+        user_stats.total_purchases = 0;
+        user_stats.total_redemptions = 0;
+        user_stats.total_ratings_given = 0;
+        user_stats.total_comments = 0;
+        user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
+        user_stats.reputation_score = 0;
+        user_stats.tier = ReputationTier::Bronze;
+        user_stats.badges_earned = Vec::new();
+        user_stats.joined_at = current_time;
+        user_stats.last_activity = current_time;
+    }
+
+    user_stats.total_redemptions += 1;
+    user_stats.add_reputation(10); // 10 points per redemption
+    user_stats.last_activity = current_time;
+
+    if user_stats.total_redemptions == 10 && !user_stats.has_badge(BadgeType::TenRedemptions as u8) {
+        msg!("🏆 User eligible for TenRedemptions badge! Total redemptions: {}", user_stats.total_redemptions);
+    }
+
+    if user_stats.total_redemptions == 50 && !user_stats.has_badge(BadgeType::FiftyRedemptions as u8) {
+        msg!("🏆 User eligible for FiftyRedemptions badge! Total redemptions: {}", user_stats.total_redemptions);
+    }
+
+    coupon.owner = owner;
+
+    // Lifetime savings are kept in lamports, so only SOL-priced promotions count
+    let promotion = &ctx.accounts.promotion;
+    let savings = promotion.savings(coupon.discount_percentage, order_amount);
+    if promotion.payment_mint.is_none() {
+        let merchant = &mut ctx.accounts.merchant;
+        merchant.total_savings_given = merchant.total_savings_given.saturating_add(savings);
+        if user_stats.add_savings(savings) && !user_stats.has_badge(BadgeType::SavedOneSol as u8) {
+            msg!("🏆 User eligible for SavedOneSol badge! Total saved: {} lamports", user_stats.total_savings);
+        }
+    }
+
+    // Multi-use and stored-value coupons stay with the holder until exhausted
+    if !coupon.consume(amount)? {
+        emit!(CouponPartiallyRedeemed {
+            coupon: coupon.key(),
+            nft_mint: ctx.accounts.asset.key(),
+            user: owner,
+            merchant: coupon.merchant,
+            amount,
+            uses_remaining: coupon.uses_remaining,
+            balance_remaining: coupon.balance_remaining,
+            approved_by: ctx.accounts.merchant_authority.key(),
+            order_amount,
+            savings,
+            payment_mint: promotion.payment_mint,
+            timestamp: current_time,
+        });
+
+        msg!("✅ Core coupon partially redeemed: {} uses / {} balance left",
+            coupon.uses_remaining, coupon.balance_remaining);
+
+        return Ok(());
+    }
+
+    coupon.is_redeemed = true;
+    coupon.redeemed_at = current_time;
+    ctx.accounts.merchant.total_coupons_redeemed += 1;
+
+    burn_core_asset(
+        &ctx.accounts.mpl_core_program.to_account_info(),
+        &ctx.accounts.asset.to_account_info(),
+        &ctx.accounts.user.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    emit!(CouponRedeemed {
+        coupon: coupon.key(),
+        nft_mint: ctx.accounts.asset.key(),
+        user: owner,
+        merchant: coupon.merchant,
+        discount_percentage: coupon.discount_percentage,
+        redemption_code: format!("REDEEMED-{}", coupon.id),
+        approved_by: ctx.accounts.merchant_authority.key(),
+        order_amount,
+        savings,
+        payment_mint: ctx.accounts.promotion.payment_mint,
+        timestamp: current_time,
+    });
+
+    msg!("✅ Core coupon redeemed! Reputation: {} | Tier: {:?}",
+        user_stats.reputation_score, user_stats.tier);
+
+    Ok(())
+}
+
+// ============================================================================
+// Handlers for lib.rs
+// ============================================================================
+
+pub fn handler_mint_core_coupon(ctx: Context<MintCoreCoupon>, coupon_id: u64) -> Result<()> {
+    mint_core_coupon(ctx, coupon_id)
+}
+
+pub fn handler_redeem_core_coupon(
+    ctx: Context<RedeemCoreCoupon>,
+    amount: u64,
+    order_amount: u64,
+) -> Result<()> {
+    redeem_core_coupon(ctx, amount, order_amount)
+}
@@ -1,6 +1,6 @@
 // src/instructions/create_promotion.rs
 use anchor_lang::prelude::*;
-use crate::state::{Promotion, Merchant};
+use crate::state::{Promotion, Merchant, MerchantStaff, StaffRole, Marketplace, IssuanceMode, RedemptionTicket, CouponNftConfig, GeofenceConfig, GeoCell, Location, validate_coordinates, RedemptionModel};
 use crate::errors::CouponError;
 use crate::events::PromotionCreated;
 
@@ -15,8 +15,24 @@ pub struct CreateCouponPromotion<'info> {
     )]
     pub promotion: Account<'info, Promotion>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = merchant.is_active @ CouponError::MerchantInactive
+    )]
     pub merchant: Account<'info, Merchant>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    /// Present when a manager-level staff key signs instead of the merchant authority
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub staff: Option<Account<'info, MerchantStaff>>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -31,12 +47,29 @@ pub fn handler(
     category: String,
     description: String,
     price: u64,
+    payment_mint: Option<Pubkey>,
+    nft: CouponNftConfig,
+    issuance_mode: IssuanceMode,
+    geofence: Option<GeofenceConfig>,
+    redemption_model: RedemptionModel,
+    transferable: bool,
+    resale_allowed: bool,
 ) -> Result<()> {
     require!(discount_percentage > 0 && discount_percentage <= 100, CouponError::InvalidDiscount);
     require!(max_supply > 0, CouponError::InvalidSupply);
     require!(expiry_timestamp > Clock::get()?.unix_timestamp, CouponError::InvalidExpiry);
     require!(category.len() <= 30, CouponError::CategoryTooLong);
     require!(description.len() <= 200, CouponError::DescriptionTooLong);
+    nft.validate()?;
+    redemption_model.validate()?;
+    // A coupon that can't change hands can't be resold either
+    require!(transferable || !resale_allowed, CouponError::CouponNotTransferable);
+    ctx.accounts.marketplace.require_payment_mint(payment_mint)?;
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.staff.as_deref(),
+        StaffRole::can_manage_promotions,
+    )?;
 
     let promotion = &mut ctx.accounts.promotion;
     promotion.merchant = ctx.accounts.merchant.key();
@@ -47,20 +80,43 @@ pub fn handler(
     promotion.category = category;
     promotion.description = description;
     promotion.price = price;
+    promotion.payment_mint = payment_mint;
     promotion.is_active = true;
     promotion.created_at = Clock::get()?.unix_timestamp;
     
-    // Initialize location data (default to no location)
-    promotion.location = crate::state::Location {
-        latitude: 0,
-        longitude: 0,
-        region_code: 0,
-        country_code: 0,
-        city_hash: 0,
-    };
-    promotion.geo_cell_id = 0;
-    promotion.radius_meters = 0;
-    promotion.is_location_based = false;
+    // Location-based promotions can only be redeemed near the merchant
+    if let Some(fence) = geofence {
+        validate_coordinates(fence.latitude, fence.longitude)?;
+        require!(
+            fence.radius_meters > 0 && fence.radius_meters <= Promotion::MAX_GEOFENCE_RADIUS_METERS,
+            CouponError::InvalidGeofenceRadius
+        );
+
+        let (cell_lat, cell_lon) = GeoCell::from_coords(fence.latitude, fence.longitude);
+        promotion.location = Location::from_coords(fence.latitude, fence.longitude);
+        promotion.geo_cell_id = GeoCell::to_cell_id(cell_lat, cell_lon);
+        promotion.radius_meters = fence.radius_meters;
+        promotion.is_location_based = true;
+    } else {
+        promotion.location = Location::from_microdegrees(0, 0);
+        promotion.geo_cell_id = 0;
+        promotion.radius_meters = 0;
+        promotion.is_location_based = false;
+    }
+
+    promotion.metadata_base_uri = nft.metadata_base_uri;
+    promotion.nft_name = nft.name;
+    promotion.nft_symbol = nft.symbol;
+    promotion.issuance_mode = issuance_mode;
+    promotion.transferable = transferable;
+    promotion.resale_allowed = resale_allowed;
+    promotion.royalty_basis_points = 0;
+    promotion.max_resale_bps = None;
+    promotion.ticket_validity_seconds = RedemptionTicket::DEFAULT_VALIDITY_SECONDS;
+    promotion.redemption_model = redemption_model;
+    promotion.max_discount_cap = None;
+    promotion.open_escrows = 0;
+    promotion.require_enforceable_terms()?;
 
     emit!(PromotionCreated {
         promotion: promotion.key(),
@@ -69,6 +125,10 @@ pub fn handler(
         max_supply,
         expiry_timestamp,
         price,
+        payment_mint,
+        issuance_mode,
+        redemption_model,
+        merchant_verification: ctx.accounts.merchant.verification_status(promotion.created_at),
     });
 
     Ok(())
@@ -0,0 +1,71 @@
+// src/instructions/expire_coupon.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::{Coupon, Promotion, CouponMetadataState};
+use crate::errors::CouponError;
+use crate::utils::CouponMetadataCpi;
+
+/// Permissionless crank: once a coupon is past its expiry, anyone can flip
+/// its NFT metadata to the expired URI so wallets stop showing it as usable.
+#[derive(Accounts)]
+pub struct ExpireCoupon<'info> {
+    #[account(
+        mut,
+        constraint = !coupon.is_redeemed @ CouponError::CouponAlreadyRedeemed,
+        constraint = coupon.expiry_timestamp <= Clock::get()?.unix_timestamp @ CouponError::CouponNotExpired
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Metadata account, validated by the Token Metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Program PDA that holds update authority over coupon NFTs
+    #[account(
+        seeds = [b"coupon_authority"],
+        bump
+    )]
+    pub coupon_authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Metaplex Token Metadata Program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: Sysvar Instructions
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ExpireCoupon>) -> Result<()> {
+    let metadata_cpi = CouponMetadataCpi {
+        token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        mint: ctx.accounts.nft_mint.to_account_info(),
+        coupon_authority: ctx.accounts.coupon_authority.to_account_info(),
+        coupon_authority_bump: ctx.bumps.coupon_authority,
+        payer: ctx.accounts.payer.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+    };
+    metadata_cpi.set_state(&mut ctx.accounts.coupon, &ctx.accounts.promotion, CouponMetadataState::Expired)?;
+
+    msg!("✅ Coupon {} marked expired", ctx.accounts.coupon.id);
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program::{transfer, Transfer};
-use anchor_spl::token::{Token, TokenAccount, Mint};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::{
     GroupDeal, 
     GroupParticipant, 
@@ -9,11 +9,13 @@ use crate::state::{
     Merchant,
     Coupon,
     Marketplace,
+    RevenueConfig,
     UserStats,
     BadgeType,
     ReputationTier,
 };
 use crate::errors::CouponError;
+use crate::utils::{PaymentRail, MerchantRevenue};
 use crate::events::{GroupDealCreated, GroupDealJoined, GroupDealFinalized, GroupDealRefunded};
 
 // ============================================================================
@@ -43,7 +45,8 @@ pub struct CreateGroupDeal<'info> {
     pub promotion: Account<'info, Promotion>,
     
     #[account(
-        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant,
+        constraint = merchant.is_active @ CouponError::MerchantInactive
     )]
     pub merchant: Account<'info, Merchant>,
     
@@ -110,6 +113,7 @@ pub fn create_group_deal(
     group_deal.created_at = clock.unix_timestamp;
     group_deal.finalized_at = 0;
     group_deal.total_escrowed = 0;
+    group_deal.payment_mint = promotion.payment_mint;
     
     emit!(GroupDealCreated {
         group_deal: group_deal.key(),
@@ -119,11 +123,12 @@ pub fn create_group_deal(
         target_participants,
         base_price,
         deadline: group_deal.deadline,
+        payment_mint: group_deal.payment_mint,
     });
     
     msg!("✅ Group deal created!");
     msg!("✅ Target: {} participants", target_participants);
-    msg!("✅ Base price: {} lamports", base_price);
+    msg!("✅ Base price: {}", base_price);
     msg!("✅ Deadline: {}", group_deal.deadline);
     
     Ok(())
@@ -171,6 +176,22 @@ pub struct JoinGroupDeal<'info> {
     
     #[account(mut)]
     pub user: Signer<'info>,
+
+    // SPL payment accounts, only required when the deal has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub user_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = payment_mint,
+        associated_token::authority = escrow_vault,
+        associated_token::token_program = payment_token_program
+    )]
+    pub escrow_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
@@ -189,16 +210,16 @@ pub fn join_group_deal(ctx: Context<JoinGroupDeal>) -> Result<()> {
     let current_price = group_deal.get_current_price();
     
     // Escrow payment to vault
-    transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.user.to_account_info(),
-                to: ctx.accounts.escrow_vault.to_account_info(),
-            },
-        ),
-        current_price,
+    let rail = PaymentRail::resolve(
+        group_deal.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
     )?;
+    let user_info = ctx.accounts.user.to_account_info();
+    let from = rail.endpoint(&user_info, &ctx.accounts.user_payment_account)?;
+    let to = rail.endpoint(&ctx.accounts.escrow_vault.to_account_info(), &ctx.accounts.escrow_payment_account)?;
+    rail.transfer(&from, &to, &user_info, current_price, &[])?;
     
     // Initialize participant
     participant.group_deal = group_deal.key();
@@ -221,6 +242,7 @@ pub fn join_group_deal(ctx: Context<JoinGroupDeal>) -> Result<()> {
         user_stats.total_ratings_given = 0;
         user_stats.total_comments = 0;
         user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
         user_stats.reputation_score = 0;
         user_stats.tier = ReputationTier::Bronze;
         user_stats.badges_earned = Vec::new();
@@ -241,7 +263,7 @@ pub fn join_group_deal(ctx: Context<JoinGroupDeal>) -> Result<()> {
     msg!("✅ Joined group deal!");
     msg!("✅ Participants: {}/{}", group_deal.current_participants, group_deal.target_participants);
     msg!("✅ Current discount: {}%", group_deal.get_current_discount());
-    msg!("✅ Price paid: {} lamports", current_price);
+    msg!("✅ Price paid: {}", current_price);
     
     if group_deal.is_target_reached() {
         msg!("🎉 Target reached! Deal can be finalized.");
@@ -265,10 +287,18 @@ pub struct FinalizeGroupDeal<'info> {
     #[account(mut)]
     pub promotion: Account<'info, Promotion>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = merchant.is_active @ CouponError::MerchantInactive
+    )]
     pub merchant: Account<'info, Merchant>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = !marketplace.is_paused @ CouponError::MarketplacePaused
+    )]
     pub marketplace: Account<'info, Marketplace>,
     
     /// CHECK: Escrow vault
@@ -279,18 +309,47 @@ pub struct FinalizeGroupDeal<'info> {
     )]
     pub escrow_vault: UncheckedAccount<'info>,
     
-    /// CHECK: Merchant receives payment
+    /// CHECK: Merchant receives payment when no revenue split is configured
     #[account(
         mut,
         constraint = merchant.authority == merchant_authority.key() @ CouponError::NotMerchantAuthority
     )]
     pub merchant_authority: UncheckedAccount<'info>,
+
+    /// Required when the merchant has a revenue split; recipient endpoints
+    /// (wallets for SOL, token accounts otherwise) follow in remaining accounts
+    #[account(
+        seeds = [b"revenue_config", merchant.key().as_ref()],
+        bump
+    )]
+    pub revenue_config: Option<Account<'info, RevenueConfig>>,
+    
+    /// CHECK: Fee treasury PDA
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
     
     pub authority: Signer<'info>,
+
+    // SPL payment accounts, only required when the deal has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub escrow_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub merchant_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub treasury_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn finalize_group_deal(ctx: Context<FinalizeGroupDeal>) -> Result<()> {
+pub fn finalize_group_deal<'info>(
+    ctx: Context<'_, '_, '_, 'info, FinalizeGroupDeal<'info>>,
+) -> Result<()> {
     let group_deal = &mut ctx.accounts.group_deal;
     let clock = Clock::get()?;
     
@@ -300,8 +359,8 @@ pub fn finalize_group_deal(ctx: Context<FinalizeGroupDeal>) -> Result<()> {
     );
     
     // Calculate payments
-    let marketplace = &ctx.accounts.marketplace;
-    let marketplace_fee = (group_deal.total_escrowed * marketplace.fee_basis_points as u64) / 10000;
+    let marketplace = &mut ctx.accounts.marketplace;
+    let marketplace_fee = marketplace.calculate_fee(group_deal.total_escrowed);
     let merchant_payment = group_deal.total_escrowed - marketplace_fee;
     
     // Transfer to merchant - Store group_deal key before creating seeds
@@ -312,18 +371,35 @@ pub fn finalize_group_deal(ctx: Context<FinalizeGroupDeal>) -> Result<()> {
         &[ctx.bumps.escrow_vault],
     ];
     let signer_seeds = &[&escrow_seeds[..]];
-    
-    transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow_vault.to_account_info(),
-                to: ctx.accounts.merchant_authority.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        merchant_payment,
+
+    let rail = PaymentRail::resolve(
+        group_deal.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
+    )?;
+    let escrow_info = ctx.accounts.escrow_vault.to_account_info();
+    let from = rail.endpoint(&escrow_info, &ctx.accounts.escrow_payment_account)?;
+    let merchant_to = rail.endpoint(
+        &ctx.accounts.merchant_authority.to_account_info(),
+        &ctx.accounts.merchant_payment_account,
     )?;
+    let treasury_to = rail.endpoint(&ctx.accounts.treasury.to_account_info(), &ctx.accounts.treasury_payment_account)?;
+    
+    MerchantRevenue {
+        merchant: &ctx.accounts.merchant,
+        merchant_to: &merchant_to,
+        revenue_config: ctx.accounts.revenue_config.as_deref(),
+        recipient_accounts: ctx.remaining_accounts,
+        source: group_deal_key,
+    }
+    .distribute(&rail, &from, &escrow_info, merchant_payment, signer_seeds)?;
+    
+    // Move the marketplace fee into the treasury
+    rail.transfer(&from, &treasury_to, &escrow_info, marketplace_fee, signer_seeds)?;
+    if !rail.is_token() {
+        marketplace.record_fee(marketplace_fee);
+    }
     
     // Update promotion supply
     let promotion = &mut ctx.accounts.promotion;
@@ -348,7 +424,7 @@ pub fn finalize_group_deal(ctx: Context<FinalizeGroupDeal>) -> Result<()> {
     msg!("✅ Group deal finalized successfully!");
     msg!("✅ Participants: {}", group_deal.current_participants);
     msg!("✅ Final discount: {}%", group_deal.get_current_discount());
-    msg!("✅ Merchant revenue: {} lamports", merchant_payment);
+    msg!("✅ Merchant revenue: {}", merchant_payment);
     
     Ok(())
 }
@@ -388,6 +464,14 @@ pub struct RefundGroupDeal<'info> {
         constraint = participant.user == user.key() @ CouponError::NotCouponOwner
     )]
     pub user: UncheckedAccount<'info>,
+
+    // SPL payment accounts, only required when the deal has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub escrow_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub user_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
     
     pub system_program: Program<'info, System>,
 }
@@ -406,18 +490,17 @@ pub fn refund_group_deal(ctx: Context<RefundGroupDeal>) -> Result<()> {
         &[ctx.bumps.escrow_vault],
     ];
     let signer_seeds = &[&escrow_seeds[..]];
-    
-    transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.system_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow_vault.to_account_info(),
-                to: ctx.accounts.user.to_account_info(),
-            },
-            signer_seeds,
-        ),
-        refund_amount,
+
+    let rail = PaymentRail::resolve(
+        group_deal.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
     )?;
+    let escrow_info = ctx.accounts.escrow_vault.to_account_info();
+    let from = rail.endpoint(&escrow_info, &ctx.accounts.escrow_payment_account)?;
+    let to = rail.endpoint(&ctx.accounts.user.to_account_info(), &ctx.accounts.user_payment_account)?;
+    rail.transfer(&from, &to, &escrow_info, refund_amount, signer_seeds)?;
     
     participant.is_refunded = true;
     
@@ -428,7 +511,7 @@ pub fn refund_group_deal(ctx: Context<RefundGroupDeal>) -> Result<()> {
         timestamp: Clock::get()?.unix_timestamp,
     });
     
-    msg!("✅ Refund processed: {} lamports", refund_amount);
+    msg!("✅ Refund processed: {}", refund_amount);
     
     Ok(())
 }
@@ -487,8 +570,11 @@ pub fn mint_group_coupon(ctx: Context<MintGroupCoupon>, coupon_id: u64) -> Resul
     coupon.expiry_timestamp = promotion.expiry_timestamp;
     coupon.is_redeemed = false;
     coupon.redeemed_at = 0;
+    coupon.fill(promotion.redemption_model);
     coupon.created_at = clock.unix_timestamp;
     coupon.mint = None;
+    coupon.is_escrowed = false;
+    coupon.serial_number = 0; // No NFT behind group coupons
     coupon.metadata_uri = "https://example.com/group-coupon.json".to_string();
     
     // Mark as minted
@@ -527,7 +613,9 @@ pub fn handler_join_group_deal(ctx: Context<JoinGroupDeal>) -> Result<()> {
     join_group_deal(ctx)
 }
 
-pub fn handler_finalize_group_deal(ctx: Context<FinalizeGroupDeal>) -> Result<()> {
+pub fn handler_finalize_group_deal<'info>(
+    ctx: Context<'_, '_, '_, 'info, FinalizeGroupDeal<'info>>,
+) -> Result<()> {
     finalize_group_deal(ctx)
 }
 
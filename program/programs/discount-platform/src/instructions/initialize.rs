@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 use crate::state::Marketplace;
 use crate::events::MarketplaceInitialized;
 use crate::errors::CouponError;
@@ -13,6 +14,13 @@ pub struct Initialize<'info> {
         bump
     )]
     pub marketplace: Account<'info, Marketplace>,
+    /// CHECK: Fee treasury PDA (system-owned, holds lamports only)
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
     #[account(mut)]
     pub authority: Signer<'info>,
     pub system_program: Program<'info, System>,
@@ -24,6 +32,28 @@ pub fn handler(ctx: Context<Initialize>) -> Result<()> {
     marketplace.total_coupons = 0;
     marketplace.total_merchants = 0;
     marketplace.fee_basis_points = 250;
+    marketplace.pending_authority = None;
+    marketplace.is_paused = false;
+    marketplace.total_fees_collected = 0;
+    marketplace.total_fees_withdrawn = 0;
+    marketplace.accepted_mints = Vec::new();
+    marketplace.verifiers = Vec::new();
+
+    // Fund the treasury up to rent exemption so small fees can land in it
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let top_up = rent_exempt_minimum.saturating_sub(ctx.accounts.treasury.lamports());
+    if top_up > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            top_up,
+        )?;
+    }
 
     emit!(MarketplaceInitialized {
         marketplace: marketplace.key(),
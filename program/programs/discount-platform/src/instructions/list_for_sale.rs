@@ -1,15 +1,19 @@
 use anchor_lang::prelude::*;
-use crate::state::{Coupon, Listing, UserStats,ReputationTier};
+use anchor_spl::token::{Token, TokenAccount, Mint, transfer, Transfer, close_account, CloseAccount};
+use crate::state::{Coupon, Promotion, Listing, Marketplace, UserStats, ReputationTier, IssuanceMode};
 use crate::errors::CouponError;
 use crate::events::{CouponListed, ListingCancelled};
 
 pub fn handler(
     ctx: Context<ListCouponForSale>,
     price: u64,
+    payment_mint: Option<Pubkey>,
 ) -> Result<()> {
     require!(price > 0, CouponError::InvalidPrice);
+    ctx.accounts.promotion.require_resale_price(price)?;
+    ctx.accounts.marketplace.require_payment_mint(payment_mint)?;
 
-    let coupon = &ctx.accounts.coupon;
+    let coupon = &mut ctx.accounts.coupon;
     require!(!coupon.is_redeemed, CouponError::CouponAlreadyRedeemed);
     require!(coupon.expiry_timestamp > Clock::get()?.unix_timestamp, CouponError::CouponExpired);
     require!(coupon.owner == ctx.accounts.seller.key(), CouponError::NotCouponOwner);
@@ -25,6 +29,7 @@ pub fn handler(
         user_stats.total_ratings_given = 0;
         user_stats.total_comments = 0;
         user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
         user_stats.reputation_score = 0;
         user_stats.tier = ReputationTier::Bronze;
         user_stats.badges_earned = Vec::new();
@@ -37,10 +42,24 @@ pub fn handler(
     user_stats.add_reputation(3); // 3 points per listing
     user_stats.last_activity = current_time;
 
+    // Escrow the NFT in the listing vault until it is sold or the listing is cancelled
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.seller_token_account.to_account_info(),
+        to: ctx.accounts.listing_vault.to_account_info(),
+        authority: ctx.accounts.seller.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    transfer(cpi_ctx, 1)?;
+    coupon.is_escrowed = true;
+    ctx.accounts.promotion.open_escrow();
+
     let listing = &mut ctx.accounts.listing;
-    listing.coupon = ctx.accounts.coupon.key();
+    listing.coupon = coupon.key();
     listing.seller = ctx.accounts.seller.key();
     listing.price = price;
+    listing.payment_mint = payment_mint;
+    listing.royalty_basis_points = ctx.accounts.promotion.royalty_basis_points;
     listing.is_active = true;
     listing.created_at = current_time;
 
@@ -50,6 +69,7 @@ pub fn handler(
         nft_mint: coupon.mint.unwrap_or(Pubkey::default()),
         seller: listing.seller,
         price,
+        payment_mint,
     });
 
     msg!("Coupon listed! Total listings: {} | Reputation: {} | Tier: {:?}", 
@@ -65,6 +85,35 @@ pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
 
     listing.is_active = false;
 
+    // Return the NFT to the seller and close the vault
+    let nft_mint_key = ctx.accounts.nft_mint.key();
+    let vault_seeds = &[
+        b"listing_vault",
+        nft_mint_key.as_ref(),
+        &[ctx.bumps.listing_vault],
+    ];
+    let vault_signer = &[&vault_seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.listing_vault.to_account_info(),
+        to: ctx.accounts.seller_token_account.to_account_info(),
+        authority: ctx.accounts.listing_vault.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, vault_signer);
+    transfer(cpi_ctx, 1)?;
+
+    let cpi_accounts = CloseAccount {
+        account: ctx.accounts.listing_vault.to_account_info(),
+        destination: ctx.accounts.seller.to_account_info(),
+        authority: ctx.accounts.listing_vault.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, vault_signer);
+    close_account(cpi_ctx)?;
+    ctx.accounts.coupon.is_escrowed = false;
+    ctx.accounts.promotion.close_escrow();
+
     emit!(ListingCancelled {
         listing: listing.key(),
         coupon: listing.coupon,
@@ -86,9 +135,25 @@ pub struct ListCouponForSale<'info> {
     )]
     pub listing: Account<'info, Listing>,
     #[account(
-        constraint = coupon.owner == seller.key() @ CouponError::NotCouponOwner
+        mut,
+        constraint = coupon.owner == seller.key() @ CouponError::NotCouponOwner,
+        constraint = !coupon.is_escrowed @ CouponError::CouponEscrowed
     )]
     pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon,
+        constraint = promotion.resale_allowed @ CouponError::ResaleNotAllowed,
+        constraint = promotion.issuance_mode == IssuanceMode::TokenMetadata @ CouponError::WrongIssuanceMode
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
     
     #[account(
         init_if_needed,
@@ -98,9 +163,35 @@ pub struct ListCouponForSale<'info> {
         bump
     )]
     pub user_stats: Account<'info, UserStats>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Seller's token account
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == nft_mint.key(),
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: Listing vault token account (PDA)
+    #[account(
+        init,
+        payer = seller,
+        token::mint = nft_mint,
+        token::authority = listing_vault,
+        seeds = [b"listing_vault", nft_mint.key().as_ref()],
+        bump
+    )]
+    pub listing_vault: Account<'info, TokenAccount>,
     
     #[account(mut)]
     pub seller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
@@ -108,8 +199,46 @@ pub struct ListCouponForSale<'info> {
 pub struct CancelListing<'info> {
     #[account(
         mut,
+        close = seller,
         constraint = listing.seller == seller.key() @ CouponError::NotListingSeller
     )]
     pub listing: Account<'info, Listing>,
+
+    #[account(
+        mut,
+        constraint = coupon.key() == listing.coupon @ CouponError::WrongCoupon
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Listing vault token account (PDA)
+    #[account(
+        mut,
+        seeds = [b"listing_vault", nft_mint.key().as_ref()],
+        bump
+    )]
+    pub listing_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: Seller's token account
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == nft_mint.key(),
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
     pub seller: Signer<'info>,
+    pub token_program: Program<'info, Token>,
 }
\ No newline at end of file
@@ -0,0 +1,429 @@
+// src/instructions/marketplace_admin.rs
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::Marketplace;
+use crate::errors::CouponError;
+use crate::utils::PaymentRail;
+use crate::events::{
+    MarketplaceConfigUpdated,
+    MarketplaceAuthorityProposed,
+    MarketplaceAuthorityTransferred,
+    TreasuryWithdrawn,
+    PaymentMintUpdated,
+    VerifierUpdated,
+};
+
+// ============================================================================
+// Update Marketplace Config (fee, pause flag)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct UpdateMarketplaceConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ CouponError::NotMarketplaceAuthority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn update_marketplace_config(
+    ctx: Context<UpdateMarketplaceConfig>,
+    fee_basis_points: Option<u16>,
+    is_paused: Option<bool>,
+) -> Result<()> {
+    let marketplace = &mut ctx.accounts.marketplace;
+    let old_fee_basis_points = marketplace.fee_basis_points;
+
+    if let Some(fee) = fee_basis_points {
+        require!(fee <= Marketplace::MAX_FEE_BASIS_POINTS, CouponError::InvalidFee);
+        marketplace.fee_basis_points = fee;
+    }
+
+    if let Some(paused) = is_paused {
+        marketplace.is_paused = paused;
+    }
+
+    emit!(MarketplaceConfigUpdated {
+        marketplace: marketplace.key(),
+        authority: marketplace.authority,
+        old_fee_basis_points,
+        new_fee_basis_points: marketplace.fee_basis_points,
+        is_paused: marketplace.is_paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Marketplace config updated");
+    msg!("✅ Fee: {} bps | Paused: {}", marketplace.fee_basis_points, marketplace.is_paused);
+
+    Ok(())
+}
+
+// ============================================================================
+// Propose Authority (step 1 of handover)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ CouponError::NotMarketplaceAuthority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    let marketplace = &mut ctx.accounts.marketplace;
+    require!(new_authority != Pubkey::default(), CouponError::InvalidInput);
+
+    marketplace.pending_authority = Some(new_authority);
+
+    emit!(MarketplaceAuthorityProposed {
+        marketplace: marketplace.key(),
+        current_authority: marketplace.authority,
+        proposed_authority: new_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Authority handover proposed to {}", new_authority);
+
+    Ok(())
+}
+
+// ============================================================================
+// Accept Authority (step 2 of handover, signed by the new key)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub new_authority: Signer<'info>,
+}
+
+pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let marketplace = &mut ctx.accounts.marketplace;
+    let pending = marketplace.pending_authority.ok_or(CouponError::NoPendingAuthority)?;
+    require!(
+        pending == ctx.accounts.new_authority.key(),
+        CouponError::NotPendingAuthority
+    );
+
+    let previous_authority = marketplace.authority;
+    marketplace.authority = pending;
+    marketplace.pending_authority = None;
+
+    emit!(MarketplaceAuthorityTransferred {
+        marketplace: marketplace.key(),
+        previous_authority,
+        new_authority: marketplace.authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Marketplace authority transferred to {}", marketplace.authority);
+
+    Ok(())
+}
+
+// ============================================================================
+// Withdraw Treasury (authority only)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ CouponError::NotMarketplaceAuthority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    /// CHECK: Fee treasury PDA
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Wallet (SOL) or token account (SPL) chosen by the authority
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    // Only passed when withdrawing SPL fees
+    pub payment_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub treasury_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, CouponError::InvalidPrice);
+
+    let payment_mint = ctx.accounts.payment_mint.as_ref().map(|mint| mint.key());
+    let rail = PaymentRail::resolve(
+        payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
+    )?;
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+    let source = rail.endpoint(&treasury_info, &ctx.accounts.treasury_payment_account)?;
+
+    // SOL withdrawals must leave the treasury rent exempt
+    let available = match &ctx.accounts.treasury_payment_account {
+        Some(vault) if rail.is_token() => vault.amount,
+        _ => {
+            let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+            ctx.accounts.treasury.lamports().saturating_sub(rent_exempt_minimum)
+        }
+    };
+    require!(amount <= available, CouponError::InsufficientTreasuryBalance);
+
+    let treasury_seeds = &[
+        b"treasury".as_ref(),
+        &[ctx.bumps.treasury],
+    ];
+    let signer_seeds = &[&treasury_seeds[..]];
+
+    rail.transfer(
+        &source,
+        &ctx.accounts.destination.to_account_info(),
+        &treasury_info,
+        amount,
+        signer_seeds,
+    )?;
+
+    let marketplace = &mut ctx.accounts.marketplace;
+    if !rail.is_token() {
+        marketplace.total_fees_withdrawn = marketplace.total_fees_withdrawn.saturating_add(amount);
+    }
+
+    emit!(TreasuryWithdrawn {
+        marketplace: marketplace.key(),
+        treasury: ctx.accounts.treasury.key(),
+        destination: ctx.accounts.destination.key(),
+        payment_mint,
+        amount,
+        remaining_balance: available - amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Withdrew {} from treasury", amount);
+
+    Ok(())
+}
+
+// ============================================================================
+// Payment Mint Allow-List
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct AddPaymentMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ CouponError::NotMarketplaceAuthority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    /// CHECK: Fee treasury PDA, owner of the fee vault for this mint
+    #[account(
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = payment_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = payment_token_program
+    )]
+    pub treasury_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_payment_mint(ctx: Context<AddPaymentMint>) -> Result<()> {
+    let marketplace = &mut ctx.accounts.marketplace;
+    let mint = ctx.accounts.payment_mint.key();
+
+    require!(!marketplace.is_mint_accepted(&mint), CouponError::InvalidInput);
+    require!(
+        marketplace.accepted_mints.len() < Marketplace::MAX_ACCEPTED_MINTS,
+        CouponError::InvalidInput
+    );
+
+    marketplace.accepted_mints.push(mint);
+
+    emit!(PaymentMintUpdated {
+        marketplace: marketplace.key(),
+        mint,
+        accepted: true,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Payment mint accepted: {}", mint);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemovePaymentMint<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ CouponError::NotMarketplaceAuthority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn remove_payment_mint(ctx: Context<RemovePaymentMint>, mint: Pubkey) -> Result<()> {
+    let marketplace = &mut ctx.accounts.marketplace;
+    require!(marketplace.is_mint_accepted(&mint), CouponError::PaymentMintNotAccepted);
+
+    // Existing listings keep settling in this mint; only new ones are blocked
+    marketplace.accepted_mints.retain(|accepted| accepted != &mint);
+
+    emit!(PaymentMintUpdated {
+        marketplace: marketplace.key(),
+        mint,
+        accepted: false,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Payment mint removed: {}", mint);
+
+    Ok(())
+}
+
+// ============================================================================
+// Merchant Verifier Delegation
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct UpdateVerifiers<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ CouponError::NotMarketplaceAuthority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn add_verifier(ctx: Context<UpdateVerifiers>, verifier: Pubkey) -> Result<()> {
+    let marketplace = &mut ctx.accounts.marketplace;
+
+    require!(!marketplace.verifiers.contains(&verifier), CouponError::InvalidInput);
+    require!(
+        marketplace.verifiers.len() < Marketplace::MAX_VERIFIERS,
+        CouponError::InvalidInput
+    );
+
+    marketplace.verifiers.push(verifier);
+
+    emit!(VerifierUpdated {
+        marketplace: marketplace.key(),
+        verifier,
+        is_verifier: true,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Verifier added: {}", verifier);
+
+    Ok(())
+}
+
+pub fn remove_verifier(ctx: Context<UpdateVerifiers>, verifier: Pubkey) -> Result<()> {
+    let marketplace = &mut ctx.accounts.marketplace;
+    require!(marketplace.verifiers.contains(&verifier), CouponError::NotVerifier);
+
+    // Attestations already issued by this key stay valid until they expire or are revoked
+    marketplace.verifiers.retain(|existing| existing != &verifier);
+
+    emit!(VerifierUpdated {
+        marketplace: marketplace.key(),
+        verifier,
+        is_verifier: false,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Verifier removed: {}", verifier);
+
+    Ok(())
+}
+
+// ============================================================================
+// Handlers for lib.rs
+// ============================================================================
+
+pub fn handler_update_marketplace_config(
+    ctx: Context<UpdateMarketplaceConfig>,
+    fee_basis_points: Option<u16>,
+    is_paused: Option<bool>,
+) -> Result<()> {
+    update_marketplace_config(ctx, fee_basis_points, is_paused)
+}
+
+pub fn handler_propose_authority(
+    ctx: Context<ProposeAuthority>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    propose_authority(ctx, new_authority)
+}
+
+pub fn handler_accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    accept_authority(ctx)
+}
+
+pub fn handler_withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    withdraw_treasury(ctx, amount)
+}
+
+pub fn handler_add_payment_mint(ctx: Context<AddPaymentMint>) -> Result<()> {
+    add_payment_mint(ctx)
+}
+
+pub fn handler_remove_payment_mint(ctx: Context<RemovePaymentMint>, mint: Pubkey) -> Result<()> {
+    remove_payment_mint(ctx, mint)
+}
+
+pub fn handler_add_verifier(ctx: Context<UpdateVerifiers>, verifier: Pubkey) -> Result<()> {
+    add_verifier(ctx, verifier)
+}
+
+pub fn handler_remove_verifier(ctx: Context<UpdateVerifiers>, verifier: Pubkey) -> Result<()> {
+    remove_verifier(ctx, verifier)
+}
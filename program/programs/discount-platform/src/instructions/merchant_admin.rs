@@ -0,0 +1,361 @@
+// src/instructions/merchant_admin.rs
+use anchor_lang::prelude::*;
+use crate::state::{Merchant, Marketplace, Location, validate_coordinates, VerificationTier, RevenueConfig, RevenueShare};
+use crate::errors::CouponError;
+use crate::events::{
+    MerchantUpdated,
+    MerchantStatusChanged,
+    MerchantAuthorityTransferred,
+    MerchantVerified,
+    MerchantVerificationRevoked,
+    RevenueConfigUpdated,
+    RevenueConfigClosed,
+};
+
+// ============================================================================
+// Update Merchant Profile (name, category, location)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct UpdateMerchant<'info> {
+    #[account(
+        mut,
+        constraint = merchant.authority == authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn update_merchant(
+    ctx: Context<UpdateMerchant>,
+    name: Option<String>,
+    category: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+
+    if let Some(new_name) = name {
+        require!(new_name.len() <= 50, CouponError::NameTooLong);
+        merchant.name = new_name;
+    }
+
+    if let Some(new_category) = category {
+        require!(new_category.len() <= 30, CouponError::CategoryTooLong);
+        merchant.category = new_category;
+    }
+
+    match (latitude, longitude) {
+        (Some(lat), Some(lon)) => {
+            validate_coordinates(lat, lon)?;
+
+            merchant.location = Location::from_coords(lat, lon);
+            merchant.has_physical_location = true;
+        }
+        (None, None) => {}
+        // A single coordinate can't describe a location
+        _ => return err!(CouponError::InvalidCoordinates),
+    }
+
+    emit!(MerchantUpdated {
+        merchant: merchant.key(),
+        authority: merchant.authority,
+        name: merchant.name.clone(),
+        category: merchant.category.clone(),
+        has_physical_location: merchant.has_physical_location,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Merchant profile updated");
+
+    Ok(())
+}
+
+// ============================================================================
+// Activate / Deactivate Merchant
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetMerchantActive<'info> {
+    #[account(
+        mut,
+        constraint = merchant.authority == authority.key()
+            || marketplace.authority == authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn set_merchant_active(ctx: Context<SetMerchantActive>, is_active: bool) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+    let authority = ctx.accounts.authority.key();
+
+    if authority == ctx.accounts.marketplace.authority {
+        // Marketplace action: a deactivation here is a suspension the merchant can't undo
+        merchant.suspended_by_marketplace = !is_active;
+    } else {
+        require!(
+            !is_active || !merchant.suspended_by_marketplace,
+            CouponError::MerchantSuspended
+        );
+    }
+
+    merchant.is_active = is_active;
+
+    emit!(MerchantStatusChanged {
+        merchant: merchant.key(),
+        is_active,
+        suspended_by_marketplace: merchant.suspended_by_marketplace,
+        changed_by: authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Merchant {}", if is_active { "activated" } else { "deactivated" });
+
+    Ok(())
+}
+
+// ============================================================================
+// Transfer Merchant Authority
+// ============================================================================
+
+/// The merchant PDA keeps its original seeds, so clients must track the
+/// merchant address rather than re-deriving it from the new authority.
+#[derive(Accounts)]
+pub struct TransferMerchantAuthority<'info> {
+    #[account(
+        mut,
+        constraint = merchant.authority == authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    pub authority: Signer<'info>,
+
+    // Must co-sign so the merchant can't be handed to a key nobody controls
+    pub new_authority: Signer<'info>,
+}
+
+pub fn transfer_merchant_authority(ctx: Context<TransferMerchantAuthority>) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+    let previous_authority = merchant.authority;
+    let new_authority = ctx.accounts.new_authority.key();
+
+    merchant.authority = new_authority;
+
+    emit!(MerchantAuthorityTransferred {
+        merchant: merchant.key(),
+        previous_authority,
+        new_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Merchant authority transferred to {}", new_authority);
+
+    Ok(())
+}
+
+// ============================================================================
+// Merchant Verification (marketplace authority or delegated verifier)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct VerifyMerchant<'info> {
+    #[account(mut)]
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.can_verify(&verifier.key()) @ CouponError::NotVerifier
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+
+    pub verifier: Signer<'info>,
+}
+
+pub fn verify_merchant(
+    ctx: Context<VerifyMerchant>,
+    tier: VerificationTier,
+    attestation_hash: [u8; 32],
+    expires_at: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    // Revocation has its own instruction so the audit trail stays explicit
+    require!(tier != VerificationTier::Unverified, CouponError::InvalidInput);
+    require!(expires_at > now, CouponError::InvalidExpiry);
+
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.verification_tier = tier;
+    merchant.attestation_hash = attestation_hash;
+    merchant.verification_expires_at = expires_at;
+    merchant.verified_by = ctx.accounts.verifier.key();
+
+    emit!(MerchantVerified {
+        merchant: merchant.key(),
+        tier,
+        attestation_hash,
+        expires_at,
+        verifier: merchant.verified_by,
+        timestamp: now,
+    });
+
+    msg!("✅ Merchant verified: {:?} until {}", tier, expires_at);
+
+    Ok(())
+}
+
+pub fn revoke_merchant_verification(ctx: Context<VerifyMerchant>) -> Result<()> {
+    let merchant = &mut ctx.accounts.merchant;
+    require!(
+        merchant.verification_tier != VerificationTier::Unverified,
+        CouponError::InvalidInput
+    );
+
+    merchant.verification_tier = VerificationTier::Unverified;
+    merchant.attestation_hash = [0u8; 32];
+    merchant.verification_expires_at = 0;
+    merchant.verified_by = Pubkey::default();
+
+    emit!(MerchantVerificationRevoked {
+        merchant: merchant.key(),
+        revoked_by: ctx.accounts.verifier.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Merchant verification revoked");
+
+    Ok(())
+}
+
+// ============================================================================
+// Revenue Split Configuration
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetRevenueConfig<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RevenueConfig::INIT_SPACE,
+        seeds = [b"revenue_config", merchant.key().as_ref()],
+        bump
+    )]
+    pub revenue_config: Account<'info, RevenueConfig>,
+
+    #[account(
+        mut,
+        constraint = merchant.authority == authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_revenue_config(ctx: Context<SetRevenueConfig>, recipients: Vec<RevenueShare>) -> Result<()> {
+    RevenueConfig::validate(&recipients)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    let revenue_config = &mut ctx.accounts.revenue_config;
+    revenue_config.merchant = ctx.accounts.merchant.key();
+    revenue_config.recipients = recipients.clone();
+    revenue_config.updated_at = now;
+
+    ctx.accounts.merchant.has_revenue_split = true;
+
+    emit!(RevenueConfigUpdated {
+        merchant: revenue_config.merchant,
+        recipients,
+        timestamp: now,
+    });
+
+    msg!("✅ Revenue split set: {} recipients", revenue_config.recipients.len());
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseRevenueConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"revenue_config", merchant.key().as_ref()],
+        bump,
+        close = authority
+    )]
+    pub revenue_config: Account<'info, RevenueConfig>,
+
+    #[account(
+        mut,
+        constraint = merchant.authority == authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn close_revenue_config(ctx: Context<CloseRevenueConfig>) -> Result<()> {
+    // Payouts go back to the merchant authority in full
+    ctx.accounts.merchant.has_revenue_split = false;
+
+    emit!(RevenueConfigClosed {
+        merchant: ctx.accounts.merchant.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Revenue split removed");
+
+    Ok(())
+}
+
+// ============================================================================
+// Handlers for lib.rs
+// ============================================================================
+
+pub fn handler_update_merchant(
+    ctx: Context<UpdateMerchant>,
+    name: Option<String>,
+    category: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> Result<()> {
+    update_merchant(ctx, name, category, latitude, longitude)
+}
+
+pub fn handler_set_merchant_active(ctx: Context<SetMerchantActive>, is_active: bool) -> Result<()> {
+    set_merchant_active(ctx, is_active)
+}
+
+pub fn handler_transfer_merchant_authority(ctx: Context<TransferMerchantAuthority>) -> Result<()> {
+    transfer_merchant_authority(ctx)
+}
+
+pub fn handler_verify_merchant(
+    ctx: Context<VerifyMerchant>,
+    tier: VerificationTier,
+    attestation_hash: [u8; 32],
+    expires_at: i64,
+) -> Result<()> {
+    verify_merchant(ctx, tier, attestation_hash, expires_at)
+}
+
+pub fn handler_revoke_merchant_verification(ctx: Context<VerifyMerchant>) -> Result<()> {
+    revoke_merchant_verification(ctx)
+}
+
+pub fn handler_set_revenue_config(ctx: Context<SetRevenueConfig>, recipients: Vec<RevenueShare>) -> Result<()> {
+    set_revenue_config(ctx, recipients)
+}
+
+pub fn handler_close_revenue_config(ctx: Context<CloseRevenueConfig>) -> Result<()> {
+    close_revenue_config(ctx)
+}
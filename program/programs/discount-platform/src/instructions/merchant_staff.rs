@@ -0,0 +1,231 @@
+// src/instructions/merchant_staff.rs
+use anchor_lang::prelude::*;
+use crate::state::{Merchant, MerchantStaff, StaffRole, Location, validate_coordinates};
+use crate::errors::CouponError;
+use crate::events::{StaffAdded, StaffUpdated, StaffRemoved};
+
+/// Resolve optional per-store coordinates; both or neither must be given.
+fn store_location(latitude: Option<f64>, longitude: Option<f64>) -> Result<Option<Location>> {
+    match (latitude, longitude) {
+        (Some(lat), Some(lon)) => {
+            validate_coordinates(lat, lon)?;
+            Ok(Some(Location::from_coords(lat, lon)))
+        }
+        (None, None) => Ok(None),
+        _ => err!(CouponError::InvalidCoordinates),
+    }
+}
+
+// ============================================================================
+// Add Staff Member
+// ============================================================================
+
+#[derive(Accounts)]
+#[instruction(staff: Pubkey)]
+pub struct AddStaff<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + MerchantStaff::INIT_SPACE,
+        seeds = [b"merchant_staff", merchant.key().as_ref(), staff.as_ref()],
+        bump
+    )]
+    pub staff_member: Account<'info, MerchantStaff>,
+
+    pub merchant: Account<'info, Merchant>,
+
+    /// Present when an Owner-role staff key, not the merchant authority, signs
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub authority_staff: Option<Account<'info, MerchantStaff>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_staff(
+    ctx: Context<AddStaff>,
+    staff: Pubkey,
+    role: StaffRole,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    ctx.accounts.merchant.authorize(
+        &authority,
+        ctx.accounts.authority_staff.as_deref(),
+        StaffRole::can_manage_staff,
+    )?;
+
+    let location = store_location(latitude, longitude)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    let staff_member = &mut ctx.accounts.staff_member;
+    staff_member.merchant = ctx.accounts.merchant.key();
+    staff_member.staff = staff;
+    staff_member.role = role;
+    staff_member.is_active = true;
+    staff_member.added_by = authority;
+    staff_member.added_at = now;
+    staff_member.has_store_location = location.is_some();
+    staff_member.store_location = location.unwrap_or(Location {
+        latitude: 0,
+        longitude: 0,
+        region_code: 0,
+        country_code: 0,
+        city_hash: 0,
+    });
+
+    emit!(StaffAdded {
+        merchant: staff_member.merchant,
+        staff,
+        role,
+        added_by: authority,
+        timestamp: now,
+    });
+
+    msg!("✅ Staff added: {} as {:?}", staff, role);
+
+    Ok(())
+}
+
+// ============================================================================
+// Update Staff Member (role, active flag, store location)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct UpdateStaff<'info> {
+    #[account(
+        mut,
+        constraint = staff_member.merchant == merchant.key() @ CouponError::WrongMerchant
+    )]
+    pub staff_member: Account<'info, MerchantStaff>,
+
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub authority_staff: Option<Account<'info, MerchantStaff>>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn update_staff(
+    ctx: Context<UpdateStaff>,
+    role: Option<StaffRole>,
+    is_active: Option<bool>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> Result<()> {
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.authority_staff.as_deref(),
+        StaffRole::can_manage_staff,
+    )?;
+
+    let location = store_location(latitude, longitude)?;
+    let staff_member = &mut ctx.accounts.staff_member;
+
+    if let Some(new_role) = role {
+        staff_member.role = new_role;
+    }
+
+    if let Some(active) = is_active {
+        staff_member.is_active = active;
+    }
+
+    if let Some(new_location) = location {
+        staff_member.store_location = new_location;
+        staff_member.has_store_location = true;
+    }
+
+    emit!(StaffUpdated {
+        merchant: staff_member.merchant,
+        staff: staff_member.staff,
+        role: staff_member.role,
+        is_active: staff_member.is_active,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Staff updated: {}", staff_member.staff);
+
+    Ok(())
+}
+
+// ============================================================================
+// Remove Staff Member (rent back to the signer)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct RemoveStaff<'info> {
+    #[account(
+        mut,
+        constraint = staff_member.merchant == merchant.key() @ CouponError::WrongMerchant,
+        close = authority
+    )]
+    pub staff_member: Account<'info, MerchantStaff>,
+
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub authority_staff: Option<Account<'info, MerchantStaff>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn remove_staff(ctx: Context<RemoveStaff>) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    ctx.accounts.merchant.authorize(
+        &authority,
+        ctx.accounts.authority_staff.as_deref(),
+        StaffRole::can_manage_staff,
+    )?;
+
+    emit!(StaffRemoved {
+        merchant: ctx.accounts.staff_member.merchant,
+        staff: ctx.accounts.staff_member.staff,
+        removed_by: authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Staff removed: {}", ctx.accounts.staff_member.staff);
+
+    Ok(())
+}
+
+// ============================================================================
+// Handlers for lib.rs
+// ============================================================================
+
+pub fn handler_add_staff(
+    ctx: Context<AddStaff>,
+    staff: Pubkey,
+    role: StaffRole,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> Result<()> {
+    add_staff(ctx, staff, role, latitude, longitude)
+}
+
+pub fn handler_update_staff(
+    ctx: Context<UpdateStaff>,
+    role: Option<StaffRole>,
+    is_active: Option<bool>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> Result<()> {
+    update_staff(ctx, role, is_active, latitude, longitude)
+}
+
+pub fn handler_remove_staff(ctx: Context<RemoveStaff>) -> Result<()> {
+    remove_staff(ctx)
+}
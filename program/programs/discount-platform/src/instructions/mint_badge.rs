@@ -1,11 +1,13 @@
 // src/instructions/mint_badge.rs
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::associated_token::AssociatedToken;
 use mpl_token_metadata::instructions::CreateV1CpiBuilder;
 use mpl_token_metadata::types::{TokenStandard, PrintSupply};
-use crate::state::{BadgeNFT, BadgeType};
+use crate::state::{BadgeNFT, BadgeType, Marketplace, UserStats, ReputationTier};
 use crate::errors::CouponError;
 use crate::events::BadgeEarned;
+use crate::utils::mint_soulbound_badge;
 
 #[derive(Accounts)]
 #[instruction(badge_type: BadgeType)]
@@ -24,10 +26,19 @@ pub struct MintBadge<'info> {
         init,
         payer = user,
         mint::decimals = 0,
-        mint::authority = authority,
-        mint::freeze_authority = authority
+        mint::authority = badge_authority,
+        mint::freeze_authority = badge_authority
     )]
     pub mint: Account<'info, Mint>,
+
+    /// Holder's badge token account, frozen once the badge is minted
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub token_account: Account<'info, TokenAccount>,
     
     /// CHECK: Metadata account
     #[account(mut)]
@@ -37,12 +48,39 @@ pub struct MintBadge<'info> {
     #[account(mut)]
     pub master_edition: UncheckedAccount<'info>,
     
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ CouponError::NotMarketplaceAuthority
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    /// CHECK: Program PDA that holds mint, freeze and update authority over badge NFTs
+    #[account(
+        seeds = [b"badge_authority"],
+        bump
+    )]
+    pub badge_authority: UncheckedAccount<'info>,
+    
     #[account(mut)]
     pub user: Signer<'info>,
+    
+    /// Marketplace authority approving the badge
     pub authority: Signer<'info>,
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     
     /// CHECK: Metaplex Token Metadata Program
+    #[account(address = mpl_token_metadata::ID)]
     pub token_metadata_program: UncheckedAccount<'info>,
     
     /// CHECK: Sysvar Instructions - ADD THIS
@@ -54,32 +92,68 @@ pub struct MintBadge<'info> {
 }
 
 pub fn handler(ctx: Context<MintBadge>, badge_type: BadgeType) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    // Initialize UserStats if first time
+    let user_stats = &mut ctx.accounts.user_stats;
+    if user_stats.user == Pubkey::default() {
+        user_stats.user = ctx.accounts.user.key();
+        user_stats.total_purchases = 0;
+        user_stats.total_redemptions = 0;
+        user_stats.total_ratings_given = 0;
+        user_stats.total_comments = 0;
+        user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
+        user_stats.reputation_score = 0;
+        user_stats.tier = ReputationTier::Bronze;
+        user_stats.badges_earned = Vec::new();
+        user_stats.joined_at = current_time;
+        user_stats.last_activity = current_time;
+    }
+
     let badge = &mut ctx.accounts.badge_nft;
     badge.user = ctx.accounts.user.key();
     badge.badge_type = badge_type;
     badge.mint = ctx.accounts.mint.key();
     badge.metadata = ctx.accounts.metadata.key();
-    badge.earned_at = Clock::get()?.unix_timestamp;
+    badge.earned_at = current_time;
     badge.metadata_uri = "https://example.com/badge.json".to_string();
 
-    // Create Metaplex metadata with sysvar_instructions
+    // Mint and freeze the badge, then create Metaplex metadata, all signed
+    // by the program's badge authority
+    let authority_seeds: &[&[u8]] = &[b"badge_authority", &[ctx.bumps.badge_authority]];
+    let authority_info = ctx.accounts.badge_authority.to_account_info();
+
+    mint_soulbound_badge(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.token_account.to_account_info(),
+        &authority_info,
+        &[authority_seeds],
+    )?;
+
     CreateV1CpiBuilder::new(&ctx.accounts.token_metadata_program.to_account_info())
         .metadata(&ctx.accounts.metadata.to_account_info())
         .master_edition(Some(&ctx.accounts.master_edition.to_account_info()))
         .mint(&ctx.accounts.mint.to_account_info(), true)
-        .authority(&ctx.accounts.authority.to_account_info())
+        .authority(&authority_info)
         .payer(&ctx.accounts.user.to_account_info())
-        .update_authority(&ctx.accounts.authority.to_account_info(), true)
+        .update_authority(&authority_info, true)
         .system_program(&ctx.accounts.system_program.to_account_info())
-        .sysvar_instructions(&ctx.accounts.sysvar_instructions.to_account_info()) // ADD THIS
-        .spl_token_program(Some(&ctx.accounts.token_program.to_account_info())) // ADD THIS
-        .name("Achievement Badge".to_string())
+        .sysvar_instructions(&ctx.accounts.sysvar_instructions.to_account_info())
+        .spl_token_program(Some(&ctx.accounts.token_program.to_account_info()))
+        .name(badge_type.display_name().to_string())
         .symbol("BADGE".to_string())
         .uri(badge.metadata_uri.clone())
         .seller_fee_basis_points(0)
         .token_standard(TokenStandard::NonFungible)
         .print_supply(PrintSupply::Zero)
-        .invoke()?;
+        .invoke_signed(&[authority_seeds])?;
+
+    let reputation_points = badge_type.reputation_points();
+    user_stats.add_badge(badge_type as u8);
+    user_stats.add_reputation(reputation_points);
+    user_stats.last_activity = current_time;
 
     emit!(BadgeEarned {
         user: badge.user,
@@ -87,5 +161,7 @@ pub fn handler(ctx: Context<MintBadge>, badge_type: BadgeType) -> Result<()> {
         mint: badge.mint,
     });
 
+    msg!("✅ Badge issued: {} (+{} reputation)", badge_type.display_name(), reputation_points);
+
     Ok(())
 }
\ No newline at end of file
@@ -4,7 +4,7 @@ use anchor_spl::token::{Mint, Token, TokenAccount, mint_to, MintTo};
 use anchor_spl::associated_token::AssociatedToken;
 use mpl_token_metadata::instructions::CreateV1CpiBuilder;
 use mpl_token_metadata::types::{TokenStandard, PrintSupply};
-use crate::state::{Coupon, Promotion, Merchant, Marketplace, UserStats, BadgeType, ReputationTier};
+use crate::state::{Coupon, Promotion, Merchant, MerchantStaff, StaffRole, Marketplace, UserStats, BadgeType, ReputationTier, CouponMetadataState, IssuanceMode};
 use crate::errors::CouponError;
 use crate::events::CouponMinted;
 
@@ -24,8 +24,8 @@ pub struct MintCoupon<'info> {
         init,
         payer = payer,
         mint::decimals = 0,
-        mint::authority = authority,
-        mint::freeze_authority = authority
+        mint::authority = coupon_authority,
+        mint::freeze_authority = coupon_authority
     )]
     pub nft_mint: Account<'info, Mint>,
     
@@ -46,14 +46,40 @@ pub struct MintCoupon<'info> {
     #[account(mut)]
     pub master_edition: UncheckedAccount<'info>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant,
+        constraint = promotion.issuance_mode == IssuanceMode::TokenMetadata @ CouponError::WrongIssuanceMode
+    )]
     pub promotion: Account<'info, Promotion>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = merchant.is_active @ CouponError::MerchantInactive
+    )]
     pub merchant: Account<'info, Merchant>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = !marketplace.is_paused @ CouponError::MarketplacePaused
+    )]
     pub marketplace: Account<'info, Marketplace>,
+
+    /// Present when a staff key rather than the merchant authority signs
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub staff: Option<Account<'info, MerchantStaff>>,
+
+    /// CHECK: Program PDA that holds mint, freeze and update authority over coupon NFTs
+    #[account(
+        seeds = [b"coupon_authority"],
+        bump
+    )]
+    pub coupon_authority: UncheckedAccount<'info>,
     
     /// CHECK: Recipient of the NFT
     pub recipient: UncheckedAccount<'info>,
@@ -70,11 +96,13 @@ pub struct MintCoupon<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     
+    /// Merchant authority or a staff key allowed to manage promotions
     pub authority: Signer<'info>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     
     /// CHECK: Metaplex Token Metadata Program
+    #[account(address = mpl_token_metadata::ID)]
     pub token_metadata_program: UncheckedAccount<'info>,
     
     /// CHECK: Sysvar Instructions
@@ -86,6 +114,12 @@ pub struct MintCoupon<'info> {
 }
 
 pub fn handler(ctx: Context<MintCoupon>, coupon_id: u64) -> Result<()> {
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.staff.as_deref(),
+        StaffRole::can_manage_promotions,
+    )?;
+
     let promotion = &mut ctx.accounts.promotion;
     require!(promotion.is_active, CouponError::PromotionInactive);
     require!(promotion.current_supply < promotion.max_supply, CouponError::SupplyExhausted);
@@ -102,6 +136,7 @@ pub fn handler(ctx: Context<MintCoupon>, coupon_id: u64) -> Result<()> {
         user_stats.total_ratings_given = 0;
         user_stats.total_comments = 0;
         user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
         user_stats.reputation_score = 0;
         user_stats.tier = ReputationTier::Bronze;
         user_stats.badges_earned = Vec::new();
@@ -128,19 +163,26 @@ pub fn handler(ctx: Context<MintCoupon>, coupon_id: u64) -> Result<()> {
     coupon.expiry_timestamp = promotion.expiry_timestamp;
     coupon.is_redeemed = false;
     coupon.redeemed_at = 0;
+    coupon.fill(promotion.redemption_model);
     coupon.created_at = current_time;
     coupon.mint = Some(ctx.accounts.nft_mint.key());
-    coupon.metadata_uri = "https://example.com/metadata.json".to_string();
+    coupon.is_escrowed = false;
+    coupon.serial_number = promotion.current_supply + 1;
+    coupon.metadata_uri = promotion.coupon_uri(coupon.serial_number, CouponMetadataState::Active);
+
+    // Mint NFT to recipient, signed by the program's coupon authority
+    let authority_seeds: &[&[u8]] = &[b"coupon_authority", &[ctx.bumps.coupon_authority]];
+    let authority_info = ctx.accounts.coupon_authority.to_account_info();
 
-    // Mint NFT to recipient
     mint_to(
-        CpiContext::new(
+        CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             MintTo {
                 mint: ctx.accounts.nft_mint.to_account_info(),
                 to: ctx.accounts.token_account.to_account_info(),
-                authority: ctx.accounts.authority.to_account_info(),
+                authority: authority_info.clone(),
             },
+            &[authority_seeds],
         ),
         1,
     )?;
@@ -150,19 +192,19 @@ pub fn handler(ctx: Context<MintCoupon>, coupon_id: u64) -> Result<()> {
         .metadata(&ctx.accounts.metadata.to_account_info())
         .master_edition(Some(&ctx.accounts.master_edition.to_account_info()))
         .mint(&ctx.accounts.nft_mint.to_account_info(), true)
-        .authority(&ctx.accounts.authority.to_account_info())
+        .authority(&authority_info)
         .payer(&ctx.accounts.payer.to_account_info())
-        .update_authority(&ctx.accounts.authority.to_account_info(), true)
+        .update_authority(&authority_info, true)
         .system_program(&ctx.accounts.system_program.to_account_info())
         .sysvar_instructions(&ctx.accounts.sysvar_instructions.to_account_info())
         .spl_token_program(Some(&ctx.accounts.token_program.to_account_info()))
-        .name("Discount Coupon".to_string())
-        .symbol("DC".to_string())
+        .name(promotion.nft_name.clone())
+        .symbol(promotion.nft_symbol.clone())
         .uri(coupon.metadata_uri.clone())
-        .seller_fee_basis_points(0)
+        .seller_fee_basis_points(promotion.royalty_basis_points)
         .token_standard(TokenStandard::NonFungible)
         .print_supply(PrintSupply::Zero)
-        .invoke()?;
+        .invoke_signed(&[authority_seeds])?;
 
     promotion.current_supply += 1;
     ctx.accounts.merchant.total_coupons_created += 1;
@@ -175,6 +217,7 @@ pub fn handler(ctx: Context<MintCoupon>, coupon_id: u64) -> Result<()> {
         recipient: ctx.accounts.recipient.key(),
         merchant: coupon.merchant,
         discount_percentage: coupon.discount_percentage,
+        merchant_verification: ctx.accounts.merchant.verification_status(current_time),
     });
 
     msg!("Coupon minted! Purchases: {} | Reputation: {} | Tier: {:?}", 
@@ -3,8 +3,11 @@ pub mod initialize;
 pub mod register_merchant;
 pub mod create_promotion;
 pub mod mint_coupon;
+pub mod purchase_coupon;
+pub mod core_coupons;
 pub mod transfer_coupon;
 pub mod redeem_coupon;
+pub mod expire_coupon;
 pub mod list_for_sale;
 pub mod buy_listing;
 pub mod mint_badge;
@@ -19,6 +22,10 @@ pub mod auto_award_badge;
 pub mod redemption_tickets;
 pub mod group_deals;
 pub mod auctions;
+pub mod marketplace_admin;
+pub mod promotion_admin;
+pub mod merchant_admin;
+pub mod merchant_staff;
 
 
 
@@ -27,8 +34,11 @@ pub use initialize::*;
 pub use register_merchant::*;
 pub use create_promotion::*;
 pub use mint_coupon::*;
+pub use purchase_coupon::*;
+pub use core_coupons::*;
 pub use transfer_coupon::*;
 pub use redeem_coupon::*;
+pub use expire_coupon::*;
 pub use list_for_sale::*;
 pub use buy_listing::*;
 pub use mint_badge::*;
@@ -42,4 +52,8 @@ pub use claim_rewards::*;
 pub use auto_award_badge::*;
 pub use redemption_tickets::*;
 pub use group_deals::*;
-pub use auctions::*;
\ No newline at end of file
+pub use auctions::*;
+pub use marketplace_admin::*;
+pub use promotion_admin::*;
+pub use merchant_admin::*;
+pub use merchant_staff::*;
\ No newline at end of file
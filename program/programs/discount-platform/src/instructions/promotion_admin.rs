@@ -0,0 +1,350 @@
+// src/instructions/promotion_admin.rs
+use anchor_lang::prelude::*;
+use crate::state::{Promotion, Merchant, MerchantStaff, StaffRole, RedemptionTicket};
+use crate::errors::CouponError;
+use crate::events::{
+    PromotionUpdated,
+    ResaleTermsUpdated,
+    TicketValidityUpdated,
+    DiscountCapUpdated,
+    PromotionPaused,
+    PromotionResumed,
+    PromotionClosed,
+};
+
+// ============================================================================
+// Update Promotion (expiry, supply, price, description, transfer policy)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct UpdatePromotion<'info> {
+    #[account(
+        mut,
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    #[account(
+        constraint = merchant.authority == authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn update_promotion(
+    ctx: Context<UpdatePromotion>,
+    expiry_timestamp: Option<i64>,
+    max_supply: Option<u32>,
+    price: Option<u64>,
+    description: Option<String>,
+    transferable: Option<bool>,
+    resale_allowed: Option<bool>,
+) -> Result<()> {
+    let promotion = &mut ctx.accounts.promotion;
+    let now = Clock::get()?.unix_timestamp;
+
+    if let Some(expiry) = expiry_timestamp {
+        require!(expiry > now, CouponError::InvalidExpiry);
+        // Minted coupons keep the expiry they were issued with, so it can't move earlier
+        require!(
+            promotion.current_supply == 0 || expiry >= promotion.expiry_timestamp,
+            CouponError::InvalidExpiry
+        );
+        promotion.expiry_timestamp = expiry;
+    }
+
+    if let Some(supply) = max_supply {
+        // Coupons already minted can't be taken back
+        require!(supply > 0 && supply >= promotion.current_supply, CouponError::InvalidSupply);
+        promotion.max_supply = supply;
+    }
+
+    if let Some(new_price) = price {
+        promotion.price = new_price;
+    }
+
+    if let Some(text) = description {
+        require!(text.len() <= 200, CouponError::DescriptionTooLong);
+        promotion.description = text;
+    }
+
+    // Holders bought under the current policy, so after the first mint it can only loosen
+    if let Some(allowed) = transferable {
+        require!(
+            promotion.current_supply == 0 || allowed || !promotion.transferable,
+            CouponError::TransferPolicyLocked
+        );
+        promotion.transferable = allowed;
+    }
+
+    if let Some(allowed) = resale_allowed {
+        require!(
+            promotion.current_supply == 0 || allowed || !promotion.resale_allowed,
+            CouponError::TransferPolicyLocked
+        );
+        promotion.resale_allowed = allowed;
+    }
+
+    // A coupon that can't change hands can't be resold either
+    require!(promotion.transferable || !promotion.resale_allowed, CouponError::CouponNotTransferable);
+    promotion.require_enforceable_terms()?;
+
+    emit!(PromotionUpdated {
+        promotion: promotion.key(),
+        merchant: promotion.merchant,
+        max_supply: promotion.max_supply,
+        expiry_timestamp: promotion.expiry_timestamp,
+        price: promotion.price,
+        transferable: promotion.transferable,
+        resale_allowed: promotion.resale_allowed,
+        timestamp: now,
+    });
+
+    msg!("✅ Promotion updated");
+
+    Ok(())
+}
+
+// ============================================================================
+// Set Resale Terms (merchant royalty, resale price cap)
+// ============================================================================
+
+pub fn set_resale_terms(
+    ctx: Context<UpdatePromotion>,
+    royalty_basis_points: u16,
+    max_resale_bps: Option<u16>,
+) -> Result<()> {
+    require!(
+        royalty_basis_points <= Promotion::MAX_ROYALTY_BASIS_POINTS,
+        CouponError::InvalidRoyalty
+    );
+    require!(max_resale_bps != Some(0), CouponError::InvalidPrice);
+
+    let promotion = &mut ctx.accounts.promotion;
+    // Minted coupons carry the royalty in their metadata, so it is fixed from the first mint
+    require!(
+        promotion.current_supply == 0 || royalty_basis_points == promotion.royalty_basis_points,
+        CouponError::RoyaltyLocked
+    );
+    promotion.royalty_basis_points = royalty_basis_points;
+    promotion.max_resale_bps = max_resale_bps;
+    promotion.require_enforceable_terms()?;
+
+    emit!(ResaleTermsUpdated {
+        promotion: promotion.key(),
+        merchant: promotion.merchant,
+        royalty_basis_points,
+        max_resale_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Resale terms updated: {} bps royalty", royalty_basis_points);
+
+    Ok(())
+}
+
+// ============================================================================
+// Set Ticket Validity (how long redemption tickets stay valid)
+// ============================================================================
+
+pub fn set_ticket_validity(ctx: Context<UpdatePromotion>, validity_seconds: u32) -> Result<()> {
+    require!(
+        (RedemptionTicket::MIN_VALIDITY_SECONDS..=RedemptionTicket::MAX_VALIDITY_SECONDS)
+            .contains(&validity_seconds),
+        CouponError::InvalidTicketValidity
+    );
+
+    let promotion = &mut ctx.accounts.promotion;
+    promotion.ticket_validity_seconds = validity_seconds;
+
+    emit!(TicketValidityUpdated {
+        promotion: promotion.key(),
+        merchant: promotion.merchant,
+        ticket_validity_seconds: validity_seconds,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Redemption tickets now valid for {} seconds", validity_seconds);
+
+    Ok(())
+}
+
+// ============================================================================
+// Set Discount Cap (most a single redemption can save)
+// ============================================================================
+
+pub fn set_discount_cap(ctx: Context<UpdatePromotion>, max_discount_cap: Option<u64>) -> Result<()> {
+    require!(max_discount_cap != Some(0), CouponError::InvalidDiscount);
+
+    let promotion = &mut ctx.accounts.promotion;
+    promotion.max_discount_cap = max_discount_cap;
+
+    emit!(DiscountCapUpdated {
+        promotion: promotion.key(),
+        merchant: promotion.merchant,
+        max_discount_cap,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Discount cap updated: {:?}", max_discount_cap);
+
+    Ok(())
+}
+
+// ============================================================================
+// Pause / Resume Promotion
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct SetPromotionActive<'info> {
+    #[account(
+        mut,
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    pub merchant: Account<'info, Merchant>,
+
+    /// Present when a manager-level staff key signs instead of the merchant authority
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub staff: Option<Account<'info, MerchantStaff>>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn pause_promotion(ctx: Context<SetPromotionActive>) -> Result<()> {
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.staff.as_deref(),
+        StaffRole::can_manage_promotions,
+    )?;
+
+    let promotion = &mut ctx.accounts.promotion;
+    require!(promotion.is_active, CouponError::PromotionInactive);
+
+    promotion.is_active = false;
+
+    emit!(PromotionPaused {
+        promotion: promotion.key(),
+        merchant: promotion.merchant,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Promotion paused");
+
+    Ok(())
+}
+
+pub fn resume_promotion(ctx: Context<SetPromotionActive>) -> Result<()> {
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.staff.as_deref(),
+        StaffRole::can_manage_promotions,
+    )?;
+
+    let promotion = &mut ctx.accounts.promotion;
+    require!(!promotion.is_active, CouponError::PromotionAlreadyActive);
+
+    promotion.is_active = true;
+
+    emit!(PromotionResumed {
+        promotion: promotion.key(),
+        merchant: promotion.merchant,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Promotion resumed");
+
+    Ok(())
+}
+
+// ============================================================================
+// Close Promotion (after expiry, rent back to the merchant)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct ClosePromotion<'info> {
+    #[account(
+        mut,
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant,
+        close = authority
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    #[account(
+        constraint = merchant.authority == authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn close_promotion(ctx: Context<ClosePromotion>) -> Result<()> {
+    let promotion = &ctx.accounts.promotion;
+    let now = Clock::get()?.unix_timestamp;
+
+    // Coupons outlive a sold-out promotion and still read it on redeem and
+    // resale, so it can only go once they have all expired
+    require!(promotion.expiry_timestamp <= now, CouponError::PromotionStillLive);
+    require!(promotion.open_escrows == 0, CouponError::PromotionHasOpenEscrows);
+
+    emit!(PromotionClosed {
+        promotion: promotion.key(),
+        merchant: promotion.merchant,
+        coupons_minted: promotion.current_supply,
+        timestamp: now,
+    });
+
+    msg!("✅ Promotion closed, rent returned to merchant");
+
+    Ok(())
+}
+
+// ============================================================================
+// Handlers for lib.rs
+// ============================================================================
+
+pub fn handler_update_promotion(
+    ctx: Context<UpdatePromotion>,
+    expiry_timestamp: Option<i64>,
+    max_supply: Option<u32>,
+    price: Option<u64>,
+    description: Option<String>,
+    transferable: Option<bool>,
+    resale_allowed: Option<bool>,
+) -> Result<()> {
+    update_promotion(ctx, expiry_timestamp, max_supply, price, description, transferable, resale_allowed)
+}
+
+pub fn handler_set_resale_terms(
+    ctx: Context<UpdatePromotion>,
+    royalty_basis_points: u16,
+    max_resale_bps: Option<u16>,
+) -> Result<()> {
+    set_resale_terms(ctx, royalty_basis_points, max_resale_bps)
+}
+
+pub fn handler_set_ticket_validity(ctx: Context<UpdatePromotion>, validity_seconds: u32) -> Result<()> {
+    set_ticket_validity(ctx, validity_seconds)
+}
+
+pub fn handler_set_discount_cap(ctx: Context<UpdatePromotion>, max_discount_cap: Option<u64>) -> Result<()> {
+    set_discount_cap(ctx, max_discount_cap)
+}
+
+pub fn handler_pause_promotion(ctx: Context<SetPromotionActive>) -> Result<()> {
+    pause_promotion(ctx)
+}
+
+pub fn handler_resume_promotion(ctx: Context<SetPromotionActive>) -> Result<()> {
+    resume_promotion(ctx)
+}
+
+pub fn handler_close_promotion(ctx: Context<ClosePromotion>) -> Result<()> {
+    close_promotion(ctx)
+}
@@ -0,0 +1,307 @@
+// src/instructions/purchase_coupon.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount, mint_to, MintTo};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    Mint as PaymentMint,
+    TokenAccount as PaymentTokenAccount,
+    TokenInterface,
+};
+use mpl_token_metadata::instructions::CreateV1CpiBuilder;
+use mpl_token_metadata::types::{TokenStandard, PrintSupply};
+use crate::state::{
+    Coupon,
+    Promotion,
+    Merchant,
+    Marketplace,
+    RevenueConfig,
+    UserStats,
+    BadgeType,
+    ReputationTier,
+    CouponMetadataState,
+    IssuanceMode,
+};
+use crate::errors::CouponError;
+use crate::utils::{PaymentRail, MerchantRevenue};
+use crate::events::{CouponMinted, CouponPurchased};
+
+/// Self-serve primary sale: the buyer pays `promotion.price`, up to their
+/// `max_price`, and the program mints the coupon NFT through its own PDA, no
+/// merchant signature needed.
+#[derive(Accounts)]
+pub struct PurchaseCoupon<'info> {
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + Coupon::INIT_SPACE,
+        seeds = [b"coupon", promotion.key().as_ref(), &promotion.current_supply.to_le_bytes()],
+        bump
+    )]
+    pub coupon: Box<Account<'info, Coupon>>,
+
+    #[account(
+        init,
+        payer = buyer,
+        mint::decimals = 0,
+        mint::authority = coupon_authority,
+        mint::freeze_authority = coupon_authority
+    )]
+    pub nft_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = buyer,
+        associated_token::mint = nft_mint,
+        associated_token::authority = buyer
+    )]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Metadata account, created by the Token Metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Master Edition account, created by the Token Metadata program
+    #[account(mut)]
+    pub master_edition: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = promotion.merchant == merchant.key() @ CouponError::WrongMerchant,
+        constraint = promotion.issuance_mode == IssuanceMode::TokenMetadata @ CouponError::WrongIssuanceMode
+    )]
+    pub promotion: Box<Account<'info, Promotion>>,
+
+    #[account(
+        mut,
+        constraint = merchant.is_active @ CouponError::MerchantInactive
+    )]
+    pub merchant: Box<Account<'info, Merchant>>,
+
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = !marketplace.is_paused @ CouponError::MarketplacePaused
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>,
+
+    /// CHECK: Program PDA that holds mint, freeze and update authority over coupon NFTs
+    #[account(
+        seeds = [b"coupon_authority"],
+        bump
+    )]
+    pub coupon_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", buyer.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Box<Account<'info, UserStats>>,
+
+    /// CHECK: Merchant receives the sale proceeds when no revenue split is configured
+    #[account(
+        mut,
+        constraint = merchant.authority == merchant_authority.key() @ CouponError::NotMerchantAuthority
+    )]
+    pub merchant_authority: UncheckedAccount<'info>,
+
+    /// Required when the merchant has a revenue split; recipient endpoints
+    /// follow in remaining accounts
+    #[account(
+        seeds = [b"revenue_config", merchant.key().as_ref()],
+        bump
+    )]
+    pub revenue_config: Option<Account<'info, RevenueConfig>>,
+
+    /// CHECK: Fee treasury PDA
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    // SPL payment accounts, only required when the promotion has a payment mint
+    pub payment_mint: Option<InterfaceAccount<'info, PaymentMint>>,
+    #[account(mut)]
+    pub buyer_payment_account: Option<InterfaceAccount<'info, PaymentTokenAccount>>,
+    #[account(mut)]
+    pub merchant_payment_account: Option<InterfaceAccount<'info, PaymentTokenAccount>>,
+    #[account(mut)]
+    pub treasury_payment_account: Option<InterfaceAccount<'info, PaymentTokenAccount>>,
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Metaplex Token Metadata Program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: Sysvar Instructions
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, PurchaseCoupon<'info>>,
+    max_price: u64,
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+
+    let promotion = &ctx.accounts.promotion;
+    require!(promotion.is_active, CouponError::PromotionInactive);
+    require!(promotion.current_supply < promotion.max_supply, CouponError::SupplyExhausted);
+    require!(promotion.expiry_timestamp > current_time, CouponError::PromotionExpired);
+    // The merchant can reprice at any time; never charge more than the buyer agreed to
+    require!(promotion.price <= max_price, CouponError::PriceAboveMax);
+
+    // Take payment: marketplace fee to the treasury, the rest to the merchant
+    let price = promotion.price;
+    let marketplace_fee = ctx.accounts.marketplace.calculate_fee(price);
+    let merchant_share = price - marketplace_fee;
+
+    let rail = PaymentRail::resolve(
+        promotion.payment_mint,
+        &ctx.accounts.payment_mint,
+        &ctx.accounts.payment_token_program,
+        &ctx.accounts.system_program,
+    )?;
+    let buyer_info = ctx.accounts.buyer.to_account_info();
+    let from = rail.endpoint(&buyer_info, &ctx.accounts.buyer_payment_account)?;
+    let merchant_to = rail.endpoint(
+        &ctx.accounts.merchant_authority.to_account_info(),
+        &ctx.accounts.merchant_payment_account,
+    )?;
+    let treasury_to = rail.endpoint(&ctx.accounts.treasury.to_account_info(), &ctx.accounts.treasury_payment_account)?;
+
+    rail.transfer(&from, &treasury_to, &buyer_info, marketplace_fee, &[])?;
+    if !rail.is_token() {
+        ctx.accounts.marketplace.record_fee(marketplace_fee);
+    }
+
+    MerchantRevenue {
+        merchant: &ctx.accounts.merchant,
+        merchant_to: &merchant_to,
+        revenue_config: ctx.accounts.revenue_config.as_deref(),
+        recipient_accounts: ctx.remaining_accounts,
+        source: promotion.key(),
+    }
+    .distribute(&rail, &from, &buyer_info, merchant_share, &[])?;
+
+    // Initialize UserStats if first time
+    let user_stats = &mut ctx.accounts.user_stats;
+    if user_stats.user == Pubkey::default() {
+        user_stats.user = ctx.accounts.buyer.key();
+        user_stats.total_purchases = 0;
+        user_stats.total_redemptions = 0;
+        user_stats.total_ratings_given = 0;
+        user_stats.total_comments = 0;
+        user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
+        user_stats.reputation_score = 0;
+        user_stats.tier = ReputationTier::Bronze;
+        user_stats.badges_earned = Vec::new();
+        user_stats.joined_at = current_time;
+        user_stats.last_activity = current_time;
+    }
+
+    // Update user stats for purchase
+    user_stats.total_purchases += 1;
+    user_stats.add_reputation(5); // 5 points per purchase
+    user_stats.last_activity = current_time;
+
+    if user_stats.total_purchases == 1 && !user_stats.has_badge(BadgeType::FirstPurchase as u8) {
+        msg!("🏆 User eligible for FirstPurchase badge! Total purchases: {}", user_stats.total_purchases);
+    }
+
+    let coupon = &mut ctx.accounts.coupon;
+    coupon.id = promotion.current_supply as u64 + 1;
+    coupon.promotion = promotion.key();
+    coupon.owner = ctx.accounts.buyer.key();
+    coupon.merchant = ctx.accounts.merchant.key();
+    coupon.discount_percentage = promotion.discount_percentage;
+    coupon.expiry_timestamp = promotion.expiry_timestamp;
+    coupon.is_redeemed = false;
+    coupon.redeemed_at = 0;
+    coupon.fill(promotion.redemption_model);
+    coupon.created_at = current_time;
+    coupon.mint = Some(ctx.accounts.nft_mint.key());
+    coupon.is_escrowed = false;
+    coupon.serial_number = promotion.current_supply + 1;
+    coupon.metadata_uri = promotion.coupon_uri(coupon.serial_number, CouponMetadataState::Active);
+
+    // Mint and describe the NFT, signed by the program's coupon authority
+    let authority_seeds: &[&[u8]] = &[b"coupon_authority", &[ctx.bumps.coupon_authority]];
+    let authority_info = ctx.accounts.coupon_authority.to_account_info();
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.nft_mint.to_account_info(),
+                to: ctx.accounts.token_account.to_account_info(),
+                authority: authority_info.clone(),
+            },
+            &[authority_seeds],
+        ),
+        1,
+    )?;
+
+    CreateV1CpiBuilder::new(&ctx.accounts.token_metadata_program.to_account_info())
+        .metadata(&ctx.accounts.metadata.to_account_info())
+        .master_edition(Some(&ctx.accounts.master_edition.to_account_info()))
+        .mint(&ctx.accounts.nft_mint.to_account_info(), true)
+        .authority(&authority_info)
+        .payer(&buyer_info)
+        .update_authority(&authority_info, true)
+        .system_program(&ctx.accounts.system_program.to_account_info())
+        .sysvar_instructions(&ctx.accounts.sysvar_instructions.to_account_info())
+        .spl_token_program(Some(&ctx.accounts.token_program.to_account_info()))
+        .name(promotion.nft_name.clone())
+        .symbol(promotion.nft_symbol.clone())
+        .uri(coupon.metadata_uri.clone())
+        .seller_fee_basis_points(promotion.royalty_basis_points)
+        .token_standard(TokenStandard::NonFungible)
+        .print_supply(PrintSupply::Zero)
+        .invoke_signed(&[authority_seeds])?;
+
+    let promotion = &mut ctx.accounts.promotion;
+    promotion.current_supply += 1;
+    ctx.accounts.merchant.total_coupons_created += 1;
+    ctx.accounts.marketplace.total_coupons += 1;
+
+    emit!(CouponMinted {
+        coupon: coupon.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        promotion: promotion.key(),
+        recipient: coupon.owner,
+        merchant: coupon.merchant,
+        discount_percentage: coupon.discount_percentage,
+        merchant_verification: ctx.accounts.merchant.verification_status(current_time),
+    });
+
+    emit!(CouponPurchased {
+        coupon: coupon.key(),
+        promotion: promotion.key(),
+        buyer: coupon.owner,
+        merchant: coupon.merchant,
+        price,
+        marketplace_fee,
+        payment_mint: promotion.payment_mint,
+        timestamp: current_time,
+    });
+
+    msg!("✅ Coupon purchased for {} (fee {})", price, marketplace_fee);
+
+    Ok(())
+}
@@ -1,15 +1,23 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount, Mint, burn, Burn};
-use crate::state::{Coupon, Merchant, UserStats, BadgeType,ReputationTier};
+use crate::state::{Coupon, Promotion, Merchant, MerchantStaff, StaffRole, UserStats, BadgeType,ReputationTier, CouponMetadataState, IssuanceMode};
+use crate::utils::CouponMetadataCpi;
 use crate::errors::CouponError;
-use crate::events::CouponRedeemed;
+use crate::events::{CouponRedeemed, CouponPartiallyRedeemed};
 
-pub fn handler(ctx: Context<RedeemCoupon>) -> Result<()> {
+pub fn handler(ctx: Context<RedeemCoupon>, amount: u64, order_amount: u64) -> Result<()> {
     let coupon = &mut ctx.accounts.coupon;
     require!(!coupon.is_redeemed, CouponError::CouponAlreadyRedeemed);
     require!(coupon.expiry_timestamp > Clock::get()?.unix_timestamp, CouponError::CouponExpired);
     require!(coupon.owner == ctx.accounts.user.key(), CouponError::NotCouponOwner);
     require!(coupon.merchant == ctx.accounts.merchant.key(), CouponError::WrongMerchant);
+    // Location-based promotions go through redemption tickets, which record where the holder is
+    require!(!ctx.accounts.promotion.is_location_based, CouponError::LocationNotSupported);
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.merchant_authority.key(),
+        ctx.accounts.staff.as_deref(),
+        StaffRole::can_redeem,
+    )?;
 
     let current_time = Clock::get()?.unix_timestamp;
 
@@ -22,6 +30,7 @@ pub fn handler(ctx: Context<RedeemCoupon>) -> Result<()> {
         user_stats.total_ratings_given = 0;
         user_stats.total_comments = 0;
         user_stats.total_listings = 0;
+        user_stats.total_savings = 0;
         user_stats.reputation_score = 0;
         user_stats.tier = ReputationTier::Bronze;
         user_stats.badges_earned = Vec::new();
@@ -43,6 +52,40 @@ pub fn handler(ctx: Context<RedeemCoupon>) -> Result<()> {
         msg!("🏆 User eligible for FiftyRedemptions badge! Total redemptions: {}", user_stats.total_redemptions);
     }
 
+    // Lifetime savings are kept in lamports, so only SOL-priced promotions count
+    let promotion = &ctx.accounts.promotion;
+    let savings = promotion.savings(coupon.discount_percentage, order_amount);
+    if promotion.payment_mint.is_none() {
+        let merchant = &mut ctx.accounts.merchant;
+        merchant.total_savings_given = merchant.total_savings_given.saturating_add(savings);
+        if user_stats.add_savings(savings) && !user_stats.has_badge(BadgeType::SavedOneSol as u8) {
+            msg!("🏆 User eligible for SavedOneSol badge! Total saved: {} lamports", user_stats.total_savings);
+        }
+    }
+
+    // Multi-use and stored-value coupons stay with the holder until exhausted
+    if !coupon.consume(amount)? {
+        emit!(CouponPartiallyRedeemed {
+            coupon: coupon.key(),
+            nft_mint: ctx.accounts.nft_mint.key(),
+            user: coupon.owner,
+            merchant: coupon.merchant,
+            amount,
+            uses_remaining: coupon.uses_remaining,
+            balance_remaining: coupon.balance_remaining,
+            approved_by: ctx.accounts.merchant_authority.key(),
+            order_amount,
+            savings,
+            payment_mint: promotion.payment_mint,
+            timestamp: current_time,
+        });
+
+        msg!("Partial redemption: {} uses / {} balance left",
+            coupon.uses_remaining, coupon.balance_remaining);
+
+        return Ok(());
+    }
+
     // Mark as redeemed
     coupon.is_redeemed = true;
     coupon.redeemed_at = current_time;
@@ -51,6 +94,19 @@ pub fn handler(ctx: Context<RedeemCoupon>) -> Result<()> {
     let merchant = &mut ctx.accounts.merchant;
     merchant.total_coupons_redeemed += 1;
 
+    // Show the redeemed state in wallets before the token is burned
+    let metadata_cpi = CouponMetadataCpi {
+        token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        mint: ctx.accounts.nft_mint.to_account_info(),
+        coupon_authority: ctx.accounts.coupon_authority.to_account_info(),
+        coupon_authority_bump: ctx.bumps.coupon_authority,
+        payer: ctx.accounts.user.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+    };
+    metadata_cpi.set_state(coupon, &ctx.accounts.promotion, CouponMetadataState::Redeemed)?;
+
     // Burn the NFT
     burn(
         CpiContext::new(
@@ -71,6 +127,10 @@ pub fn handler(ctx: Context<RedeemCoupon>) -> Result<()> {
         merchant: coupon.merchant,
         discount_percentage: coupon.discount_percentage,
         redemption_code: format!("REDEEMED-{}", coupon.id),
+        approved_by: ctx.accounts.merchant_authority.key(),
+        order_amount,
+        savings,
+        payment_mint: ctx.accounts.promotion.payment_mint,
         timestamp: coupon.redeemed_at,
     });
 
@@ -85,13 +145,34 @@ pub struct RedeemCoupon<'info> {
     #[account(
         mut,
         constraint = coupon.owner == user.key() @ CouponError::NotCouponOwner,
-        constraint = coupon.merchant == merchant.key() @ CouponError::WrongMerchant
+        constraint = coupon.merchant == merchant.key() @ CouponError::WrongMerchant,
+        constraint = !coupon.is_escrowed @ CouponError::CouponEscrowed
     )]
     pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon,
+        constraint = promotion.issuance_mode == IssuanceMode::TokenMetadata @ CouponError::WrongIssuanceMode
+    )]
+    pub promotion: Account<'info, Promotion>,
     
     /// CHECK: SPL Token Mint
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
     pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Metadata account, validated by the Token Metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Program PDA that holds update authority over coupon NFTs
+    #[account(
+        seeds = [b"coupon_authority"],
+        bump
+    )]
+    pub coupon_authority: UncheckedAccount<'info>,
     
     /// CHECK: SPL Token Account
     #[account(
@@ -103,9 +184,16 @@ pub struct RedeemCoupon<'info> {
     
     #[account(
         mut,
-        constraint = merchant.authority == merchant_authority.key() @ CouponError::NotMerchantAuthority
+        constraint = merchant.is_active @ CouponError::MerchantInactive
     )]
     pub merchant: Account<'info, Merchant>,
+
+    /// Present when a staff key rather than the merchant authority co-signs
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), merchant_authority.key().as_ref()],
+        bump
+    )]
+    pub staff: Option<Account<'info, MerchantStaff>>,
     
     #[account(
         init_if_needed,
@@ -118,7 +206,17 @@ pub struct RedeemCoupon<'info> {
     
     #[account(mut)]
     pub user: Signer<'info>,
+    /// Merchant authority or a staff key with an active entry
     pub merchant_authority: Signer<'info>,
     pub token_program: Program<'info, Token>,
+
+    /// CHECK: Metaplex Token Metadata Program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: Sysvar Instructions
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
\ No newline at end of file
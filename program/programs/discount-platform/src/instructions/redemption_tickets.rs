@@ -3,15 +3,31 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount, Mint, burn, Burn, close_account, CloseAccount};
 use crate::state::{
     Coupon, 
+    Promotion,
+    IssuanceMode,
     Merchant, 
+    MerchantStaff,
+    StaffRole,
     UserStats, 
     RedemptionTicket, 
     RedemptionLocation,
+    Location,
+    validate_coordinates,
+    RedemptionModel,
     BadgeType,
     ReputationTier,
+    CouponMetadataState,
 };
 use crate::errors::CouponError;
-use crate::events::{TicketGenerated, TicketRedeemed, CouponRedeemed};
+use crate::utils::{CouponMetadataCpi, require_ed25519_signature};
+use crate::events::{
+    TicketGenerated,
+    TicketRedeemed,
+    TicketClosed,
+    SignedTicketRedeemed,
+    CouponRedeemed,
+    CouponPartiallyRedeemed,
+};
 
 // ============================================================================
 // Generate Redemption Ticket (User Side)
@@ -37,9 +53,16 @@ pub struct GenerateRedemptionTicket<'info> {
     #[account(
         constraint = coupon.owner == user.key() @ CouponError::NotCouponOwner,
         constraint = !coupon.is_redeemed @ CouponError::CouponAlreadyRedeemed,
+        constraint = !coupon.is_escrowed @ CouponError::CouponEscrowed,
         constraint = coupon.expiry_timestamp > Clock::get()?.unix_timestamp @ CouponError::CouponExpired
     )]
     pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon,
+        constraint = promotion.issuance_mode == IssuanceMode::TokenMetadata @ CouponError::WrongIssuanceMode
+    )]
+    pub promotion: Account<'info, Promotion>,
     
     #[account(mut)]
     pub user: Signer<'info>,
@@ -68,14 +91,13 @@ pub fn generate_redemption_ticket(
     ticket.merchant = coupon.merchant;
     ticket.ticket_hash = ticket_hash;
     ticket.created_at = clock.unix_timestamp;
-    ticket.expires_at = clock.unix_timestamp + 300; // 5 minutes
+    ticket.expires_at = clock.unix_timestamp + ctx.accounts.promotion.ticket_validity_seconds as i64;
     ticket.is_consumed = false;
     ticket.nonce = nonce;
     
     // Optional: Record location where ticket was generated
     if let (Some(lat), Some(lon)) = (latitude, longitude) {
-        require!(lat >= -90.0 && lat <= 90.0, CouponError::InvalidCoordinates);
-        require!(lon >= -180.0 && lon <= 180.0, CouponError::InvalidCoordinates);
+        validate_coordinates(lat, lon)?;
         
         ticket.redemption_location = Some(RedemptionLocation {
             latitude: (lat * 1_000_000.0) as i32,
@@ -110,7 +132,8 @@ pub struct VerifyAndRedeemTicket<'info> {
         mut,
         constraint = !ticket.is_consumed @ CouponError::CouponAlreadyRedeemed,
         constraint = ticket.merchant == merchant.key() @ CouponError::WrongMerchant,
-        constraint = ticket.coupon == coupon.key() @ CouponError::WrongCoupon
+        constraint = ticket.coupon == coupon.key() @ CouponError::WrongCoupon,
+        close = user
     )]
     pub ticket: Account<'info, RedemptionTicket>,
     
@@ -121,6 +144,11 @@ pub struct VerifyAndRedeemTicket<'info> {
         constraint = coupon.merchant == merchant.key() @ CouponError::WrongMerchant
     )]
     pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Account<'info, Promotion>,
     
     /// CHECK: NFT Mint
     #[account(
@@ -128,6 +156,17 @@ pub struct VerifyAndRedeemTicket<'info> {
         constraint = nft_mint.key() == coupon.mint.unwrap() @ CouponError::WrongCoupon
     )]
     pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Metadata account, validated by the Token Metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Program PDA that holds update authority over coupon NFTs
+    #[account(
+        seeds = [b"coupon_authority"],
+        bump
+    )]
+    pub coupon_authority: UncheckedAccount<'info>,
     
     /// CHECK: Token account holding the NFT
     #[account(
@@ -139,9 +178,16 @@ pub struct VerifyAndRedeemTicket<'info> {
     
     #[account(
         mut,
-        constraint = merchant.authority == merchant_authority.key() @ CouponError::NotMerchantAuthority
+        constraint = merchant.is_active @ CouponError::MerchantInactive
     )]
     pub merchant: Account<'info, Merchant>,
+
+    /// Present when a staff key rather than the merchant authority co-signs
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), merchant_authority.key().as_ref()],
+        bump
+    )]
+    pub staff: Option<Account<'info, MerchantStaff>>,
     
     #[account(
         mut,
@@ -157,15 +203,61 @@ pub struct VerifyAndRedeemTicket<'info> {
     )]
     pub user: Signer<'info>,
     
+    /// Merchant authority or a staff key with an active entry
     pub merchant_authority: Signer<'info>,
     pub token_program: Program<'info, Token>,
+
+    /// CHECK: Metaplex Token Metadata Program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: Sysvar Instructions
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn verify_and_redeem_ticket(
-    ctx: Context<VerifyAndRedeemTicket>,
+/// Places a location-based coupon may be redeemed at besides the promotion's
+/// own location: the merchant's address, the co-signing staff member's store
+/// and the stores of any other staff entries passed as remaining accounts
+fn store_locations<'info>(
+    merchant: &Account<'info, Merchant>,
+    staff: Option<&MerchantStaff>,
+    other_staff: &[AccountInfo<'info>],
+) -> Result<Vec<Location>> {
+    let mut stores = Vec::with_capacity(other_staff.len() + 2);
+    if merchant.has_physical_location {
+        stores.push(merchant.location.clone());
+    }
+    if let Some(entry) = staff.filter(|entry| entry.has_store_location) {
+        stores.push(entry.store_location.clone());
+    }
+
+    for account in other_staff {
+        require_keys_eq!(*account.owner, crate::ID, CouponError::WrongMerchant);
+        let entry = MerchantStaff::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+        require_keys_eq!(entry.merchant, merchant.key(), CouponError::WrongMerchant);
+        if entry.is_active && entry.has_store_location {
+            stores.push(entry.store_location.clone());
+        }
+    }
+
+    Ok(stores)
+}
+
+pub fn verify_and_redeem_ticket<'info>(
+    ctx: Context<'_, '_, '_, 'info, VerifyAndRedeemTicket<'info>>,
     expected_hash: [u8; 32],
+    amount: u64,
+    order_amount: u64,
 ) -> Result<()> {
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.merchant_authority.key(),
+        ctx.accounts.staff.as_deref(),
+        StaffRole::can_redeem,
+    )?;
+
     let ticket = &mut ctx.accounts.ticket;
     let coupon = &mut ctx.accounts.coupon;
     let clock = Clock::get()?;
@@ -192,18 +284,24 @@ pub fn verify_and_redeem_ticket(
         computed_hash == ticket.ticket_hash,
         CouponError::InvalidInput
     );
+
+    // Location-based promotions need a ticket generated inside the geofence
+    let promotion = &ctx.accounts.promotion;
+    if promotion.is_location_based {
+        let stores = store_locations(
+            &ctx.accounts.merchant,
+            ctx.accounts.staff.as_deref(),
+            ctx.remaining_accounts,
+        )?;
+        let point = ticket
+            .redemption_location
+            .map(|at| Location::from_microdegrees(at.latitude, at.longitude));
+        promotion.require_within_geofence(point.as_ref(), &stores)?;
+    }
     
-    // Mark ticket as consumed (prevents double-redemption)
+    // Mark ticket as consumed; the account is closed to the user on return
     ticket.is_consumed = true;
     
-    // Mark coupon as redeemed
-    coupon.is_redeemed = true;
-    coupon.redeemed_at = clock.unix_timestamp;
-    
-    // Update merchant stats
-    let merchant = &mut ctx.accounts.merchant;
-    merchant.total_coupons_redeemed += 1;
-    
     // Update user stats
     let user_stats = &mut ctx.accounts.user_stats;
     user_stats.total_redemptions += 1;
@@ -218,7 +316,72 @@ pub fn verify_and_redeem_ticket(
     if user_stats.total_redemptions == 50 && !user_stats.has_badge(BadgeType::FiftyRedemptions as u8) {
         msg!("🏆 User eligible for FiftyRedemptions badge!");
     }
+
+    // Lifetime savings are kept in lamports, so only SOL-priced promotions count
+    let savings = promotion.savings(coupon.discount_percentage, order_amount);
+    if promotion.payment_mint.is_none() {
+        let merchant = &mut ctx.accounts.merchant;
+        merchant.total_savings_given = merchant.total_savings_given.saturating_add(savings);
+        if user_stats.add_savings(savings) && !user_stats.has_badge(BadgeType::SavedOneSol as u8) {
+            msg!("🏆 User eligible for SavedOneSol badge!");
+        }
+    }
+
+    // Multi-use and stored-value coupons stay with the holder until exhausted;
+    // each use takes a fresh ticket
+    if !coupon.consume(amount)? {
+        emit!(TicketRedeemed {
+            ticket: ticket.key(),
+            coupon: coupon.key(),
+            nft_mint: ctx.accounts.nft_mint.key(),
+            user: ticket.user,
+            merchant: ctx.accounts.merchant.key(),
+            approved_by: ctx.accounts.merchant_authority.key(),
+            redeemed_at: clock.unix_timestamp,
+        });
+
+        emit!(CouponPartiallyRedeemed {
+            coupon: coupon.key(),
+            nft_mint: ctx.accounts.nft_mint.key(),
+            user: ticket.user,
+            merchant: ctx.accounts.merchant.key(),
+            amount,
+            uses_remaining: coupon.uses_remaining,
+            balance_remaining: coupon.balance_remaining,
+            approved_by: ctx.accounts.merchant_authority.key(),
+            order_amount,
+            savings,
+            payment_mint: promotion.payment_mint,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("✓ Coupon partially redeemed via ticket: {} uses / {} balance left",
+            coupon.uses_remaining, coupon.balance_remaining);
+
+        return Ok(());
+    }
+    
+    // Mark coupon as redeemed
+    coupon.is_redeemed = true;
+    coupon.redeemed_at = clock.unix_timestamp;
+    
+    // Update merchant stats
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.total_coupons_redeemed += 1;
     
+    // Show the redeemed state in wallets before the token is burned
+    let metadata_cpi = CouponMetadataCpi {
+        token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        mint: ctx.accounts.nft_mint.to_account_info(),
+        coupon_authority: ctx.accounts.coupon_authority.to_account_info(),
+        coupon_authority_bump: ctx.bumps.coupon_authority,
+        payer: ctx.accounts.user.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+    };
+    metadata_cpi.set_state(coupon, &ctx.accounts.promotion, CouponMetadataState::Redeemed)?;
+
     // Burn the NFT (permanent on-chain proof)
     let cpi_accounts = Burn {
         mint: ctx.accounts.nft_mint.to_account_info(),
@@ -251,6 +414,7 @@ pub fn verify_and_redeem_ticket(
         nft_mint: ctx.accounts.nft_mint.key(),
         user: ticket.user,
         merchant: merchant.key(),
+        approved_by: ctx.accounts.merchant_authority.key(),
         redeemed_at: clock.unix_timestamp,
     });
     
@@ -261,6 +425,10 @@ pub fn verify_and_redeem_ticket(
         merchant: merchant.key(),
         discount_percentage: coupon.discount_percentage,
         redemption_code: format!("TICKET-{}", ticket.nonce),
+        approved_by: ctx.accounts.merchant_authority.key(),
+        order_amount,
+        savings,
+        payment_mint: promotion.payment_mint,
         timestamp: clock.unix_timestamp,
     });
     
@@ -271,6 +439,203 @@ pub fn verify_and_redeem_ticket(
     Ok(())
 }
 
+// ============================================================================
+// Redeem Signed Ticket (Merchant Side, accepted offline)
+// ============================================================================
+
+/// The holder signs `RedemptionTicket::signed_ticket_hash` off-chain and
+/// shows it with the signature as a QR code. The point-of-sale checks the
+/// signature offline and submits later with an Ed25519 sigverify instruction
+/// placed right before this one; the holder doesn't sign the transaction.
+#[derive(Accounts)]
+pub struct RedeemSignedTicket<'info> {
+    #[account(
+        mut,
+        constraint = !coupon.is_redeemed @ CouponError::CouponAlreadyRedeemed,
+        constraint = !coupon.is_escrowed @ CouponError::CouponEscrowed,
+        constraint = coupon.merchant == merchant.key() @ CouponError::WrongMerchant
+    )]
+    pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon
+    )]
+    pub promotion: Account<'info, Promotion>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Metadata account, validated by the Token Metadata program
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Program PDA that holds update authority over coupon NFTs
+    #[account(
+        seeds = [b"coupon_authority"],
+        bump
+    )]
+    pub coupon_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = merchant.is_active @ CouponError::MerchantInactive
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    /// Present when a staff key rather than the merchant authority submits
+    #[account(
+        seeds = [b"merchant_staff", merchant.key().as_ref(), merchant_authority.key().as_ref()],
+        bump
+    )]
+    pub staff: Option<Account<'info, MerchantStaff>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_stats", coupon.owner.as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    /// Merchant authority or a staff key with an active entry
+    #[account(mut)]
+    pub merchant_authority: Signer<'info>,
+
+    /// CHECK: Metaplex Token Metadata Program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: Sysvar Instructions, holds the Ed25519 sigverify instruction
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn redeem_signed_ticket(
+    ctx: Context<RedeemSignedTicket>,
+    nonce: u64,
+    expires_at: i64,
+    order_amount: u64,
+) -> Result<()> {
+    ctx.accounts.merchant.authorize(
+        &ctx.accounts.merchant_authority.key(),
+        ctx.accounts.staff.as_deref(),
+        StaffRole::can_redeem,
+    )?;
+
+    // Signed tickets carry no location to check against the geofence
+    require!(!ctx.accounts.promotion.is_location_based, CouponError::LocationNotSupported);
+    // A signed ticket could be replayed against a coupon that is still live
+    // after one use, so offline redemption is limited to single-use coupons
+    require!(
+        ctx.accounts.promotion.redemption_model == RedemptionModel::SingleUse,
+        CouponError::UnsupportedRedemptionModel
+    );
+
+    let coupon = &mut ctx.accounts.coupon;
+    let clock = Clock::get()?;
+
+    // The ticket must have been valid while the coupon was, and the merchant
+    // gets a bounded window to submit what it accepted offline
+    require!(expires_at <= coupon.expiry_timestamp, CouponError::CouponExpired);
+    require!(
+        clock.unix_timestamp <= expires_at + RedemptionTicket::OFFLINE_SUBMISSION_WINDOW,
+        CouponError::TicketExpired
+    );
+
+    // Only the current holder's signature over this exact ticket and bill counts
+    let ticket_hash = RedemptionTicket::signed_ticket_hash(
+        &coupon.key(),
+        &coupon.owner,
+        nonce,
+        expires_at,
+        order_amount,
+    );
+    require_ed25519_signature(
+        &ctx.accounts.sysvar_instructions.to_account_info(),
+        &coupon.owner,
+        &ticket_hash,
+    )?;
+
+    coupon.consume(1)?;
+    coupon.is_redeemed = true;
+    coupon.redeemed_at = clock.unix_timestamp;
+
+    let merchant = &mut ctx.accounts.merchant;
+    merchant.total_coupons_redeemed += 1;
+
+    let user_stats = &mut ctx.accounts.user_stats;
+    user_stats.total_redemptions += 1;
+    user_stats.add_reputation(10);
+    user_stats.last_activity = clock.unix_timestamp;
+
+    if user_stats.total_redemptions == 10 && !user_stats.has_badge(BadgeType::TenRedemptions as u8) {
+        msg!("🏆 User eligible for TenRedemptions badge!");
+    }
+
+    if user_stats.total_redemptions == 50 && !user_stats.has_badge(BadgeType::FiftyRedemptions as u8) {
+        msg!("🏆 User eligible for FiftyRedemptions badge!");
+    }
+
+    // Lifetime savings are kept in lamports, so only SOL-priced promotions count
+    let promotion = &ctx.accounts.promotion;
+    let savings = promotion.savings(coupon.discount_percentage, order_amount);
+    if promotion.payment_mint.is_none() {
+        merchant.total_savings_given = merchant.total_savings_given.saturating_add(savings);
+        if user_stats.add_savings(savings) && !user_stats.has_badge(BadgeType::SavedOneSol as u8) {
+            msg!("🏆 User eligible for SavedOneSol badge!");
+        }
+    }
+
+    // Without the holder's signature the NFT can't be burned; it stays in
+    // their wallet showing the redeemed artwork
+    let metadata_cpi = CouponMetadataCpi {
+        token_metadata_program: ctx.accounts.token_metadata_program.to_account_info(),
+        metadata: ctx.accounts.metadata.to_account_info(),
+        mint: ctx.accounts.nft_mint.to_account_info(),
+        coupon_authority: ctx.accounts.coupon_authority.to_account_info(),
+        coupon_authority_bump: ctx.bumps.coupon_authority,
+        payer: ctx.accounts.merchant_authority.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        sysvar_instructions: ctx.accounts.sysvar_instructions.to_account_info(),
+    };
+    metadata_cpi.set_state(coupon, &ctx.accounts.promotion, CouponMetadataState::Redeemed)?;
+
+    emit!(SignedTicketRedeemed {
+        coupon: coupon.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        user: coupon.owner,
+        merchant: merchant.key(),
+        ticket_hash,
+        nonce,
+        expires_at,
+        approved_by: ctx.accounts.merchant_authority.key(),
+        redeemed_at: clock.unix_timestamp,
+    });
+
+    emit!(CouponRedeemed {
+        coupon: coupon.key(),
+        nft_mint: ctx.accounts.nft_mint.key(),
+        user: coupon.owner,
+        merchant: merchant.key(),
+        discount_percentage: coupon.discount_percentage,
+        redemption_code: format!("SIGNED-{}", nonce),
+        approved_by: ctx.accounts.merchant_authority.key(),
+        order_amount,
+        savings,
+        payment_mint: promotion.payment_mint,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("✓ Coupon redeemed from signed ticket");
+    msg!("✓ Merchant total redemptions: {}", merchant.total_coupons_redeemed);
+
+    Ok(())
+}
+
 // ============================================================================
 // Cancel Ticket (User can cancel if not yet redeemed)
 // ============================================================================
@@ -296,6 +661,47 @@ pub fn cancel_redemption_ticket(ctx: Context<CancelRedemptionTicket>) -> Result<
     Ok(())
 }
 
+// ============================================================================
+// Close Expired Ticket (permissionless crank, rent back to the user)
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct CloseExpiredTicket<'info> {
+    #[account(
+        mut,
+        constraint = !ticket.is_valid(Clock::get()?.unix_timestamp) @ CouponError::TicketStillValid,
+        close = user
+    )]
+    pub ticket: Account<'info, RedemptionTicket>,
+
+    /// CHECK: Receives the ticket rent; must be the user who created it
+    #[account(
+        mut,
+        constraint = ticket.user == user.key() @ CouponError::NotCouponOwner
+    )]
+    pub user: UncheckedAccount<'info>,
+
+    /// Anyone may crank expired or consumed tickets closed
+    pub cranker: Signer<'info>,
+}
+
+pub fn close_expired_ticket(ctx: Context<CloseExpiredTicket>) -> Result<()> {
+    let ticket = &ctx.accounts.ticket;
+
+    emit!(TicketClosed {
+        ticket: ticket.key(),
+        coupon: ticket.coupon,
+        user: ticket.user,
+        was_consumed: ticket.is_consumed,
+        closed_by: ctx.accounts.cranker.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✓ Expired redemption ticket closed, rent refunded to user");
+
+    Ok(())
+}
+
 // ============================================================================
 // Handler wrapper for lib.rs
 // ============================================================================
@@ -309,13 +715,28 @@ pub fn handler_generate_ticket(
     generate_redemption_ticket(ctx, nonce, latitude, longitude)
 }
 
-pub fn handler_verify_redeem_ticket(
-    ctx: Context<VerifyAndRedeemTicket>,
+pub fn handler_verify_redeem_ticket<'info>(
+    ctx: Context<'_, '_, '_, 'info, VerifyAndRedeemTicket<'info>>,
     expected_hash: [u8; 32],
+    amount: u64,
+    order_amount: u64,
 ) -> Result<()> {
-    verify_and_redeem_ticket(ctx, expected_hash)
+    verify_and_redeem_ticket(ctx, expected_hash, amount, order_amount)
+}
+
+pub fn handler_redeem_signed_ticket(
+    ctx: Context<RedeemSignedTicket>,
+    nonce: u64,
+    expires_at: i64,
+    order_amount: u64,
+) -> Result<()> {
+    redeem_signed_ticket(ctx, nonce, expires_at, order_amount)
 }
 
 pub fn handler_cancel_ticket(ctx: Context<CancelRedemptionTicket>) -> Result<()> {
     cancel_redemption_ticket(ctx)
+}
+
+pub fn handler_close_expired_ticket(ctx: Context<CloseExpiredTicket>) -> Result<()> {
+    close_expired_ticket(ctx)
 }
\ No newline at end of file
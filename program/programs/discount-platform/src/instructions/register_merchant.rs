@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::{Merchant, Marketplace, Location};
+use crate::state::{Merchant, Marketplace, Location, validate_coordinates, VerificationTier};
 use crate::errors::CouponError;
 use crate::events::MerchantRegistered;
 
@@ -19,13 +19,14 @@ pub fn handler(
     merchant.category = category.clone();
     merchant.total_coupons_created = 0;
     merchant.total_coupons_redeemed = 0;
+    merchant.total_savings_given = 0;
     merchant.is_active = true;
+    merchant.suspended_by_marketplace = false;
     merchant.created_at = Clock::get()?.unix_timestamp;
     
     // Set location
     if let (Some(lat), Some(lon)) = (latitude, longitude) {
-        require!(lat >= -90.0 && lat <= 90.0, CouponError::InvalidCoordinates);
-        require!(lon >= -180.0 && lon <= 180.0, CouponError::InvalidCoordinates);
+        validate_coordinates(lat, lon)?;
         
         merchant.location = Location::from_coords(lat, lon);
         merchant.has_physical_location = true;
@@ -40,6 +41,12 @@ pub fn handler(
         merchant.has_physical_location = false;
     }
 
+    merchant.verification_tier = VerificationTier::Unverified;
+    merchant.attestation_hash = [0u8; 32];
+    merchant.verification_expires_at = 0;
+    merchant.verified_by = Pubkey::default();
+    merchant.has_revenue_split = false;
+
     let marketplace = &mut ctx.accounts.marketplace;
     marketplace.total_merchants += 1;
 
@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount, Mint, transfer, Transfer};
-use crate::state::{Coupon, StakingPool, StakeAccount};
+use crate::state::{Coupon, Promotion, StakingPool, StakeAccount, CouponMetadataState, IssuanceMode};
 use crate::errors::CouponError;
 use crate::events::RewardsStaked;
+use crate::utils::CouponMetadataCpi;
 
 #[derive(Accounts)]
 pub struct StakeCoupon<'info> {
@@ -25,16 +26,36 @@ pub struct StakeCoupon<'info> {
     #[account(
         mut,
         constraint = coupon.owner == user.key() @ CouponError::NotCouponOwner,
-        constraint = !coupon.is_redeemed @ CouponError::CouponAlreadyRedeemed
+        constraint = !coupon.is_redeemed @ CouponError::CouponAlreadyRedeemed,
+        constraint = !coupon.is_escrowed @ CouponError::CouponEscrowed
     )]
     pub coupon: Account<'info, Coupon>,
 
+    #[account(
+        mut,
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon,
+        constraint = promotion.issuance_mode == IssuanceMode::TokenMetadata @ CouponError::WrongIssuanceMode
+    )]
+    pub promotion: Account<'info, Promotion>,
+
     /// CHECK: NFT Mint
     #[account(
         constraint = nft_mint.key() == coupon.mint.unwrap() @ CouponError::WrongCoupon
     )]
     pub nft_mint: Account<'info, Mint>,
 
+    /// CHECK: Metadata account, validated by the Token Metadata program.
+    /// Optional: only needed to refresh the NFT's wallet-facing state.
+    #[account(mut)]
+    pub metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Program PDA that holds update authority over coupon NFTs
+    #[account(
+        seeds = [b"coupon_authority"],
+        bump
+    )]
+    pub coupon_authority: Option<UncheckedAccount<'info>>,
+
     /// CHECK: User's token account
     #[account(
         mut,
@@ -45,7 +66,10 @@ pub struct StakeCoupon<'info> {
 
     /// CHECK: Staking vault token account (PDA)
     #[account(
-        mut,
+        init,
+        payer = user,
+        token::mint = nft_mint,
+        token::authority = stake_vault,
         seeds = [b"stake_vault", nft_mint.key().as_ref()],
         bump
     )]
@@ -54,6 +78,15 @@ pub struct StakeCoupon<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     pub token_program: Program<'info, Token>,
+
+    /// CHECK: Metaplex Token Metadata Program
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Sysvar Instructions
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -63,7 +96,7 @@ pub fn handler(
 ) -> Result<()> {
     let staking_pool = &mut ctx.accounts.staking_pool;
     let stake_account = &mut ctx.accounts.stake_account;
-    let coupon = &ctx.accounts.coupon;
+    let coupon_key = ctx.accounts.coupon.key();
     let current_time = Clock::get()?.unix_timestamp;
 
     require!(staking_pool.is_active, CouponError::PromotionInactive);
@@ -84,10 +117,12 @@ pub fn handler(
     let cpi_program = ctx.accounts.token_program.to_account_info();
     let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
     transfer(cpi_ctx, 1)?;
+    ctx.accounts.coupon.is_escrowed = true;
+    ctx.accounts.promotion.open_escrow();
 
     // Initialize stake account
     stake_account.user = ctx.accounts.user.key();
-    stake_account.coupon = coupon.key();
+    stake_account.coupon = coupon_key;
     stake_account.nft_mint = ctx.accounts.nft_mint.key();
     stake_account.amount_staked = 1_000_000; // Base value for NFT
     stake_account.staked_at = current_time;
@@ -100,6 +135,27 @@ pub fn handler(
     // Update pool stats
     staking_pool.total_staked += stake_account.amount_staked;
 
+    // Show the staked state in wallets while the NFT sits in the vault. The
+    // refresh is cosmetic, so callers may skip it by omitting the accounts.
+    if let (Some(token_metadata_program), Some(metadata), Some(coupon_authority), Some(sysvar_instructions)) = (
+        &ctx.accounts.token_metadata_program,
+        &ctx.accounts.metadata,
+        &ctx.accounts.coupon_authority,
+        &ctx.accounts.sysvar_instructions,
+    ) {
+        let metadata_cpi = CouponMetadataCpi {
+            token_metadata_program: token_metadata_program.to_account_info(),
+            metadata: metadata.to_account_info(),
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            coupon_authority: coupon_authority.to_account_info(),
+            coupon_authority_bump: ctx.bumps.coupon_authority.ok_or(CouponError::InvalidInput)?,
+            payer: ctx.accounts.user.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            sysvar_instructions: sysvar_instructions.to_account_info(),
+        };
+        metadata_cpi.set_state(&mut ctx.accounts.coupon, &ctx.accounts.promotion, CouponMetadataState::Staked)?;
+    }
+
     // Calculate expected rewards
     let expected_rewards = stake_account.calculate_rewards(
         stake_account.unlock_at,
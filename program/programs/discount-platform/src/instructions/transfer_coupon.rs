@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
-use crate::state::Coupon;
+use anchor_spl::token::{Token, TokenAccount, Mint, transfer, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use crate::state::{Coupon, Promotion, IssuanceMode};
 use crate::errors::CouponError;
 use crate::events::CouponTransferred;
 
@@ -10,6 +12,16 @@ pub fn handler(ctx: Context<TransferCoupon>) -> Result<()> {
     require!(coupon.owner == ctx.accounts.from_authority.key(), CouponError::NotCouponOwner);
 
     let old_owner = coupon.owner;
+
+    // Move the NFT together with the ownership record
+    let cpi_accounts = Transfer {
+        from: ctx.accounts.from_token_account.to_account_info(),
+        to: ctx.accounts.new_owner_token_account.to_account_info(),
+        authority: ctx.accounts.from_authority.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+    transfer(cpi_ctx, 1)?;
     
     // Update coupon owner
     coupon.owner = ctx.accounts.new_owner.key();
@@ -32,7 +44,41 @@ pub struct TransferCoupon<'info> {
         constraint = coupon.owner == from_authority.key() @ CouponError::NotCouponOwner
     )]
     pub coupon: Account<'info, Coupon>,
+
+    #[account(
+        constraint = coupon.promotion == promotion.key() @ CouponError::WrongCoupon,
+        constraint = promotion.transferable @ CouponError::CouponNotTransferable,
+        constraint = promotion.issuance_mode == IssuanceMode::TokenMetadata @ CouponError::WrongIssuanceMode
+    )]
+    pub promotion: Account<'info, Promotion>,
     /// CHECK: This is the new owner
     pub new_owner: UncheckedAccount<'info>,
+
+    /// CHECK: NFT Mint
+    #[account(
+        constraint = coupon.mint == Some(nft_mint.key()) @ CouponError::WrongCoupon
+    )]
+    pub nft_mint: Account<'info, Mint>,
+
+    /// CHECK: Current owner's token account
+    #[account(
+        mut,
+        constraint = from_token_account.mint == nft_mint.key(),
+        constraint = from_token_account.owner == from_authority.key()
+    )]
+    pub from_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = from_authority,
+        associated_token::mint = nft_mint,
+        associated_token::authority = new_owner
+    )]
+    pub new_owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
     pub from_authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
\ No newline at end of file
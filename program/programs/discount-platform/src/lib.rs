@@ -5,9 +5,10 @@ pub mod state;
 pub mod instructions;
 pub mod errors;
 pub mod events;
+pub mod utils;
 
 use instructions::*;
-use state::{BadgeType, DiscountTier, AuctionType};
+use state::{BadgeType, DiscountTier, AuctionType, AuctionTerms, VerificationTier, StaffRole, RevenueShare, IssuanceMode, CouponNftConfig, GeofenceConfig, RedemptionModel};
 
 declare_id!("9P3wW4XQH7DntMqfEiLqS6SNztihxfenNUSqECh3WTf3");
 
@@ -19,6 +20,45 @@ pub mod discount_platform {
         instructions::initialize::handler(ctx)
     }
 
+    pub fn update_marketplace_config(
+        ctx: Context<UpdateMarketplaceConfig>,
+        fee_basis_points: Option<u16>,
+        is_paused: Option<bool>,
+    ) -> Result<()> {
+        instructions::marketplace_admin::handler_update_marketplace_config(ctx, fee_basis_points, is_paused)
+    }
+
+    pub fn propose_authority(
+        ctx: Context<ProposeAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::marketplace_admin::handler_propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::marketplace_admin::handler_accept_authority(ctx)
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::marketplace_admin::handler_withdraw_treasury(ctx, amount)
+    }
+
+    pub fn add_payment_mint(ctx: Context<AddPaymentMint>) -> Result<()> {
+        instructions::marketplace_admin::handler_add_payment_mint(ctx)
+    }
+
+    pub fn remove_payment_mint(ctx: Context<RemovePaymentMint>, mint: Pubkey) -> Result<()> {
+        instructions::marketplace_admin::handler_remove_payment_mint(ctx, mint)
+    }
+
+    pub fn add_verifier(ctx: Context<UpdateVerifiers>, verifier: Pubkey) -> Result<()> {
+        instructions::marketplace_admin::handler_add_verifier(ctx, verifier)
+    }
+
+    pub fn remove_verifier(ctx: Context<UpdateVerifiers>, verifier: Pubkey) -> Result<()> {
+        instructions::marketplace_admin::handler_remove_verifier(ctx, verifier)
+    }
+
     pub fn register_merchant(
         ctx: Context<RegisterMerchant>,
         name: String,
@@ -29,6 +69,69 @@ pub mod discount_platform {
         instructions::register_merchant::handler(ctx, name, category, latitude, longitude)
     }
 
+    pub fn update_merchant(
+        ctx: Context<UpdateMerchant>,
+        name: Option<String>,
+        category: Option<String>,
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+    ) -> Result<()> {
+        instructions::merchant_admin::handler_update_merchant(ctx, name, category, latitude, longitude)
+    }
+
+    pub fn set_merchant_active(ctx: Context<SetMerchantActive>, is_active: bool) -> Result<()> {
+        instructions::merchant_admin::handler_set_merchant_active(ctx, is_active)
+    }
+
+    pub fn transfer_merchant_authority(ctx: Context<TransferMerchantAuthority>) -> Result<()> {
+        instructions::merchant_admin::handler_transfer_merchant_authority(ctx)
+    }
+
+    pub fn set_revenue_config(ctx: Context<SetRevenueConfig>, recipients: Vec<RevenueShare>) -> Result<()> {
+        instructions::merchant_admin::handler_set_revenue_config(ctx, recipients)
+    }
+
+    pub fn close_revenue_config(ctx: Context<CloseRevenueConfig>) -> Result<()> {
+        instructions::merchant_admin::handler_close_revenue_config(ctx)
+    }
+
+    pub fn add_staff(
+        ctx: Context<AddStaff>,
+        staff: Pubkey,
+        role: StaffRole,
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+    ) -> Result<()> {
+        instructions::merchant_staff::handler_add_staff(ctx, staff, role, latitude, longitude)
+    }
+
+    pub fn update_staff(
+        ctx: Context<UpdateStaff>,
+        role: Option<StaffRole>,
+        is_active: Option<bool>,
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+    ) -> Result<()> {
+        instructions::merchant_staff::handler_update_staff(ctx, role, is_active, latitude, longitude)
+    }
+
+    pub fn remove_staff(ctx: Context<RemoveStaff>) -> Result<()> {
+        instructions::merchant_staff::handler_remove_staff(ctx)
+    }
+
+    pub fn verify_merchant(
+        ctx: Context<VerifyMerchant>,
+        tier: VerificationTier,
+        attestation_hash: [u8; 32],
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::merchant_admin::handler_verify_merchant(ctx, tier, attestation_hash, expires_at)
+    }
+
+    pub fn revoke_merchant_verification(ctx: Context<VerifyMerchant>) -> Result<()> {
+        instructions::merchant_admin::handler_revoke_merchant_verification(ctx)
+    }
+
     pub fn create_promotion(
         ctx: Context<CreateCouponPromotion>,
         discount_percentage: u8,
@@ -37,6 +140,13 @@ pub mod discount_platform {
         category: String,
         description: String,
         price: u64,
+        payment_mint: Option<Pubkey>,
+        nft: CouponNftConfig,
+        issuance_mode: IssuanceMode,
+        geofence: Option<GeofenceConfig>,
+        redemption_model: RedemptionModel,
+        transferable: bool,
+        resale_allowed: bool,
     ) -> Result<()> {
         instructions::create_promotion::handler(
             ctx,
@@ -46,26 +156,108 @@ pub mod discount_platform {
             category,
             description,
             price,
+            payment_mint,
+            nft,
+            issuance_mode,
+            geofence,
+            redemption_model,
+            transferable,
+            resale_allowed,
+        )
+    }
+
+    pub fn update_promotion(
+        ctx: Context<UpdatePromotion>,
+        expiry_timestamp: Option<i64>,
+        max_supply: Option<u32>,
+        price: Option<u64>,
+        description: Option<String>,
+        transferable: Option<bool>,
+        resale_allowed: Option<bool>,
+    ) -> Result<()> {
+        instructions::promotion_admin::handler_update_promotion(
+            ctx,
+            expiry_timestamp,
+            max_supply,
+            price,
+            description,
+            transferable,
+            resale_allowed,
         )
     }
 
+    pub fn set_resale_terms(
+        ctx: Context<UpdatePromotion>,
+        royalty_basis_points: u16,
+        max_resale_bps: Option<u16>,
+    ) -> Result<()> {
+        instructions::promotion_admin::handler_set_resale_terms(ctx, royalty_basis_points, max_resale_bps)
+    }
+
+    pub fn set_ticket_validity(ctx: Context<UpdatePromotion>, validity_seconds: u32) -> Result<()> {
+        instructions::promotion_admin::handler_set_ticket_validity(ctx, validity_seconds)
+    }
+
+    pub fn set_discount_cap(ctx: Context<UpdatePromotion>, max_discount_cap: Option<u64>) -> Result<()> {
+        instructions::promotion_admin::handler_set_discount_cap(ctx, max_discount_cap)
+    }
+
+    pub fn pause_promotion(ctx: Context<SetPromotionActive>) -> Result<()> {
+        instructions::promotion_admin::handler_pause_promotion(ctx)
+    }
+
+    pub fn resume_promotion(ctx: Context<SetPromotionActive>) -> Result<()> {
+        instructions::promotion_admin::handler_resume_promotion(ctx)
+    }
+
+    pub fn close_promotion(ctx: Context<ClosePromotion>) -> Result<()> {
+        instructions::promotion_admin::handler_close_promotion(ctx)
+    }
+
+    pub fn purchase_coupon<'info>(
+        ctx: Context<'_, '_, '_, 'info, PurchaseCoupon<'info>>,
+        max_price: u64,
+    ) -> Result<()> {
+        instructions::purchase_coupon::handler(ctx, max_price)
+    }
+
     pub fn mint_coupon(ctx: Context<MintCoupon>, coupon_id: u64) -> Result<()> {
         instructions::mint_coupon::handler(ctx, coupon_id)
     }
 
+    pub fn mint_core_coupon(ctx: Context<MintCoreCoupon>, coupon_id: u64) -> Result<()> {
+        instructions::core_coupons::handler_mint_core_coupon(ctx, coupon_id)
+    }
+
+    pub fn redeem_core_coupon(
+        ctx: Context<RedeemCoreCoupon>,
+        amount: u64,
+        order_amount: u64,
+    ) -> Result<()> {
+        instructions::core_coupons::handler_redeem_core_coupon(ctx, amount, order_amount)
+    }
+
     pub fn transfer_coupon(ctx: Context<TransferCoupon>) -> Result<()> {
         instructions::transfer_coupon::handler(ctx)
     }
 
-    pub fn redeem_coupon(ctx: Context<RedeemCoupon>) -> Result<()> {
-        instructions::redeem_coupon::handler(ctx)
+    pub fn redeem_coupon(ctx: Context<RedeemCoupon>, amount: u64, order_amount: u64) -> Result<()> {
+        instructions::redeem_coupon::handler(ctx, amount, order_amount)
     }
 
-    pub fn list_for_sale(ctx: Context<ListCouponForSale>, price: u64) -> Result<()> {
-        instructions::list_for_sale::handler(ctx, price)
+    pub fn expire_coupon(ctx: Context<ExpireCoupon>) -> Result<()> {
+        instructions::expire_coupon::handler(ctx)
     }
 
-    pub fn buy_listing(ctx: Context<BuyListedCoupon>) -> Result<()> {
+    pub fn list_for_sale(
+        ctx: Context<ListCouponForSale>,
+        price: u64,
+        payment_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::list_for_sale::handler(ctx, price, payment_mint)
+    }
+
+    pub fn buy_listing<'info>(ctx: Context<'_, '_, '_, 'info, BuyListedCoupon<'info>>) -> Result<()> {
         instructions::buy_listing::handler(ctx)
     }
 
@@ -147,11 +339,22 @@ pub mod discount_platform {
         instructions::redemption_tickets::handler_generate_ticket(ctx, nonce, latitude, longitude)
     }
 
-    pub fn verify_and_redeem_ticket(
-        ctx: Context<VerifyAndRedeemTicket>,
+    pub fn verify_and_redeem_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, VerifyAndRedeemTicket<'info>>,
         expected_hash: [u8; 32],
+        amount: u64,
+        order_amount: u64,
     ) -> Result<()> {
-        instructions::redemption_tickets::handler_verify_redeem_ticket(ctx, expected_hash)
+        instructions::redemption_tickets::handler_verify_redeem_ticket(ctx, expected_hash, amount, order_amount)
+    }
+
+    pub fn redeem_signed_ticket(
+        ctx: Context<RedeemSignedTicket>,
+        nonce: u64,
+        expires_at: i64,
+        order_amount: u64,
+    ) -> Result<()> {
+        instructions::redemption_tickets::handler_redeem_signed_ticket(ctx, nonce, expires_at, order_amount)
     }
 
     pub fn cancel_redemption_ticket(
@@ -160,6 +363,12 @@ pub mod discount_platform {
         instructions::redemption_tickets::handler_cancel_ticket(ctx)
     }
 
+    pub fn close_expired_ticket(
+        ctx: Context<CloseExpiredTicket>,
+    ) -> Result<()> {
+        instructions::redemption_tickets::handler_close_expired_ticket(ctx)
+    }
+
     pub fn create_group_deal(
         ctx: Context<CreateGroupDeal>,
         deal_id: u64,
@@ -186,8 +395,8 @@ pub mod discount_platform {
         instructions::group_deals::handler_join_group_deal(ctx)
     }
 
-    pub fn finalize_group_deal(
-        ctx: Context<FinalizeGroupDeal>,
+    pub fn finalize_group_deal<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeGroupDeal<'info>>,
     ) -> Result<()> {
         instructions::group_deals::handler_finalize_group_deal(ctx)
     }
@@ -212,8 +421,7 @@ pub mod discount_platform {
         starting_price: u64,
         reserve_price: u64,
         duration_seconds: i64,
-        auto_extend: bool,
-        min_bid_increment: u64,
+        terms: AuctionTerms,
     ) -> Result<()> {
         instructions::auctions::handler_create_auction(
             ctx,
@@ -222,8 +430,7 @@ pub mod discount_platform {
             starting_price,
             reserve_price,
             duration_seconds,
-            auto_extend,
-            min_bid_increment,
+            terms,
         )
     }
 
@@ -234,14 +441,48 @@ pub mod discount_platform {
         instructions::auctions::handler_place_bid(ctx, bid_amount)
     }
 
-    pub fn buy_dutch_auction(
-        ctx: Context<BuyDutchAuction>,
+    pub fn buy_now<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyNow<'info>>,
+    ) -> Result<()> {
+        instructions::auctions::handler_buy_now(ctx)
+    }
+
+    pub fn commit_bid(
+        ctx: Context<CommitBid>,
+        deposit: u64,
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        instructions::auctions::handler_commit_bid(ctx, deposit, commitment)
+    }
+
+    pub fn reveal_bid(
+        ctx: Context<RevealBid>,
+        amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::auctions::handler_reveal_bid(ctx, amount, salt)
+    }
+
+    pub fn withdraw_bid(
+        ctx: Context<WithdrawBid>,
+    ) -> Result<()> {
+        instructions::auctions::handler_withdraw_bid(ctx)
+    }
+
+    pub fn buy_dutch_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyDutchAuction<'info>>,
     ) -> Result<()> {
         instructions::auctions::handler_buy_dutch_auction(ctx)
     }
 
-    pub fn finalize_auction(
-        ctx: Context<FinalizeAuction>,
+    pub fn get_dutch_price(
+        ctx: Context<GetDutchPrice>,
+    ) -> Result<u64> {
+        instructions::auctions::handler_get_dutch_price(ctx)
+    }
+
+    pub fn finalize_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, FinalizeAuction<'info>>,
     ) -> Result<()> {
         instructions::auctions::handler_finalize_auction(ctx)
     }
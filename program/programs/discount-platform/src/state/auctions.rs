@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::errors::CouponError;
 
 #[account]
 #[derive(InitSpace)]
@@ -15,9 +16,24 @@ pub struct CouponAuction {
     pub bid_count: u32,
     pub is_active: bool,
     pub is_finalized: bool,
-    pub auto_extend: bool,            // Extend if bid in last 5 mins
+    pub auto_extend: bool,            // Extend on bids inside the extension window
     pub extension_seconds: i64,       // How much to extend
+    pub extension_window_seconds: i64, // Bids this close to the end trigger an extension
+    pub max_total_extension_seconds: i64,
+    pub total_extension_seconds: i64, // Extension granted so far
     pub min_bid_increment: u64,       // Minimum increase per bid
+    pub payment_mint: Option<Pubkey>, // None = SOL
+
+    // Sealed-bid (commit-reveal) settings, unused for other auction types
+    pub reveal_end_time: i64,         // Reveals accepted after end_time until this
+    pub is_vickrey: bool,             // Winner pays the second-highest revealed bid
+    pub unrevealed_policy: UnrevealedBidPolicy,
+    pub second_highest_bid: u64,
+    pub winning_bid: Option<Pubkey>,  // Bid account that currently holds the lead
+    pub buy_now_price: Option<u64>,   // English only: ends the auction at this price
+    pub dutch_curve: DutchCurve,      // Dutch only: how the price decays
+    pub max_price: Option<u64>,       // Promotion's resale cap when the auction was created
+    pub royalty_basis_points: u16,    // Promotion's resale royalty when the auction was created
 }
 
 // Added Debug derive to fix the error
@@ -32,6 +48,96 @@ impl anchor_lang::Space for AuctionType {
     const INIT_SPACE: usize = 1;
 }
 
+/// What happens to a sealed-bid deposit that is never revealed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum UnrevealedBidPolicy {
+    Refund,       // Deposit goes back to the bidder
+    Forfeit,      // Deposit goes to the seller
+}
+
+/// Shape of the Dutch auction price decay from `starting_price` at
+/// `start_time` down to `reserve_price` at `end_time`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug, InitSpace)]
+pub enum DutchCurve {
+    Linear,
+    Stepped {
+        step_seconds: i64,       // Price drops once every step
+        step_amount: u64,        // By this much
+    },
+    Exponential {
+        half_life_seconds: i64,  // Distance to reserve halves every half-life
+    },
+    Piecewise {
+        #[max_len(8)]
+        points: Vec<PricePoint>, // Linear between points, ascending in time
+    },
+}
+
+impl DutchCurve {
+    pub const MAX_PRICE_POINTS: usize = 8;
+
+    /// Checks the curve against the auction's prices and duration
+    pub fn validate(&self, starting_price: u64, reserve_price: u64, duration: i64) -> Result<()> {
+        match self {
+            DutchCurve::Linear => {},
+            DutchCurve::Stepped { step_seconds, step_amount } => {
+                require!(*step_seconds > 0 && *step_seconds <= duration, CouponError::InvalidPriceCurve);
+                require!(*step_amount > 0, CouponError::InvalidPriceCurve);
+            },
+            DutchCurve::Exponential { half_life_seconds } => {
+                require!(*half_life_seconds > 0 && *half_life_seconds <= duration, CouponError::InvalidPriceCurve);
+            },
+            DutchCurve::Piecewise { points } => {
+                require!(!points.is_empty(), CouponError::InvalidPriceCurve);
+                require!(points.len() <= Self::MAX_PRICE_POINTS, CouponError::InvalidPriceCurve);
+                let mut last_offset = 0;
+                let mut last_price = starting_price;
+                for point in points {
+                    require!(point.offset_seconds > last_offset, CouponError::InvalidPriceCurve);
+                    require!(point.offset_seconds < duration, CouponError::InvalidPriceCurve);
+                    require!(point.price <= last_price, CouponError::InvalidPriceCurve);
+                    require!(point.price >= reserve_price, CouponError::InvalidPriceCurve);
+                    last_offset = point.offset_seconds;
+                    last_price = point.price;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct PricePoint {
+    pub offset_seconds: i64,     // Seconds after start_time
+    pub price: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct AntiSnipingConfig {
+    pub extension_window_seconds: i64,
+    pub extension_seconds: i64,
+    pub max_total_extension_seconds: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SealedBidConfig {
+    pub reveal_duration_seconds: i64,
+    pub is_vickrey: bool,
+    pub unrevealed_policy: UnrevealedBidPolicy,
+}
+
+/// `create_auction` settings beyond the core price and duration; most only
+/// apply to some auction types
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AuctionTerms {
+    pub min_bid_increment: u64,            // English and SealedBid
+    pub anti_sniping: Option<AntiSnipingConfig>,
+    pub payment_mint: Option<Pubkey>,      // None = SOL
+    pub sealed_bid_config: Option<SealedBidConfig>, // Required for SealedBid
+    pub buy_now_price: Option<u64>,        // English only
+    pub dutch_curve: Option<DutchCurve>,   // Dutch only, defaults to Linear
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Bid {
@@ -41,37 +147,145 @@ pub struct Bid {
     pub timestamp: i64,
     pub is_winning: bool,
     pub is_refunded: bool,
+    pub deposit: u64,                 // Escrowed amount (sealed bids: upper bound on amount)
+    pub commitment: [u8; 32],         // hash(amount, salt, bidder, auction) for sealed bids
+    pub is_revealed: bool,
+}
+
+impl Bid {
+    /// Binding the auction stops a revealed commitment being replayed elsewhere.
+    pub fn commitment_hash(amount: u64, salt: &[u8; 32], bidder: &Pubkey, auction: &Pubkey) -> [u8; 32] {
+        let data = [
+            &amount.to_le_bytes()[..],
+            salt.as_ref(),
+            bidder.as_ref(),
+            auction.as_ref(),
+        ].concat();
+
+        anchor_lang::solana_program::hash::hash(&data).to_bytes()
+    }
 }
 
 impl CouponAuction {
     pub fn is_expired(&self, current_time: i64) -> bool {
         current_time > self.end_time
     }
+
+    /// Whether a bid of `amount` respects the merchant's resale cap
+    pub fn within_price_cap(&self, amount: u64) -> bool {
+        match self.max_price {
+            Some(cap) => amount <= cap,
+            None => true,
+        }
+    }
     
     pub fn can_finalize(&self, current_time: i64) -> bool {
-        self.is_active && !self.is_finalized && self.is_expired(current_time)
+        let closes_at = if self.auction_type == AuctionType::SealedBid {
+            self.reveal_end_time
+        } else {
+            self.end_time
+        };
+        self.is_active && !self.is_finalized && current_time > closes_at
+    }
+
+    pub fn is_reveal_window(&self, current_time: i64) -> bool {
+        current_time > self.end_time && current_time <= self.reveal_end_time
+    }
+
+    /// Price the sealed-bid winner pays: their own bid, or the second price
+    /// (never below the reserve) for Vickrey auctions.
+    pub fn sealed_clearing_price(&self) -> u64 {
+        if self.is_vickrey {
+            self.second_highest_bid.max(self.reserve_price)
+        } else {
+            self.current_bid
+        }
     }
     
     pub fn should_extend(&self, current_time: i64) -> bool {
         self.auto_extend 
-            && (self.end_time - current_time) < 300 // Less than 5 mins left
+            && (self.end_time - current_time) < self.extension_window_seconds
+            && self.total_extension_seconds < self.max_total_extension_seconds
             && self.bid_count > 0
     }
+
+    /// Extension to grant for a bid at this time, capped by what is left of
+    /// the maximum total extension.
+    pub fn extension_for(&self, current_time: i64) -> i64 {
+        if !self.should_extend(current_time) {
+            return 0;
+        }
+        self.extension_seconds
+            .min(self.max_total_extension_seconds - self.total_extension_seconds)
+    }
     
-    pub fn calculate_dutch_price(&self, current_time: i64) -> u64 {
+    pub fn calculate_dutch_price(&self, current_time: i64) -> Result<u64> {
         if current_time <= self.start_time {
-            return self.starting_price;
+            return Ok(self.starting_price);
         }
         
         if current_time >= self.end_time {
-            return self.reserve_price;
+            return Ok(self.reserve_price);
         }
         
-        let elapsed = (current_time - self.start_time) as u64;
-        let duration = (self.end_time - self.start_time) as u64;
-        let price_drop = self.starting_price - self.reserve_price;
+        let elapsed = (current_time - self.start_time) as u128;
+        let duration = (self.end_time - self.start_time) as u128;
+        let start = self.starting_price as u128;
+        let reserve = self.reserve_price as u128;
+        let price_drop = start.checked_sub(reserve).ok_or(CouponError::MathOverflow)?;
         
-        let current_drop = (price_drop * elapsed) / duration;
-        self.starting_price - current_drop
+        let price = match &self.dutch_curve {
+            DutchCurve::Linear => {
+                let current_drop = price_drop
+                    .checked_mul(elapsed)
+                    .and_then(|v| v.checked_div(duration))
+                    .ok_or(CouponError::MathOverflow)?;
+                start - current_drop
+            },
+            DutchCurve::Stepped { step_seconds, step_amount } => {
+                let steps = elapsed
+                    .checked_div(*step_seconds as u128)
+                    .ok_or(CouponError::MathOverflow)?;
+                let current_drop = steps
+                    .checked_mul(*step_amount as u128)
+                    .ok_or(CouponError::MathOverflow)?;
+                start.saturating_sub(current_drop).max(reserve)
+            },
+            DutchCurve::Exponential { half_life_seconds } => {
+                let half_life = *half_life_seconds as u128;
+                let halvings = elapsed.checked_div(half_life).ok_or(CouponError::MathOverflow)?;
+                let remainder = elapsed % half_life;
+                // Halve the remaining drop per full half-life, then interpolate
+                // linearly towards the next halving
+                let remaining = if halvings >= 64 { 0 } else { price_drop >> halvings };
+                let partial = (remaining / 2)
+                    .checked_mul(remainder)
+                    .and_then(|v| v.checked_div(half_life))
+                    .ok_or(CouponError::MathOverflow)?;
+                reserve + remaining - partial
+            },
+            DutchCurve::Piecewise { points } => {
+                let mut prev = (0u128, start);
+                let mut next = (duration, reserve);
+                for point in points {
+                    let offset = point.offset_seconds as u128;
+                    if offset <= elapsed {
+                        prev = (offset, point.price as u128);
+                    } else {
+                        next = (offset, point.price as u128);
+                        break;
+                    }
+                }
+                let segment_drop = prev.1.checked_sub(next.1).ok_or(CouponError::MathOverflow)?;
+                let current_drop = segment_drop
+                    .checked_mul(elapsed - prev.0)
+                    .and_then(|v| v.checked_div(next.0 - prev.0))
+                    .ok_or(CouponError::MathOverflow)?;
+                prev.1 - current_drop
+            },
+        };
+        
+        u64::try_from(price).map_err(|_| CouponError::MathOverflow.into())
     }
+
 }
\ No newline at end of file
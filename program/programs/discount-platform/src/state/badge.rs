@@ -25,6 +25,35 @@ pub enum BadgeType {
     EarlyAdopter = 4,
     MerchantPartner = 5,
     CommunityModerator = 6,
+    SavedOneSol = 7,
+}
+
+impl BadgeType {
+    pub fn display_name(self) -> &'static str {
+        match self {
+            BadgeType::FirstPurchase => "First Purchase Badge",
+            BadgeType::TenRedemptions => "10 Redemptions Badge",
+            BadgeType::FiftyRedemptions => "50 Redemptions Badge",
+            BadgeType::TopReviewer => "Top Reviewer Badge",
+            BadgeType::EarlyAdopter => "Early Adopter Badge",
+            BadgeType::MerchantPartner => "Merchant Partner Badge",
+            BadgeType::CommunityModerator => "Community Moderator Badge",
+            BadgeType::SavedOneSol => "Saved 1 SOL Badge",
+        }
+    }
+
+    pub fn reputation_points(self) -> u64 {
+        match self {
+            BadgeType::FirstPurchase => 10,
+            BadgeType::TenRedemptions => 50,
+            BadgeType::FiftyRedemptions => 200,
+            BadgeType::TopReviewer => 100,
+            BadgeType::EarlyAdopter => 500,
+            BadgeType::MerchantPartner => 300,
+            BadgeType::CommunityModerator => 1000,
+            BadgeType::SavedOneSol => 100,
+        }
+    }
 }
 
 // Manual Space implementation for enum
@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::errors::CouponError;
+use super::RedemptionModel;
 
 #[account]
 #[derive(InitSpace)]
@@ -15,4 +17,57 @@ pub struct Coupon {
       #[max_len(200)]
     pub metadata_uri: String,  // IPFS or Arweave link
     pub mint: Option<Pubkey>,  // SPL Token mint address
+    pub is_escrowed: bool,     // NFT held in an auction, listing or stake vault
+    pub serial_number: u32,    // 1-based position within the promotion, 0 when no NFT
+    pub uses_remaining: u32,   // Single and multi-use coupons
+    pub balance_remaining: u64, // Stored-value coupons, 0 for the others
+}
+
+impl Coupon {
+    /// Loads the uses or value the promotion's model grants a new coupon
+    pub fn fill(&mut self, model: RedemptionModel) {
+        let (uses, balance) = match model {
+            RedemptionModel::SingleUse => (1, 0),
+            RedemptionModel::MultiUse { uses } => (uses, 0),
+            RedemptionModel::StoredValue { balance } => (0, balance),
+        };
+        self.uses_remaining = uses;
+        self.balance_remaining = balance;
+    }
+
+    /// Takes `amount` uses, or `amount` of the balance for stored-value
+    /// coupons, and returns whether the coupon is now exhausted
+    pub fn consume(&mut self, amount: u64) -> Result<bool> {
+        require!(amount > 0, CouponError::InvalidRedemptionAmount);
+
+        if self.balance_remaining > 0 {
+            require!(amount <= self.balance_remaining, CouponError::InvalidRedemptionAmount);
+            self.balance_remaining -= amount;
+            Ok(self.balance_remaining == 0)
+        } else {
+            require!(amount <= self.uses_remaining as u64, CouponError::InvalidRedemptionAmount);
+            self.uses_remaining -= amount as u32;
+            Ok(self.uses_remaining == 0)
+        }
+    }
+}
+
+/// Lifecycle state reflected in the coupon NFT's metadata URI
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum CouponMetadataState {
+    Active,
+    Staked,
+    Redeemed,
+    Expired,
+}
+
+impl CouponMetadataState {
+    pub fn uri_suffix(self) -> &'static str {
+        match self {
+            CouponMetadataState::Active => "",
+            CouponMetadataState::Staked => "-staked",
+            CouponMetadataState::Redeemed => "-redeemed",
+            CouponMetadataState::Expired => "-expired",
+        }
+    }
 }
@@ -18,6 +18,7 @@ pub struct GroupDeal {
     pub created_at: i64,
     pub finalized_at: i64,
     pub total_escrowed: u64,
+    pub payment_mint: Option<Pubkey>,   // Copied from the promotion, None = SOL
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
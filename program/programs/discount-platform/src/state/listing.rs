@@ -6,6 +6,8 @@ pub struct Listing {
     pub coupon: Pubkey,
     pub seller: Pubkey,
     pub price: u64,
+    pub payment_mint: Option<Pubkey>, // None = SOL
+    pub royalty_basis_points: u16,    // Promotion's resale royalty when listed
     pub is_active: bool,
     pub created_at: i64,
 }
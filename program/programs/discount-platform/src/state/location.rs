@@ -1,4 +1,12 @@
 use anchor_lang::prelude::*;
+use crate::errors::CouponError;
+
+/// Checks that a latitude/longitude pair is in range (and not NaN)
+pub fn validate_coordinates(latitude: f64, longitude: f64) -> Result<()> {
+    require!((-90.0..=90.0).contains(&latitude), CouponError::InvalidCoordinates);
+    require!((-180.0..=180.0).contains(&longitude), CouponError::InvalidCoordinates);
+    Ok(())
+}
 
 #[account]
 #[derive(InitSpace)]
@@ -12,6 +20,7 @@ pub struct Location {
 
 impl Location {
     pub const PRECISION: i32 = 1_000_000;
+    pub const MM_PER_DEGREE: u128 = 111_320_000; // Along a meridian
     
     pub fn from_coords(lat: f64, lon: f64) -> Self {
         Self {
@@ -23,6 +32,17 @@ impl Location {
         }
     }
     
+    /// Location from coordinates already scaled by `PRECISION`
+    pub fn from_microdegrees(latitude: i32, longitude: i32) -> Self {
+        Self {
+            latitude,
+            longitude,
+            region_code: 0,
+            country_code: 0,
+            city_hash: 0,
+        }
+    }
+
     pub fn to_coords(&self) -> (f64, f64) {
         (
             self.latitude as f64 / Self::PRECISION as f64,
@@ -30,21 +50,66 @@ impl Location {
         )
     }
     
-    pub fn distance_to(&self, other: &Location) -> f64 {
-        let (lat1, lon1) = self.to_coords();
-        let (lat2, lon2) = other.to_coords();
-        
-        let r = 6371000.0;
-        let phi1 = lat1.to_radians();
-        let phi2 = lat2.to_radians();
-        let delta_phi = (lat2 - lat1).to_radians();
-        let delta_lambda = (lon2 - lon1).to_radians();
-        
-        let a = (delta_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (delta_lambda / 2.0).sin().powi(2);
-        let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
-        
-        r * c
+    /// Approximate distance in meters, see `distance_squared_mm`
+    pub fn distance_to(&self, other: &Location) -> u64 {
+        isqrt(self.distance_squared_mm(other)) as u64 / 1000
+    }
+
+    /// Whether `other` lies within `radius_meters` of this point
+    pub fn is_within(&self, other: &Location, radius_meters: u32) -> bool {
+        let radius_mm = radius_meters as u128 * 1000;
+        self.distance_squared_mm(other) <= radius_mm * radius_mm
     }
+
+    /// Squared distance in millimeters, using an equirectangular projection
+    /// and integer math only so every validator gets the same answer. Good
+    /// to well under 1% at geofence scale (a few kilometers).
+    fn distance_squared_mm(&self, other: &Location) -> u128 {
+        let d_lat = (other.latitude as i64 - self.latitude as i64).abs();
+        let mut d_lon = (other.longitude as i64 - self.longitude as i64).abs();
+        if d_lon > 180 * Self::PRECISION as i64 {
+            d_lon = 360 * Self::PRECISION as i64 - d_lon; // Across the antimeridian
+        }
+        let mean_lat = (self.latitude as i64 + other.latitude as i64) / 2;
+
+        let lat_mm = d_lat as u128 * Self::MM_PER_DEGREE / Self::PRECISION as u128;
+        let lon_mm = d_lon as u128 * Self::MM_PER_DEGREE * cos_scaled(mean_lat)
+            / (Self::PRECISION as u128 * COS_SCALE);
+
+        lat_mm * lat_mm + lon_mm * lon_mm
+    }
+}
+
+const COS_SCALE: u128 = 1_000_000;
+
+/// cos(latitude) * COS_SCALE for a latitude in microdegrees, using
+/// Bhaskara I's rational approximation (max error about 0.2%)
+fn cos_scaled(latitude: i64) -> u128 {
+    let theta = latitude.unsigned_abs().min(90 * Location::PRECISION as u64) as u128;
+    let half_turn_sq = (180 * Location::PRECISION as u128).pow(2);
+    (half_turn_sq - 4 * theta * theta) * COS_SCALE / (half_turn_sq + theta * theta)
+}
+
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = n.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// Where a location-based promotion applies and how far from it (or from
+/// the merchant and its stores) coupons may be redeemed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct GeofenceConfig {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_meters: u32,
 }
 
 #[account]
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::errors::CouponError;
 
 #[account]
 #[derive(InitSpace)]
@@ -7,4 +8,43 @@ pub struct Marketplace {
     pub total_coupons: u64,
     pub total_merchants: u64,
     pub fee_basis_points: u16,
+    pub pending_authority: Option<Pubkey>, // Set by propose_authority, cleared on accept
+    pub is_paused: bool,                   // Emergency stop for all trading
+    pub total_fees_collected: u64,         // Lifetime SOL fees paid into the treasury PDA
+    pub total_fees_withdrawn: u64,         // Lifetime SOL withdrawals out of the treasury PDA
+    #[max_len(10)]
+    pub accepted_mints: Vec<Pubkey>,       // SPL / Token-2022 mints allowed for payments
+    #[max_len(5)]
+    pub verifiers: Vec<Pubkey>,            // Keys allowed to attest merchant verification
+}
+
+impl Marketplace {
+    pub const MAX_FEE_BASIS_POINTS: u16 = 1000; // 10% cap
+    pub const MAX_ACCEPTED_MINTS: usize = 10;
+    pub const MAX_VERIFIERS: usize = 5;
+
+    pub fn calculate_fee(&self, amount: u64) -> u64 {
+        ((amount as u128 * self.fee_basis_points as u128) / 10000) as u64
+    }
+
+    pub fn is_mint_accepted(&self, mint: &Pubkey) -> bool {
+        self.accepted_mints.contains(mint)
+    }
+
+    /// None (SOL) is always accepted; SPL mints must be on the allow-list.
+    pub fn require_payment_mint(&self, payment_mint: Option<Pubkey>) -> Result<()> {
+        if let Some(mint) = payment_mint {
+            require!(self.is_mint_accepted(&mint), CouponError::PaymentMintNotAccepted);
+        }
+        Ok(())
+    }
+
+    /// The marketplace authority can always verify; others must be delegated.
+    pub fn can_verify(&self, key: &Pubkey) -> bool {
+        self.authority == *key || self.verifiers.contains(key)
+    }
+
+    pub fn record_fee(&mut self, fee: u64) {
+        self.total_fees_collected = self.total_fees_collected.saturating_add(fee);
+    }
 }
@@ -1,6 +1,7 @@
 // src/state/merchant.rs
 use anchor_lang::prelude::*;
-use super::Location;
+use super::{Location, MerchantStaff, StaffRole};
+use crate::errors::CouponError;
 
 #[account]
 #[derive(InitSpace)]
@@ -12,10 +13,61 @@ pub struct Merchant {
     pub category: String,
     pub total_coupons_created: u64,
     pub total_coupons_redeemed: u64,
+    pub total_savings_given: u64,   // Lamports customers saved on SOL-priced coupons only
     pub is_active: bool,
+    pub suspended_by_marketplace: bool, // Only the marketplace authority can lift this
     pub created_at: i64,
 
     // Geographic data
     pub location: Location,
     pub has_physical_location: bool,
+
+    // Marketplace verification (KYC attestation)
+    pub verification_tier: VerificationTier,
+    pub attestation_hash: [u8; 32],     // Hash of the off-chain KYC record
+    pub verification_expires_at: i64,
+    pub verified_by: Pubkey,            // Marketplace authority or delegated verifier
+
+    // Set while a RevenueConfig PDA exists; payouts must then follow it
+    pub has_revenue_split: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum VerificationTier {
+    Unverified,
+    Basic,      // Identity checked
+    Verified,   // Identity and business registration checked
+    Premium,    // Full KYC plus physical location audit
+}
+
+impl Merchant {
+    /// Tier currently in force; lapsed attestations read as unverified.
+    pub fn verification_status(&self, current_time: i64) -> VerificationTier {
+        if self.verification_tier != VerificationTier::Unverified
+            && current_time < self.verification_expires_at
+        {
+            self.verification_tier
+        } else {
+            VerificationTier::Unverified
+        }
+    }
+
+    /// The merchant authority may do anything; staff need an active entry
+    /// whose role passes `permitted`. Callers derive `staff` from the signer.
+    pub fn authorize(
+        &self,
+        signer: &Pubkey,
+        staff: Option<&MerchantStaff>,
+        permitted: fn(StaffRole) -> bool,
+    ) -> Result<()> {
+        if self.authority == *signer {
+            return Ok(());
+        }
+
+        let staff = staff.ok_or(CouponError::NotMerchantAuthority)?;
+        require!(staff.is_active, CouponError::NotMerchantAuthority);
+        require!(permitted(staff.role), CouponError::StaffRoleNotPermitted);
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -0,0 +1,41 @@
+// src/state/merchant_staff.rs
+use anchor_lang::prelude::*;
+use super::Location;
+
+/// Delegated key allowed to act for a merchant, e.g. a store cashier.
+/// PDA: [b"merchant_staff", merchant, staff]
+#[account]
+#[derive(InitSpace)]
+pub struct MerchantStaff {
+    pub merchant: Pubkey,
+    pub staff: Pubkey,
+    pub role: StaffRole,
+    pub is_active: bool,
+    pub added_by: Pubkey,
+    pub added_at: i64,
+
+    // Store this key works at, if the merchant runs several
+    pub store_location: Location,
+    pub has_store_location: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum StaffRole {
+    Owner,      // Everything the merchant authority can do with staff and promotions
+    Manager,    // Redeem, create and pause promotions
+    Cashier,    // Redeem only
+}
+
+impl StaffRole {
+    pub fn can_redeem(self) -> bool {
+        true
+    }
+
+    pub fn can_manage_promotions(self) -> bool {
+        matches!(self, StaffRole::Owner | StaffRole::Manager)
+    }
+
+    pub fn can_manage_staff(self) -> bool {
+        self == StaffRole::Owner
+    }
+}
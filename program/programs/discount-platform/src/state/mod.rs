@@ -1,6 +1,8 @@
 // src/state/mod.rs
 pub mod marketplace;
 pub mod merchant;
+pub mod merchant_staff;
+pub mod revenue_config;
 pub mod promotion;
 pub mod coupon;
 pub mod listing;
@@ -17,6 +19,8 @@ pub mod auctions;
 
 pub use marketplace::*;
 pub use merchant::*;
+pub use merchant_staff::*;
+pub use revenue_config::*;
 pub use promotion::*;
 pub use coupon::*;
 pub use listing::*;
@@ -1,6 +1,7 @@
 // src/state/promotion.rs
 use anchor_lang::prelude::*;
-use super::Location;
+use crate::errors::CouponError;
+use super::{Location, CouponMetadataState};
 
 #[account]
 #[derive(InitSpace)]
@@ -15,6 +16,7 @@ pub struct Promotion {
     #[max_len(200)]
     pub description: String,
     pub price: u64,
+    pub payment_mint: Option<Pubkey>, // None = SOL
     pub is_active: bool,
     pub created_at: i64,
 
@@ -23,4 +25,159 @@ pub struct Promotion {
     pub geo_cell_id: u64,
     pub radius_meters: u32,
     pub is_location_based: bool,
+
+    // NFT metadata
+    #[max_len(160)]
+    pub metadata_base_uri: String,  // Coupon URIs are `{base}/{serial}.json`
+    #[max_len(32)]
+    pub nft_name: String,
+    #[max_len(10)]
+    pub nft_symbol: String,
+    pub issuance_mode: IssuanceMode,
+
+    // Secondary market policy
+    pub transferable: bool,     // Holders may transfer coupons to another wallet
+    pub resale_allowed: bool,   // Holders may list or auction coupons (requires transferable)
+    pub royalty_basis_points: u16,  // Merchant's cut of every resale
+    pub max_resale_bps: Option<u16>, // Resale price cap in bps of face value, None = uncapped
+
+    // Redemption
+    pub ticket_validity_seconds: u32, // How long a generated redemption ticket stays valid
+    pub redemption_model: RedemptionModel,
+    pub max_discount_cap: Option<u64>, // Most a single redemption can save, in payment units
+
+    // Coupons sitting in listing, auction or stake vaults. Settling those
+    // reads the promotion, so it can't be closed while any are open.
+    pub open_escrows: u32,
+}
+
+/// How coupons for a promotion are represented on-chain.
+///
+/// Core coupons are minted with `mint_core_coupon` and redeemed with
+/// `redeem_core_coupon` only. Purchases, tickets, transfers, listings,
+/// auctions and staking move SPL tokens and reject Core promotions with
+/// `WrongIssuanceMode`; holders trade Core assets through the Core program.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum IssuanceMode {
+    TokenMetadata,  // SPL mint + ATA + metadata + master edition
+    Core,           // Single Metaplex Core asset account, for mass issuance
+}
+
+/// Name, symbol and URI base the promotion's coupon NFTs are minted with
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CouponNftConfig {
+    pub metadata_base_uri: String,
+    pub name: String,
+    pub symbol: String,
+}
+
+impl CouponNftConfig {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.metadata_base_uri.is_empty() && self.metadata_base_uri.len() <= 160,
+            CouponError::InvalidNftMetadata
+        );
+        require!(!self.name.is_empty() && self.name.len() <= 32, CouponError::InvalidNftMetadata);
+        require!(!self.symbol.is_empty() && self.symbol.len() <= 10, CouponError::InvalidNftMetadata);
+        Ok(())
+    }
+}
+
+/// How much a single coupon is good for
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum RedemptionModel {
+    SingleUse,                      // Redeemed once, then burned
+    MultiUse { uses: u32 },         // Punch card, e.g. "5 coffees"
+    StoredValue { balance: u64 },   // Credit spent in parts, in the promotion's payment units
+}
+
+impl RedemptionModel {
+    pub const MAX_USES: u32 = 1000;
+
+    pub fn validate(self) -> Result<()> {
+        let valid = match self {
+            RedemptionModel::SingleUse => true,
+            RedemptionModel::MultiUse { uses } => uses > 0 && uses <= Self::MAX_USES,
+            RedemptionModel::StoredValue { balance } => balance > 0,
+        };
+        require!(valid, CouponError::InvalidRedemptionModel);
+        Ok(())
+    }
+}
+
+impl Promotion {
+    pub const MAX_ROYALTY_BASIS_POINTS: u16 = 2500; // 25% cap
+    pub const MAX_GEOFENCE_RADIUS_METERS: u32 = 100_000; // 100 km
+
+    /// Highest price a coupon may be resold for, if the merchant capped it
+    pub fn max_resale_price(&self) -> Option<u64> {
+        self.max_resale_bps
+            .map(|bps| ((self.price as u128 * bps as u128) / 10000) as u64)
+    }
+
+    pub fn require_resale_price(&self, price: u64) -> Result<()> {
+        if let Some(cap) = self.max_resale_price() {
+            require!(price <= cap, CouponError::ResalePriceTooHigh);
+        }
+        Ok(())
+    }
+
+    /// Records a coupon moving into a listing, auction or stake vault
+    pub fn open_escrow(&mut self) {
+        self.open_escrows += 1;
+    }
+
+    /// Records a coupon leaving its listing, auction or stake vault
+    pub fn close_escrow(&mut self) {
+        self.open_escrows = self.open_escrows.saturating_sub(1);
+    }
+
+    /// Core assets are created without plugins, so holders can move them
+    /// through the Core program directly. Only open, royalty-free terms can
+    /// be honoured for them, and `redeem_core_coupon` takes no location so
+    /// a geofence could never be satisfied.
+    pub fn require_enforceable_terms(&self) -> Result<()> {
+        if self.issuance_mode == IssuanceMode::Core {
+            require!(
+                self.transferable && self.resale_allowed && self.royalty_basis_points == 0,
+                CouponError::CoreTermsUnsupported
+            );
+            require!(!self.is_location_based, CouponError::CoreTermsUnsupported);
+        }
+        Ok(())
+    }
+
+    /// For location-based promotions, checks that `point` lies within the
+    /// radius of the promotion's own location or any of `stores` (the
+    /// merchant's address and its staffed store locations)
+    pub fn require_within_geofence(&self, point: Option<&Location>, stores: &[Location]) -> Result<()> {
+        if !self.is_location_based {
+            return Ok(());
+        }
+
+        let point = point.ok_or(CouponError::LocationNotSupported)?;
+        let inside = std::iter::once(&self.location)
+            .chain(stores)
+            .any(|center| center.is_within(point, self.radius_meters));
+        require!(inside, CouponError::LocationNotSupported);
+
+        Ok(())
+    }
+
+    /// What the holder saves on an `order_amount` bill (before discount),
+    /// capped by `max_discount_cap`
+    pub fn savings(&self, discount_percentage: u8, order_amount: u64) -> u64 {
+        let savings = ((order_amount as u128 * discount_percentage as u128) / 100) as u64;
+        self.max_discount_cap.map_or(savings, |cap| savings.min(cap))
+    }
+
+    /// Metadata URI for the coupon with the given serial number in `state`
+    pub fn coupon_uri(&self, serial_number: u32, state: CouponMetadataState) -> String {
+        format!(
+            "{}/{}{}.json",
+            self.metadata_base_uri.trim_end_matches('/'),
+            serial_number,
+            state.uri_suffix()
+        )
+    }
 }
\ No newline at end of file
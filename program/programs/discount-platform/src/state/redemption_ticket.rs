@@ -8,7 +8,7 @@ pub struct RedemptionTicket {
     pub merchant: Pubkey,
     pub ticket_hash: [u8; 32],        // Hash for QR code verification
     pub created_at: i64,
-    pub expires_at: i64,               // 5 minute expiry window
+    pub expires_at: i64,               // created_at + the promotion's ticket validity
     pub is_consumed: bool,
     pub nonce: u64,                    // Prevents replay attacks
     pub redemption_location: Option<RedemptionLocation>,
@@ -22,6 +22,14 @@ pub struct RedemptionLocation {
 }
 
 impl RedemptionTicket {
+    pub const DEFAULT_VALIDITY_SECONDS: u32 = 300;     // 5 minutes
+    pub const MIN_VALIDITY_SECONDS: u32 = 60;
+    pub const MAX_VALIDITY_SECONDS: u32 = 24 * 60 * 60; // 1 day
+
+    /// How long after a signed ticket expires a merchant may still submit
+    /// the redemption it accepted offline
+    pub const OFFLINE_SUBMISSION_WINDOW: i64 = 7 * 24 * 60 * 60;
+
     pub fn is_valid(&self, current_time: i64) -> bool {
         !self.is_consumed && current_time < self.expires_at
     }
@@ -39,4 +47,27 @@ impl RedemptionTicket {
         
         anchor_lang::solana_program::hash::hash(&data).to_bytes()
     }
-}
\ No newline at end of file
+
+    /// Message a holder signs for an offline ticket. Unlike `generate_hash`
+    /// it binds the expiry and the bill the coupon is applied to, and the QR
+    /// code carries the holder's ed25519 signature over it, so it can't be
+    /// forged from public data.
+    pub fn signed_ticket_hash(
+        coupon_key: &Pubkey,
+        user_key: &Pubkey,
+        nonce: u64,
+        expires_at: i64,
+        order_amount: u64,
+    ) -> [u8; 32] {
+        let data = [
+            b"signed_ticket".as_ref(),
+            coupon_key.as_ref(),
+            user_key.as_ref(),
+            &nonce.to_le_bytes(),
+            &expires_at.to_le_bytes(),
+            &order_amount.to_le_bytes(),
+        ].concat();
+
+        anchor_lang::solana_program::hash::hash(&data).to_bytes()
+    }
+}
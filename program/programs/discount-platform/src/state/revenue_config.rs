@@ -0,0 +1,65 @@
+// src/state/revenue_config.rs
+use anchor_lang::prelude::*;
+use crate::errors::CouponError;
+
+/// How a merchant's sale proceeds are split between payout recipients.
+/// PDA: [b"revenue_config", merchant]
+#[account]
+#[derive(InitSpace)]
+pub struct RevenueConfig {
+    pub merchant: Pubkey,
+    #[max_len(5)]
+    pub recipients: Vec<RevenueShare>,
+    pub updated_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct RevenueShare {
+    pub recipient: Pubkey,
+    pub share_bps: u16,
+}
+
+impl RevenueConfig {
+    pub const MAX_RECIPIENTS: usize = 5;
+    pub const TOTAL_BPS: u16 = 10_000;
+
+    /// Shares must be non-zero, unique per recipient and add up to 100%.
+    pub fn validate(recipients: &[RevenueShare]) -> Result<()> {
+        require!(
+            !recipients.is_empty() && recipients.len() <= Self::MAX_RECIPIENTS,
+            CouponError::InvalidRevenueSplit
+        );
+
+        let mut total: u16 = 0;
+        for (i, share) in recipients.iter().enumerate() {
+            require!(share.share_bps > 0, CouponError::InvalidRevenueSplit);
+            require!(
+                !recipients[..i].iter().any(|other| other.recipient == share.recipient),
+                CouponError::InvalidRevenueSplit
+            );
+            total = total
+                .checked_add(share.share_bps)
+                .ok_or(CouponError::InvalidRevenueSplit)?;
+        }
+        require!(total == Self::TOTAL_BPS, CouponError::InvalidRevenueSplit);
+
+        Ok(())
+    }
+
+    /// Amount owed to each recipient, in config order. Rounding dust goes
+    /// to the first recipient so the parts always sum to `amount`.
+    pub fn split(&self, amount: u64) -> Vec<u64> {
+        let mut parts: Vec<u64> = self
+            .recipients
+            .iter()
+            .map(|share| ((amount as u128 * share.share_bps as u128) / Self::TOTAL_BPS as u128) as u64)
+            .collect();
+
+        let distributed: u64 = parts.iter().sum();
+        if let Some(first) = parts.first_mut() {
+            *first += amount - distributed;
+        }
+
+        parts
+    }
+}
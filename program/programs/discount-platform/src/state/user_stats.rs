@@ -15,6 +15,9 @@ pub struct UserStats {
     pub badges_earned: Vec<u8>, // Store BadgeType as u8
     pub joined_at: i64,
     pub last_activity: i64,
+    // Lamports saved redeeming SOL-priced coupons. Savings on SPL-priced
+    // promotions aren't summed here; see the redemption events.
+    pub total_savings: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
@@ -31,6 +34,8 @@ impl anchor_lang::Space for ReputationTier {
 }
 
 impl UserStats {
+    pub const SAVED_ONE_SOL_LAMPORTS: u64 = 1_000_000_000;
+
     pub fn has_badge(&self, badge_type: u8) -> bool {
         self.badges_earned.contains(&badge_type)
     }
@@ -51,6 +56,14 @@ impl UserStats {
         };
     }
 
+    /// Adds lamports saved on a redemption; true when this crosses the
+    /// SavedOneSol milestone
+    pub fn add_savings(&mut self, lamports: u64) -> bool {
+        let before = self.total_savings;
+        self.total_savings = before.saturating_add(lamports);
+        before < Self::SAVED_ONE_SOL_LAMPORTS && self.total_savings >= Self::SAVED_ONE_SOL_LAMPORTS
+    }
+
     pub fn add_reputation(&mut self, points: u64) {
         self.reputation_score = self.reputation_score.saturating_add(points);
         self.update_tier();
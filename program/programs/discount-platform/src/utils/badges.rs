@@ -0,0 +1,37 @@
+// src/utils/badges.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, MintTo, freeze_account, FreezeAccount};
+
+/// Mints the single badge token and freezes it in the holder's account.
+/// Must run before the Metaplex master edition takes over the mint and
+/// freeze authorities: after that nothing can thaw it, so badges are soulbound.
+pub fn mint_soulbound_badge<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    badge_authority: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    mint_to(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            MintTo {
+                mint: mint.clone(),
+                to: token_account.clone(),
+                authority: badge_authority.clone(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+
+    freeze_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        FreezeAccount {
+            account: token_account.clone(),
+            mint: mint.clone(),
+            authority: badge_authority.clone(),
+        },
+        signer_seeds,
+    ))
+}
@@ -0,0 +1,70 @@
+// src/utils/ed25519.rs
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked,
+    load_instruction_at_checked,
+};
+use crate::errors::CouponError;
+
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+/// Checks that the instruction right before the current one is an Ed25519
+/// sigverify precompile call proving `signer` signed exactly `message`.
+/// The precompile fails the whole transaction on a bad signature, so only
+/// what it verified has to be matched here.
+pub fn require_ed25519_signature(
+    sysvar_instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = load_current_index_checked(sysvar_instructions)?;
+    require!(current_index > 0, CouponError::InvalidTicketSignature);
+
+    let ix = load_instruction_at_checked(current_index as usize - 1, sysvar_instructions)?;
+    require_keys_eq!(ix.program_id, ed25519_program::ID, CouponError::InvalidTicketSignature);
+    require!(ix.accounts.is_empty(), CouponError::InvalidTicketSignature);
+
+    // One signature, with its key, signature and message all inline
+    let data = &ix.data;
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN && data[0] == 1,
+        CouponError::InvalidTicketSignature
+    );
+    let offsets = &data[SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN];
+    let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]) as usize;
+
+    let signature_offset = read_u16(0);
+    let signature_ix = read_u16(2);
+    let pubkey_offset = read_u16(4);
+    let pubkey_ix = read_u16(6);
+    let message_offset = read_u16(8);
+    let message_size = read_u16(10);
+    let message_ix = read_u16(12);
+
+    let inline = u16::MAX as usize;
+    require!(
+        signature_ix == inline && pubkey_ix == inline && message_ix == inline,
+        CouponError::InvalidTicketSignature
+    );
+    require!(
+        data.len() >= signature_offset + SIGNATURE_LEN
+            && data.len() >= pubkey_offset + PUBKEY_LEN
+            && data.len() >= message_offset + message_size,
+        CouponError::InvalidTicketSignature
+    );
+
+    require!(
+        &data[pubkey_offset..pubkey_offset + PUBKEY_LEN] == signer.as_ref(),
+        CouponError::InvalidTicketSignature
+    );
+    require!(
+        &data[message_offset..message_offset + message_size] == message,
+        CouponError::InvalidTicketSignature
+    );
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+// src/utils/metadata.rs
+use anchor_lang::prelude::*;
+use mpl_token_metadata::instructions::UpdateV1CpiBuilder;
+use mpl_token_metadata::types::Data;
+use crate::state::{Coupon, CouponMetadataState, Promotion};
+use crate::events::CouponMetadataUpdated;
+
+/// Accounts needed to rewrite a coupon NFT's metadata through Metaplex
+/// `UpdateV1`, signed by the program's `coupon_authority` PDA.
+pub struct CouponMetadataCpi<'info> {
+    pub token_metadata_program: AccountInfo<'info>,
+    pub metadata: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub coupon_authority: AccountInfo<'info>,
+    pub coupon_authority_bump: u8,
+    pub payer: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub sysvar_instructions: AccountInfo<'info>,
+}
+
+impl<'info> CouponMetadataCpi<'info> {
+    /// Points the NFT at the promotion's URI for `state` and records it on the coupon.
+    pub fn set_state(
+        &self,
+        coupon: &mut Account<'info, Coupon>,
+        promotion: &Promotion,
+        state: CouponMetadataState,
+    ) -> Result<()> {
+        let uri = promotion.coupon_uri(coupon.serial_number, state);
+        let authority_seeds: &[&[u8]] = &[b"coupon_authority", &[self.coupon_authority_bump]];
+
+        UpdateV1CpiBuilder::new(&self.token_metadata_program)
+            .authority(&self.coupon_authority)
+            .mint(&self.mint)
+            .metadata(&self.metadata)
+            .payer(&self.payer)
+            .system_program(&self.system_program)
+            .sysvar_instructions(&self.sysvar_instructions)
+            .data(Data {
+                name: promotion.nft_name.clone(),
+                symbol: promotion.nft_symbol.clone(),
+                uri: uri.clone(),
+                seller_fee_basis_points: promotion.royalty_basis_points,
+                creators: None,
+            })
+            .invoke_signed(&[authority_seeds])?;
+
+        coupon.metadata_uri = uri.clone();
+
+        emit!(CouponMetadataUpdated {
+            coupon: coupon.key(),
+            nft_mint: self.mint.key(),
+            state,
+            uri,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+// src/utils/mod.rs
+pub mod payments;
+pub mod revenue;
+pub mod metadata;
+pub mod mpl_core;
+pub mod badges;
+pub mod ed25519;
+
+pub use payments::*;
+pub use revenue::*;
+pub use metadata::*;
+pub use mpl_core::*;
+pub use badges::*;
+pub use ed25519::*;
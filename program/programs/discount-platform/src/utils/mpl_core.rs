@@ -0,0 +1,103 @@
+// src/utils/mpl_core.rs
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use crate::errors::CouponError;
+
+/// Metaplex Core program. There is no Core crate in the dependency tree, so
+/// the handful of instructions we need are encoded by hand below.
+pub const MPL_CORE_ID: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+
+const CREATE_V1: u8 = 0;
+const BURN_V1: u8 = 12;
+const DATA_STATE_ACCOUNT: u8 = 0;
+const ASSET_V1_KEY: u8 = 1;
+
+/// Owner recorded in a Core asset account (`Key::AssetV1`, then the owner).
+pub fn core_asset_owner(asset: &AccountInfo) -> Result<Pubkey> {
+    require_keys_eq!(*asset.owner, MPL_CORE_ID, CouponError::InvalidCoreAsset);
+    let data = asset.try_borrow_data()?;
+    require!(data.len() >= 33 && data[0] == ASSET_V1_KEY, CouponError::InvalidCoreAsset);
+    Ok(Pubkey::try_from(&data[1..33]).map_err(|_| CouponError::InvalidCoreAsset)?)
+}
+
+/// Accounts for a Core `CreateV1`, with `authority` as both the signing
+/// authority and the asset's update authority.
+pub struct CoreAssetCpi<'info> {
+    pub core_program: AccountInfo<'info>,
+    pub asset: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub owner: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> CoreAssetCpi<'info> {
+    /// Creates the asset for `owner` with the given name and URI.
+    pub fn create(&self, name: String, uri: String, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let mut data = vec![CREATE_V1, DATA_STATE_ACCOUNT];
+        name.serialize(&mut data)?;
+        uri.serialize(&mut data)?;
+        data.push(0); // No plugins, see Promotion::require_enforceable_terms
+
+        // Unused optional accounts are passed as the Core program id
+        let instruction = Instruction {
+            program_id: MPL_CORE_ID,
+            accounts: vec![
+                AccountMeta::new(self.asset.key(), true),
+                AccountMeta::new_readonly(MPL_CORE_ID, false), // collection
+                AccountMeta::new_readonly(self.authority.key(), true),
+                AccountMeta::new(self.payer.key(), true),
+                AccountMeta::new_readonly(self.owner.key(), false),
+                AccountMeta::new_readonly(self.authority.key(), false), // update authority
+                AccountMeta::new_readonly(self.system_program.key(), false),
+                AccountMeta::new_readonly(MPL_CORE_ID, false), // log wrapper
+            ],
+            data,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[
+                self.asset.clone(),
+                self.core_program.clone(),
+                self.authority.clone(),
+                self.payer.clone(),
+                self.owner.clone(),
+                self.system_program.clone(),
+            ],
+            signer_seeds,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// `BurnV1` signed by the asset owner, who also receives the reclaimed rent.
+pub fn burn_core_asset<'info>(
+    core_program: &AccountInfo<'info>,
+    asset: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let instruction = Instruction {
+        program_id: MPL_CORE_ID,
+        accounts: vec![
+            AccountMeta::new(asset.key(), false),
+            AccountMeta::new_readonly(MPL_CORE_ID, false), // collection
+            AccountMeta::new(owner.key(), true),           // payer
+            AccountMeta::new_readonly(owner.key(), true),  // authority
+            AccountMeta::new_readonly(system_program.key(), false),
+            AccountMeta::new_readonly(MPL_CORE_ID, false), // log wrapper
+        ],
+        data: vec![BURN_V1, 0], // No compression proof
+    };
+
+    invoke_signed(
+        &instruction,
+        &[asset.clone(), core_program.clone(), owner.clone(), system_program.clone()],
+        &[],
+    )?;
+
+    Ok(())
+}
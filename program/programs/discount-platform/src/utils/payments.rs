@@ -0,0 +1,154 @@
+// src/utils/payments.rs
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{
+    self,
+    Mint,
+    TokenAccount,
+    TokenInterface,
+    TransferChecked,
+};
+use crate::errors::CouponError;
+
+/// Settlement route for a single instruction: native SOL through the system
+/// program, or an allow-listed SPL / Token-2022 mint through `transfer_checked`.
+pub struct PaymentRail<'info> {
+    system_program: AccountInfo<'info>,
+    token: Option<TokenRail<'info>>,
+}
+
+struct TokenRail<'info> {
+    mint: AccountInfo<'info>,
+    mint_key: Pubkey,
+    decimals: u8,
+    token_program: AccountInfo<'info>,
+}
+
+impl<'info> PaymentRail<'info> {
+    /// Builds the rail for `payment_mint` (None = SOL) and checks that the
+    /// optional mint / token program accounts passed in match it.
+    pub fn resolve(
+        payment_mint: Option<Pubkey>,
+        mint: &Option<InterfaceAccount<'info, Mint>>,
+        token_program: &Option<Interface<'info, TokenInterface>>,
+        system_program: &Program<'info, System>,
+    ) -> Result<Self> {
+        let token = match payment_mint {
+            None => None,
+            Some(expected) => {
+                let mint = mint.as_ref().ok_or(CouponError::InvalidPaymentMint)?;
+                let token_program = token_program.as_ref().ok_or(CouponError::InvalidPaymentMint)?;
+                require_keys_eq!(mint.key(), expected, CouponError::InvalidPaymentMint);
+                require_keys_eq!(
+                    *mint.to_account_info().owner,
+                    token_program.key(),
+                    CouponError::InvalidPaymentMint
+                );
+
+                Some(TokenRail {
+                    mint: mint.to_account_info(),
+                    mint_key: expected,
+                    decimals: mint.decimals,
+                    token_program: token_program.to_account_info(),
+                })
+            }
+        };
+
+        Ok(Self {
+            system_program: system_program.to_account_info(),
+            token,
+        })
+    }
+
+    pub fn is_token(&self) -> bool {
+        self.token.is_some()
+    }
+
+    /// Account that actually sends or receives funds for `wallet`: the wallet
+    /// itself for SOL, or its token account for the payment mint.
+    pub fn endpoint(
+        &self,
+        wallet: &AccountInfo<'info>,
+        token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+    ) -> Result<AccountInfo<'info>> {
+        match &self.token {
+            None => Ok(wallet.clone()),
+            Some(rail) => {
+                let token_account = token_account
+                    .as_ref()
+                    .ok_or(CouponError::InvalidPaymentAccount)?;
+                require_keys_eq!(token_account.mint, rail.mint_key, CouponError::InvalidPaymentAccount);
+                require_keys_eq!(token_account.owner, wallet.key(), CouponError::InvalidPaymentAccount);
+                Ok(token_account.to_account_info())
+            }
+        }
+    }
+
+    /// Same as `endpoint`, for a payee passed through `remaining_accounts`:
+    /// the wallet itself for SOL, or a token account it owns for the mint.
+    pub fn remaining_endpoint(
+        &self,
+        wallet: &Pubkey,
+        account: &AccountInfo<'info>,
+    ) -> Result<AccountInfo<'info>> {
+        require!(account.is_writable, CouponError::InvalidPaymentAccount);
+
+        match &self.token {
+            None => {
+                require_keys_eq!(account.key(), *wallet, CouponError::InvalidPaymentAccount);
+            }
+            Some(rail) => {
+                require_keys_eq!(*account.owner, rail.token_program.key(), CouponError::InvalidPaymentAccount);
+                let data = account.try_borrow_data()?;
+                let token_account = TokenAccount::try_deserialize(&mut &data[..])?;
+                require_keys_eq!(token_account.mint, rail.mint_key, CouponError::InvalidPaymentAccount);
+                require_keys_eq!(token_account.owner, *wallet, CouponError::InvalidPaymentAccount);
+            }
+        }
+
+        Ok(account.clone())
+    }
+
+    /// Moves `amount` between two endpoints. `authority` is the wallet that
+    /// owns `from`; pass `signer_seeds` when it is a PDA.
+    pub fn transfer(
+        &self,
+        from: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        amount: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        match &self.token {
+            None => system_program::transfer(
+                CpiContext::new_with_signer(
+                    self.system_program.clone(),
+                    system_program::Transfer {
+                        from: from.clone(),
+                        to: to.clone(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            ),
+            Some(rail) => token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    rail.token_program.clone(),
+                    TransferChecked {
+                        from: from.clone(),
+                        mint: rail.mint.clone(),
+                        to: to.clone(),
+                        authority: authority.clone(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                rail.decimals,
+            ),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// src/utils/revenue.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use crate::state::{Merchant, RevenueConfig};
+use crate::errors::CouponError;
+use crate::events::RevenueSplitPaid;
+use super::PaymentRail;
+
+/// Where a merchant's share of a sale goes. Without a revenue config
+/// everything goes to `merchant_to`; with one, each recipient's endpoint must
+/// be passed in `recipient_accounts`, in config order. `source` is the sale
+/// (promotion, group deal, ...) recorded on the payout events.
+pub struct MerchantRevenue<'a, 'info> {
+    pub merchant: &'a Account<'info, Merchant>,
+    pub merchant_to: &'a AccountInfo<'info>,
+    pub revenue_config: Option<&'a RevenueConfig>,
+    pub recipient_accounts: &'a [AccountInfo<'info>],
+    pub source: Pubkey,
+}
+
+impl<'a, 'info> MerchantRevenue<'a, 'info> {
+    /// Pays `amount` out of `from` to the merchant or its split recipients
+    pub fn distribute(
+        &self,
+        rail: &PaymentRail<'info>,
+        from: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        amount: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let merchant = self.merchant;
+        require!(
+            merchant.has_revenue_split == self.revenue_config.is_some(),
+            CouponError::RevenueSplitMismatch
+        );
+        let timestamp = Clock::get()?.unix_timestamp;
+
+        let Some(config) = self.revenue_config else {
+            rail.transfer(from, self.merchant_to, authority, amount, signer_seeds)?;
+            emit!(RevenueSplitPaid {
+                merchant: merchant.key(),
+                source: self.source,
+                recipient: merchant.authority,
+                share_bps: RevenueConfig::TOTAL_BPS,
+                amount,
+                timestamp,
+            });
+            return Ok(());
+        };
+
+        require!(
+            self.recipient_accounts.len() >= config.recipients.len(),
+            CouponError::RevenueSplitMismatch
+        );
+
+        for ((share, part), account) in config
+            .recipients
+            .iter()
+            .zip(config.split(amount))
+            .zip(self.recipient_accounts)
+        {
+            let to = rail.remaining_endpoint(&share.recipient, account)?;
+            rail.transfer(from, &to, authority, part, signer_seeds)?;
+
+            emit!(RevenueSplitPaid {
+                merchant: merchant.key(),
+                source: self.source,
+                recipient: share.recipient,
+                share_bps: share.share_bps,
+                amount: part,
+                timestamp,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Merchant royalty on a secondary sale, paid out like any other merchant
+/// income: to the merchant authority, or through its revenue split.
+pub struct ResaleRoyalty<'a, 'info> {
+    pub merchant: &'a Account<'info, Merchant>,
+    pub merchant_authority: AccountInfo<'info>,
+    pub merchant_payment_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    pub revenue_config: Option<&'a RevenueConfig>,
+    pub recipient_accounts: &'a [AccountInfo<'info>],
+    pub promotion: Pubkey,
+    pub basis_points: u16,
+}
+
+impl<'a, 'info> ResaleRoyalty<'a, 'info> {
+    pub fn calculate(&self, price: u64) -> u64 {
+        ((price as u128 * self.basis_points as u128) / 10000) as u64
+    }
+
+    /// Pays the royalty on a sale at `price` out of `from` and returns it.
+    /// Merchant payout accounts are only needed when a royalty is owed.
+    pub fn pay(
+        &self,
+        rail: &PaymentRail<'info>,
+        from: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+        price: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<u64> {
+        let royalty = self.calculate(price);
+        if royalty == 0 {
+            return Ok(0);
+        }
+
+        let merchant_to = rail.endpoint(&self.merchant_authority, self.merchant_payment_account)?;
+        MerchantRevenue {
+            merchant: self.merchant,
+            merchant_to: &merchant_to,
+            revenue_config: self.revenue_config,
+            recipient_accounts: self.recipient_accounts,
+            source: self.promotion,
+        }
+        .distribute(rail, from, authority, royalty, signer_seeds)?;
+
+        Ok(royalty)
+    }
+}
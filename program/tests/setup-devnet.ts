@@ -43,6 +43,7 @@ export interface TestAccounts {
   user1: Keypair;
   user2: Keypair;
   marketplacePDA: PublicKey;
+  treasuryPDA: PublicKey;
   merchant1PDA: PublicKey;
   merchant2PDA: PublicKey;
 }
@@ -233,6 +234,11 @@ async function setupTestAccountsLocal(
     [Buffer.from("marketplace")], 
     program.programId
   );
+
+  const [treasuryPDA] = derivePDA(
+    [Buffer.from("treasury")],
+    program.programId
+  );
   
   const [merchant1PDA] = derivePDA(
     [Buffer.from("merchant"), merchant1.publicKey.toBuffer()],
@@ -251,6 +257,7 @@ async function setupTestAccountsLocal(
     user1,
     user2,
     marketplacePDA,
+    treasuryPDA,
     merchant1PDA,
     merchant2PDA,
   };
@@ -304,6 +311,11 @@ async function setupTestAccountsDevnet(
     program.programId
   );
 
+  const [treasuryPDA] = derivePDA(
+    [Buffer.from("treasury")],
+    program.programId
+  );
+
   const [merchant1PDA] = derivePDA(
     [Buffer.from("merchant"), merchant1.publicKey.toBuffer()],
     program.programId
@@ -326,6 +338,7 @@ async function setupTestAccountsDevnet(
     user1,
     user2,
     marketplacePDA,
+    treasuryPDA,
     merchant1PDA,
     merchant2PDA,
   };
@@ -348,6 +361,7 @@ export async function setupTestAccountsWithMarketplace(
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -46,6 +46,7 @@ export interface TestAccounts {
   user1: Keypair;
   user2: Keypair;
   marketplacePDA: PublicKey;
+  treasuryPDA: PublicKey;
   merchant1PDA: PublicKey;
   merchant2PDA: PublicKey;
 }
@@ -286,6 +287,11 @@ export async function setupTestAccounts(
     [Buffer.from("marketplace")], 
     program.programId
   );
+
+  const [treasuryPDA] = derivePDA(
+    [Buffer.from("treasury")],
+    program.programId
+  );
   
   const [merchant1PDA] = derivePDA(
     [Buffer.from("merchant"), merchant1.publicKey.toBuffer()],
@@ -304,6 +310,7 @@ export async function setupTestAccounts(
     user1,
     user2,
     marketplacePDA,
+    treasuryPDA,
     merchant1PDA,
     merchant2PDA,
   };
@@ -329,6 +336,7 @@ export async function setupTestAccountsWithMarketplace(
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -51,6 +51,7 @@ export interface TestAccounts {
   user1: Keypair;
   user2: Keypair;
   marketplacePDA: PublicKey;
+  treasuryPDA: PublicKey;
   merchant1PDA: PublicKey;
   merchant2PDA: PublicKey;
 }
@@ -293,6 +294,11 @@ async function setupTestAccountsLocal(
     [Buffer.from("marketplace")], 
     program.programId
   );
+
+  const [treasuryPDA] = derivePDA(
+    [Buffer.from("treasury")],
+    program.programId
+  );
   
   const [merchant1PDA] = derivePDA(
     [Buffer.from("merchant"), merchant1.publicKey.toBuffer()],
@@ -311,6 +317,7 @@ async function setupTestAccountsLocal(
     user1,
     user2,
     marketplacePDA,
+    treasuryPDA,
     merchant1PDA,
     merchant2PDA,
   };
@@ -423,6 +430,11 @@ async function setupTestAccountsPublic(
     program.programId
   );
 
+  const [treasuryPDA] = derivePDA(
+    [Buffer.from("treasury")],
+    program.programId
+  );
+
   const [merchant1PDA] = derivePDA(
     [Buffer.from("merchant"), merchant1.publicKey.toBuffer()],
     program.programId
@@ -445,6 +457,7 @@ async function setupTestAccountsPublic(
     user1,
     user2,
     marketplacePDA,
+    treasuryPDA,
     merchant1PDA,
     merchant2PDA,
   };
@@ -472,6 +485,7 @@ export async function setupTestAccountsWithMarketplace(
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -41,6 +41,7 @@ export interface TestAccounts {
   user1: Keypair;
   user2: Keypair;
   marketplacePDA: PublicKey;
+  treasuryPDA: PublicKey;
   merchant1PDA: PublicKey;
   merchant2PDA: PublicKey;
 }
@@ -140,6 +141,11 @@ export async function setupTestAccounts(
     [Buffer.from("marketplace")], 
     program.programId
   );
+
+  const [treasuryPDA] = derivePDA(
+    [Buffer.from("treasury")],
+    program.programId
+  );
   
   const [merchant1PDA] = derivePDA(
     [Buffer.from("merchant"), merchant1.publicKey.toBuffer()],
@@ -158,6 +164,7 @@ export async function setupTestAccounts(
     user1,
     user2,
     marketplacePDA,
+    treasuryPDA,
     merchant1PDA,
     merchant2PDA,
   };
@@ -180,6 +187,7 @@ export async function setupTestAccountsWithMarketplace(
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
@@ -196,6 +204,64 @@ export async function setupTestAccountsWithMarketplace(
   return accounts;
 }
 
+// createPromotion arguments, in instruction order (the nft* fields form CouponNftConfig)
+export interface PromotionArgs {
+  discountPercentage: number;
+  maxSupply: number;
+  expiryTimestamp: BN;
+  category: string;
+  description: string;
+  price: BN;
+  paymentMint: PublicKey | null;
+  metadataBaseUri: string;
+  nftName: string;
+  nftSymbol: string;
+  issuanceMode: object;
+  geofence: object | null;
+  redemptionModel: object;
+  transferable: boolean;
+  resaleAllowed: boolean;
+}
+
+// Helper function: createPromotion with defaults for anything the test doesn't set.
+// Returns the method builder so callers add their own accounts and signers.
+export function createPromotion(program: Program<any>, args: Partial<PromotionArgs> = {}) {
+  const a: PromotionArgs = {
+    discountPercentage: 50,
+    maxSupply: 100,
+    expiryTimestamp: getExpiryTimestamp(30),
+    category: "food",
+    description: "Test promotion",
+    price: new BN(5 * LAMPORTS_PER_SOL),
+    paymentMint: null,
+    metadataBaseUri: "https://example.com/coupons",
+    nftName: "Test Coupon",
+    nftSymbol: "TC",
+    issuanceMode: { tokenMetadata: {} },
+    geofence: null,
+    redemptionModel: { singleUse: {} },
+    transferable: true,
+    resaleAllowed: true,
+    ...args,
+  };
+
+  return program.methods.createPromotion(
+    a.discountPercentage,
+    a.maxSupply,
+    a.expiryTimestamp,
+    a.category,
+    a.description,
+    a.price,
+    a.paymentMint,
+    { metadataBaseUri: a.metadataBaseUri, name: a.nftName, symbol: a.nftSymbol },
+    a.issuanceMode,
+    a.geofence,
+    a.redemptionModel,
+    a.transferable,
+    a.resaleAllowed
+  );
+}
+
 // Helper function: Wait for a short time
 export function wait(ms: number): Promise<void> {
   return new Promise(resolve => setTimeout(resolve, ms));
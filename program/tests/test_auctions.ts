@@ -4,6 +4,7 @@ import { DiscountPlatform } from "../target/types/discount_platform";
 import { SystemProgram, Keypair, PublicKey } from "@solana/web3.js";
 import { getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { assert, expect } from "chai";
+import { createHash, randomBytes } from "crypto";
 import { 
   setupTestAccounts, 
   TestAccounts,
@@ -19,6 +20,7 @@ import {
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_METADATA_PROGRAM_ID,
+  createPromotion,
 } from "./setup";
 
 describe("Auctions", () => {
@@ -45,6 +47,7 @@ describe("Auctions", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -82,15 +85,11 @@ describe("Auctions", () => {
     const promotionExists = await accountExists(connection, promotionPDA);
     if (!promotionExists) {
       console.log("Creating promotion...");
-      await program.methods
-        .createPromotion(
-          20, // 20% base discount
-          100, // max supply
-          getExpiryTimestamp(60),
-          "food",
-          "Auction promotion",
-          new BN(5 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, {
+        discountPercentage: 20,
+        expiryTimestamp: getExpiryTimestamp(60),
+        description: "Auction promotion",
+      })
         .accounts({
           promotion: promotionPDA,
           merchant: accounts.merchant1PDA,
@@ -104,6 +103,49 @@ describe("Auctions", () => {
     console.log("✓ Setup complete");
   });
 
+  // createAuction's AuctionTerms argument, with anything the test doesn't set left off
+  function auctionTerms(terms: Record<string, any> = {}) {
+    return {
+      minBidIncrement: new BN(0),
+      antiSniping: null,
+      paymentMint: null,
+      sealedBidConfig: null,
+      buyNowPrice: null,
+      dutchCurve: null,
+      ...terms,
+    };
+  }
+
+  // NFT escrow accounts: auctions hold the coupon NFT in an [auction_vault, auction] PDA
+  async function auctionVaultAccounts(auction: PublicKey, coupon: PublicKey) {
+    const couponAccount = await program.account.coupon.fetch(coupon);
+    const [auctionVault] = derivePDA(
+      [Buffer.from("auction_vault"), auction.toBuffer()],
+      program.programId
+    );
+    return {
+      nftMint: couponAccount.mint,
+      sellerTokenAccount: getAssociatedTokenAddressSync(couponAccount.mint, couponAccount.owner),
+      auctionVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+  }
+
+  // Merchant accounts that collect the resale royalty when an auction settles
+  async function royaltyAccounts(coupon: PublicKey) {
+    const { promotion } = await program.account.coupon.fetch(coupon);
+    const { merchant } = await program.account.promotion.fetch(promotion);
+    const { authority: merchantAuthority } = await program.account.merchant.fetch(merchant);
+    return { promotion, merchant, merchantAuthority };
+  }
+
+  async function cancelAuctionAccounts(auction: PublicKey) {
+    const { coupon } = await program.account.couponAuction.fetch(auction);
+    const { promotion } = await program.account.coupon.fetch(coupon);
+    const { sellerTokenAccount, auctionVault, tokenProgram } = await auctionVaultAccounts(auction, coupon);
+    return { coupon, promotion, sellerTokenAccount, auctionVault, tokenProgram };
+  }
+
   describe("English Auctions", () => {
     it("Test 39: Creates English auction successfully", async () => {
       console.log("\n=== TEST 39: Create English auction ===");
@@ -186,12 +228,20 @@ describe("Auctions", () => {
           startingPrice,
           reservePrice,
           new BN(3600), // 1 hour duration
-          true, // auto_extend
-          minIncrement
+          auctionTerms({
+            minBidIncrement: minIncrement,
+            antiSniping: {
+              extensionWindowSeconds: new BN(300), // bids in the last 5 minutes
+              extensionSeconds: new BN(300),
+              maxTotalExtensionSeconds: new BN(3600),
+            },
+          })
         )
         .accounts({
+          ...(await auctionVaultAccounts(auctionPDA, couponPDA)),
           auction: auctionPDA,
           coupon: couponPDA,
+          promotion: promotionPDA,
           userStats: sellerStatsPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -212,8 +262,22 @@ describe("Auctions", () => {
       assert.equal(auction.isActive, true);
       assert.equal(auction.isFinalized, false);
       assert.equal(auction.autoExtend, true);
+      assert.equal(auction.extensionWindowSeconds.toNumber(), 300);
+      assert.equal(auction.extensionSeconds.toNumber(), 300);
+      assert.equal(auction.maxTotalExtensionSeconds.toNumber(), 3600);
+      assert.equal(auction.totalExtensionSeconds.toNumber(), 0);
+      assert.equal(auction.buyNowPrice, null);
       assert.equal(auction.minBidIncrement.toString(), minIncrement.toString());
 
+      // Verify the NFT moved into the auction vault
+      const escrowedCoupon = await program.account.coupon.fetch(couponPDA);
+      assert.isTrue(escrowedCoupon.isEscrowed);
+      const { auctionVault } = await auctionVaultAccounts(auctionPDA, couponPDA);
+      const vaultBalance = await connection.getTokenAccountBalance(auctionVault);
+      assert.equal(vaultBalance.value.amount, "1");
+      const sellerBalance = await connection.getTokenAccountBalance(tokenAccount);
+      assert.equal(sellerBalance.value.amount, "0");
+
       // Verify user stats updated
       const userStats = await program.account.userStats.fetch(sellerStatsPDA);
       assert.equal(userStats.totalListings, 1);
@@ -251,12 +315,15 @@ describe("Auctions", () => {
             new BN(0.5 * LAMPORTS_PER_SOL), // starting
             new BN(1 * LAMPORTS_PER_SOL), // reserve > starting (invalid)
             new BN(3600),
-            false,
-            new BN(0.1 * LAMPORTS_PER_SOL)
+            auctionTerms({
+              minBidIncrement: new BN(0.1 * LAMPORTS_PER_SOL),
+            })
           )
           .accounts({
+            ...(await auctionVaultAccounts(auctionPDA, couponPDA)),
             auction: auctionPDA,
             coupon: couponPDA,
+            promotion: promotionPDA,
             userStats: sellerStatsPDA,
             seller: accounts.user1.publicKey,
             systemProgram: SystemProgram.programId,
@@ -271,6 +338,33 @@ describe("Auctions", () => {
       }
     });
 
+    it("Blocks redeeming a coupon while it is in an auction", async () => {
+      try {
+        await program.methods
+          .redeemCoupon(new BN(1), new BN(0))
+          .accounts({
+            coupon: couponPDA,
+            nftMint: couponMint.publicKey,
+            promotion: promotionPDA,
+            metadata: deriveMetadataPDA(couponMint.publicKey)[0],
+            tokenAccount: getAssociatedTokenAddressSync(
+              couponMint.publicKey,
+              accounts.user1.publicKey
+            ),
+            merchant: accounts.merchant1PDA,
+            user: accounts.user1.publicKey,
+            merchantAuthority: accounts.merchant1.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([accounts.user1, accounts.merchant1])
+          .rpc();
+
+        assert.fail("Should have failed with CouponEscrowed");
+      } catch (error) {
+        assert.include(error.message, "CouponEscrowed");
+      }
+    });
+
     it("Test 41: Places bid on English auction successfully", async () => {
       console.log("\n=== TEST 41: Place bid ===");
 
@@ -317,7 +411,6 @@ describe("Auctions", () => {
           bid: bidPDA,
           auction: auctionPDA,
           escrow: escrowPDA,
-          previousBidder: accounts.user1.publicKey, // Seller as placeholder for first bid
           userStats: bidderStatsPDA,
           bidder: accounts.user2.publicKey,
           systemProgram: SystemProgram.programId,
@@ -352,8 +445,8 @@ describe("Auctions", () => {
       console.log("  Escrow balance:", escrowBalance / LAMPORTS_PER_SOL, "SOL");
     });
 
-    it("Test 42: Refunds previous bidder when outbid", async () => {
-      console.log("\n=== TEST 42: Refund previous bidder ===");
+    it("Test 42: Outbid bidder withdraws their bid", async () => {
+      console.log("\n=== TEST 42: Withdraw outbid bid ===");
 
       const auctionId = new BN(1);
       const [auctionPDA] = derivePDA(
@@ -389,8 +482,17 @@ describe("Auctions", () => {
         program.programId
       );
 
-      const user2BalanceBefore = await connection.getBalance(accounts.user2.publicKey);
-      const escrowBalanceBefore = await connection.getBalance(escrowPDA);
+      // user2's bid from Test 41
+      const [user2BidPDA] = derivePDA(
+        [
+          Buffer.from("bid"),
+          auctionPDA.toBuffer(),
+          accounts.user2.publicKey.toBuffer(),
+          u32ToLeBytes(0),
+        ],
+        program.programId
+      );
+
       const newBidAmount = new BN(0.8 * LAMPORTS_PER_SOL);
 
       await program.methods
@@ -399,7 +501,6 @@ describe("Auctions", () => {
           bid: newBidPDA,
           auction: auctionPDA,
           escrow: escrowPDA,
-          previousBidder: accounts.user2.publicKey, // Previous highest bidder
           userStats: bidderStatsPDA,
           bidder: accounts.merchant1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -407,15 +508,50 @@ describe("Auctions", () => {
         .signers([accounts.merchant1])
         .rpc();
 
-      // Verify previous bidder refunded
-      const user2BalanceAfter = await connection.getBalance(accounts.user2.publicKey);
+      // Outbid funds stay in escrow until withdrawn
       const refundAmount = 0.6 * LAMPORTS_PER_SOL;
-      assert.approximately(
-        user2BalanceAfter - user2BalanceBefore,
-        refundAmount,
-        0.01 * LAMPORTS_PER_SOL
+      assert.equal(
+        await connection.getBalance(escrowPDA),
+        refundAmount + newBidAmount.toNumber()
       );
 
+      // The leading bid cannot be withdrawn
+      try {
+        await program.methods
+          .withdrawBid()
+          .accounts({
+            bid: newBidPDA,
+            auction: auctionPDA,
+            escrow: escrowPDA,
+            bidder: accounts.merchant1.publicKey,
+            seller: accounts.user1.publicKey,
+          })
+          .rpc();
+        assert.fail("Should not withdraw the winning bid");
+      } catch (error) {
+        assert.include(error.toString(), "BidStillWinning");
+      }
+
+      const user2BalanceBefore = await connection.getBalance(accounts.user2.publicKey);
+      const bidRent = await connection.getBalance(user2BidPDA);
+
+      // Anyone can trigger the withdrawal; funds always go to the bidder
+      await program.methods
+        .withdrawBid()
+        .accounts({
+          bid: user2BidPDA,
+          auction: auctionPDA,
+          escrow: escrowPDA,
+          bidder: accounts.user2.publicKey,
+          seller: accounts.user1.publicKey,
+        })
+        .rpc();
+
+      // Verify bid refunded and its account closed
+      const user2BalanceAfter = await connection.getBalance(accounts.user2.publicKey);
+      assert.equal(user2BalanceAfter - user2BalanceBefore, refundAmount + bidRent);
+      assert.isFalse(await accountExists(connection, user2BidPDA));
+
       // Verify new bid is winning
       const newBid = await program.account.bid.fetch(newBidPDA);
       assert.equal(newBid.isWinning, true);
@@ -427,11 +563,11 @@ describe("Auctions", () => {
       assert.equal(auctionAfter.highestBidder.toString(), accounts.merchant1.publicKey.toString());
       assert.equal(auctionAfter.bidCount, 2);
 
-      // Verify escrow balance updated (old bid refunded, new bid added)
+      // Verify escrow balance updated (old bid withdrawn, new bid held)
       const escrowBalanceAfter = await connection.getBalance(escrowPDA);
       assert.equal(escrowBalanceAfter, newBidAmount.toNumber());
 
-      console.log("✓ Previous bidder refunded successfully");
+      console.log("✓ Outbid bidder withdrew successfully");
       console.log("  Refund amount:", refundAmount / LAMPORTS_PER_SOL, "SOL");
       console.log("  New escrow balance:", escrowBalanceAfter / LAMPORTS_PER_SOL, "SOL");
     });
@@ -483,7 +619,6 @@ describe("Auctions", () => {
             bid: bidPDA,
             auction: auctionPDA,
             escrow: escrowPDA,
-            previousBidder: accounts.merchant1.publicKey,
             userStats: bidderStatsPDA,
             bidder: accounts.user2.publicKey,
             systemProgram: SystemProgram.programId,
@@ -569,12 +704,15 @@ describe("Auctions", () => {
           new BN(0.5 * LAMPORTS_PER_SOL),
           new BN(0.4 * LAMPORTS_PER_SOL),
           new BN(300), // 5 minutes
-          false,
-          new BN(0.1 * LAMPORTS_PER_SOL)
+          auctionTerms({
+            minBidIncrement: new BN(0.1 * LAMPORTS_PER_SOL),
+          })
         )
         .accounts({
+          ...(await auctionVaultAccounts(expiredAuctionPDA, newCouponPDA)),
           auction: expiredAuctionPDA,
           coupon: newCouponPDA,
+          promotion: promotionPDA,
           userStats: userStatsPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -591,8 +729,9 @@ describe("Auctions", () => {
       console.log("\n=== TEST 45: Auto-extend ===");
 
       console.log("  Note: Cannot test auto-extend on local validator");
-      console.log("  Contract logic: extends by 300 seconds if bid within last 5 minutes");
-      console.log("  Requires: auto_extend=true && (end_time - current_time) < 300");
+      console.log("  Contract logic: extends by extension_seconds if bid within the extension window");
+      console.log("  Requires: (end_time - current_time) < extension_window_seconds");
+      console.log("  Total extension is capped at max_total_extension_seconds");
       console.log("✓ Auto-extend logic exists in contract");
     });
 
@@ -717,12 +856,13 @@ describe("Auctions", () => {
           startingPrice,
           reservePrice,
           duration,
-          false,
-          new BN(0)
+          auctionTerms()
         )
         .accounts({
+          ...(await auctionVaultAccounts(dutchAuctionPDA, dutchCouponPDA)),
           auction: dutchAuctionPDA,
           coupon: dutchCouponPDA,
+          promotion: promotionPDA,
           userStats: userStatsPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -774,12 +914,13 @@ describe("Auctions", () => {
             new BN(0.5 * LAMPORTS_PER_SOL), // starting
             new BN(2 * LAMPORTS_PER_SOL), // reserve >= starting (invalid)
             new BN(3600),
-            false,
-            new BN(0)
+            auctionTerms()
           )
           .accounts({
+            ...(await auctionVaultAccounts(auctionPDA, dutchCouponPDA)),
             auction: auctionPDA,
             coupon: dutchCouponPDA,
+            promotion: promotionPDA,
             userStats: userStatsPDA,
             seller: accounts.user1.publicKey,
             systemProgram: SystemProgram.programId,
@@ -794,6 +935,53 @@ describe("Auctions", () => {
       }
     });
 
+    it("Rejects a stepped Dutch curve with a zero step", async () => {
+      const auctionId = new BN(202);
+      const [auctionPDA] = derivePDA(
+        [
+          Buffer.from("auction"),
+          dutchCouponPDA.toBuffer(),
+          u64ToLeBytes(auctionId),
+        ],
+        program.programId
+      );
+
+      const [userStatsPDA] = derivePDA(
+        [Buffer.from("user_stats"), accounts.user1.publicKey.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .createAuction(
+            auctionId,
+            { dutch: {} },
+            new BN(1 * LAMPORTS_PER_SOL),
+            new BN(0.5 * LAMPORTS_PER_SOL),
+            new BN(3600),
+            auctionTerms({
+              dutchCurve: { stepped: { stepSeconds: new BN(600), stepAmount: new BN(0) } },
+            })
+          )
+          .accounts({
+            ...(await auctionVaultAccounts(auctionPDA, dutchCouponPDA)),
+            auction: auctionPDA,
+            coupon: dutchCouponPDA,
+            promotion: promotionPDA,
+            userStats: userStatsPDA,
+            seller: accounts.user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.user1])
+          .rpc();
+
+        assert.fail("Should have failed with InvalidPriceCurve");
+      } catch (error) {
+        assert.include(error.message, "InvalidPriceCurve");
+        console.log("✓ Correctly rejected invalid Dutch curve");
+      }
+    });
+
     it("Test 51: Calculates Dutch auction price correctly", async () => {
       console.log("\n=== TEST 51: Dutch price calculation ===");
 
@@ -820,8 +1008,19 @@ describe("Auctions", () => {
       console.log("  Price at T+0:", price0 / LAMPORTS_PER_SOL, "SOL (starting)");
       console.log("  Price at T+1h:", price3600 / LAMPORTS_PER_SOL, "SOL");
       console.log("  Price at T+2h:", price7200 / LAMPORTS_PER_SOL, "SOL (reserve)");
+
+      // The on-chain view follows the default linear curve
+      assert.deepEqual(auction.dutchCurve, { linear: {} });
+      const onChainPrice = await program.methods
+        .getDutchPrice()
+        .accounts({ auction: dutchAuctionPDA })
+        .view();
+      const elapsed = Math.min(Math.max(getCurrentTimestamp() - startTime, 0), duration);
+      const expected = startingPrice - Math.floor(priceRange * elapsed / duration);
+      assert.approximately(onChainPrice.toNumber(), expected, priceRange * 60 / duration);
+
       console.log("✓ Dutch price calculation verified");
-      console.log("  Contract method: calculate_dutch_price(current_time)");
+      console.log("  On-chain price:", onChainPrice.toNumber() / LAMPORTS_PER_SOL, "SOL");
     });
 
     it("Test 52: Buys Dutch auction successfully", async () => {
@@ -856,6 +1055,7 @@ describe("Auctions", () => {
   describe("Sealed Bid Auctions", () => {
     let sealedCouponPDA: PublicKey;
     let sealedAuctionPDA: PublicKey;
+    const sealedBids: { bid: PublicKey; amount: BN; salt: Buffer }[] = [];
 
     it("Test 55: Creates sealed bid auction successfully", async () => {
       console.log("\n=== TEST 55: Create sealed bid auction ===");
@@ -928,12 +1128,20 @@ describe("Auctions", () => {
           new BN(1 * LAMPORTS_PER_SOL),
           new BN(0.8 * LAMPORTS_PER_SOL),
           new BN(21600), // 6 hours
-          false,
-          new BN(0.1 * LAMPORTS_PER_SOL)
+          auctionTerms({
+            minBidIncrement: new BN(0.1 * LAMPORTS_PER_SOL),
+            sealedBidConfig: {
+              revealDurationSeconds: new BN(3600), // 1 hour
+              isVickrey: true,
+              unrevealedPolicy: { forfeit: {} },
+            },
+          })
         )
         .accounts({
+          ...(await auctionVaultAccounts(sealedAuctionPDA, sealedCouponPDA)),
           auction: sealedAuctionPDA,
           coupon: sealedCouponPDA,
+          promotion: promotionPDA,
           userStats: userStatsPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -945,13 +1153,24 @@ describe("Auctions", () => {
       const auction = await program.account.couponAuction.fetch(sealedAuctionPDA);
       assert.deepEqual(auction.auctionType, { sealedBid: {} });
       assert.equal(auction.bidCount, 0);
+      assert.equal(auction.revealEndTime.toNumber(), auction.endTime.toNumber() + 3600);
+      assert.equal(auction.isVickrey, true);
+      assert.deepEqual(auction.unrevealedPolicy, { forfeit: {} });
 
       console.log("✓ Sealed bid auction created successfully");
-      console.log("  Duration: 6 hours");
-      console.log("  Bids are private until finalization");
+      console.log("  Duration: 6 hours, then a 1 hour reveal window");
+      console.log("  Bids are committed as hashes and revealed after bidding closes");
     });
 
-    it("Test 56: Multiple bidders place sealed bids", async () => {
+    // sha256(amount_le_u64 || salt || bidder || auction), matching Bid::commitment_hash
+    function sealedCommitment(amount: BN, salt: Buffer, bidder: PublicKey, auction: PublicKey): number[] {
+      const digest = createHash("sha256")
+        .update(Buffer.concat([amount.toArrayLike(Buffer, "le", 8), salt, bidder.toBuffer(), auction.toBuffer()]))
+        .digest();
+      return Array.from(digest);
+    }
+
+    it("Test 56: Multiple bidders commit sealed bids", async () => {
       console.log("\n=== TEST 56: Multiple sealed bids ===");
 
       const bidders = [accounts.user2, accounts.marketplaceAuthority, accounts.merchant1];
@@ -960,6 +1179,8 @@ describe("Auctions", () => {
         new BN(1.5 * LAMPORTS_PER_SOL),
         new BN(1.2 * LAMPORTS_PER_SOL),
       ];
+      // Deposits are padded so they don't leak the bid amount
+      const deposit = new BN(2 * LAMPORTS_PER_SOL);
 
       const [escrowPDA] = derivePDA(
         [Buffer.from("auction_escrow"), sealedAuctionPDA.toBuffer()],
@@ -967,16 +1188,9 @@ describe("Auctions", () => {
       );
 
       for (let i = 0; i < bidders.length; i++) {
-        // Get current auction state for bid_count
-        const auction = await program.account.couponAuction.fetch(sealedAuctionPDA);
-
+        // One commitment per bidder, so the PDA doesn't depend on bid_count
         const [bidPDA] = derivePDA(
-          [
-            Buffer.from("bid"),
-            sealedAuctionPDA.toBuffer(),
-            bidders[i].publicKey.toBuffer(),
-            u32ToLeBytes(auction.bidCount),
-          ],
+          [Buffer.from("bid"), sealedAuctionPDA.toBuffer(), bidders[i].publicKey.toBuffer()],
           program.programId
         );
 
@@ -985,13 +1199,16 @@ describe("Auctions", () => {
           program.programId
         );
 
+        const salt = randomBytes(32);
+        const commitment = sealedCommitment(bidAmounts[i], salt, bidders[i].publicKey, sealedAuctionPDA);
+        sealedBids.push({ bid: bidPDA, amount: bidAmounts[i], salt });
+
         await program.methods
-          .placeBid(bidAmounts[i])
+          .commitBid(deposit, commitment)
           .accounts({
             bid: bidPDA,
             auction: sealedAuctionPDA,
             escrow: escrowPDA,
-            previousBidder: accounts.user1.publicKey, // Seller as placeholder (sealed bids don't refund)
             userStats: bidderStatsPDA,
             bidder: bidders[i].publicKey,
             systemProgram: SystemProgram.programId,
@@ -1000,36 +1217,138 @@ describe("Auctions", () => {
           .rpc();
 
         const bid = await program.account.bid.fetch(bidPDA);
-        assert.equal(bid.amount.toString(), bidAmounts[i].toString());
-        assert.equal(bid.isWinning, false); // Sealed bids not marked winning until finalized
+        assert.equal(bid.amount.toNumber(), 0); // Hidden until revealed
+        assert.equal(bid.deposit.toString(), deposit.toString());
+        assert.deepEqual(bid.commitment, commitment);
+        assert.equal(bid.isRevealed, false);
+        assert.equal(bid.isWinning, false);
         
-        console.log(`  Bidder ${i + 1}: ${bidAmounts[i].toNumber() / LAMPORTS_PER_SOL} SOL (sealed)`);
+        console.log(`  Bidder ${i + 1}: committed with ${deposit.toNumber() / LAMPORTS_PER_SOL} SOL deposit`);
       }
 
       const auction = await program.account.couponAuction.fetch(sealedAuctionPDA);
       assert.equal(auction.bidCount, 3);
+      assert.equal(auction.currentBid.toNumber(), 0); // No amounts known before reveal
 
-      // Verify all bids are in escrow
+      // Verify all deposits are in escrow
       const escrowBalance = await connection.getBalance(escrowPDA);
-      const totalBids = bidAmounts.reduce((sum, amount) => sum + amount.toNumber(), 0);
-      assert.equal(escrowBalance, totalBids);
+      assert.equal(escrowBalance, deposit.toNumber() * bidders.length);
 
-      console.log("✓ All sealed bids placed successfully");
+      console.log("✓ All sealed bids committed successfully");
       console.log("  Total bids:", auction.bidCount);
       console.log("  Total escrowed:", escrowBalance / LAMPORTS_PER_SOL, "SOL");
     });
 
+    it("Rejects a second commitment from the same bidder", async () => {
+      const [bidPDA] = derivePDA(
+        [Buffer.from("bid"), sealedAuctionPDA.toBuffer(), accounts.user2.publicKey.toBuffer()],
+        program.programId
+      );
+      const [escrowPDA] = derivePDA(
+        [Buffer.from("auction_escrow"), sealedAuctionPDA.toBuffer()],
+        program.programId
+      );
+      const [bidderStatsPDA] = derivePDA(
+        [Buffer.from("user_stats"), accounts.user2.publicKey.toBuffer()],
+        program.programId
+      );
+      const commitment = sealedCommitment(
+        new BN(1.8 * LAMPORTS_PER_SOL),
+        randomBytes(32),
+        accounts.user2.publicKey,
+        sealedAuctionPDA
+      );
+
+      try {
+        await program.methods
+          .commitBid(new BN(2 * LAMPORTS_PER_SOL), commitment)
+          .accounts({
+            bid: bidPDA,
+            auction: sealedAuctionPDA,
+            escrow: escrowPDA,
+            userStats: bidderStatsPDA,
+            bidder: accounts.user2.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.user2])
+          .rpc();
+
+        assert.fail("Should have rejected a second commitment");
+      } catch (error) {
+        assert.include(error.toString(), "already in use");
+      }
+
+      const auction = await program.account.couponAuction.fetch(sealedAuctionPDA);
+      assert.equal(auction.bidCount, 3);
+      console.log("✓ Each bidder gets a single sealed commitment");
+    });
+
+    it("Rejects reveals while bidding is still open", async () => {
+      const { bid, amount, salt } = sealedBids[0];
+
+      try {
+        await program.methods
+          .revealBid(amount, Array.from(salt))
+          .accounts({
+            bid,
+            auction: sealedAuctionPDA,
+            bidder: accounts.user2.publicKey,
+          })
+          .signers([accounts.user2])
+          .rpc();
+
+        assert.fail("Should have rejected reveal before bidding closes");
+      } catch (error) {
+        assert.include(error.toString(), "RevealWindowClosed");
+        console.log("✓ Correctly rejected early reveal");
+      }
+    });
+
+    it("Rejects placeBid on a sealed bid auction", async () => {
+      const auction = await program.account.couponAuction.fetch(sealedAuctionPDA);
+      const [bidPDA] = derivePDA(
+        [
+          Buffer.from("bid"),
+          sealedAuctionPDA.toBuffer(),
+          accounts.user2.publicKey.toBuffer(),
+          u32ToLeBytes(auction.bidCount),
+        ],
+        program.programId
+      );
+      const [escrowPDA] = derivePDA(
+        [Buffer.from("auction_escrow"), sealedAuctionPDA.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .placeBid(new BN(2 * LAMPORTS_PER_SOL))
+          .accounts({
+            bid: bidPDA,
+            auction: sealedAuctionPDA,
+            escrow: escrowPDA,
+            bidder: accounts.user2.publicKey,
+          })
+          .signers([accounts.user2])
+          .rpc();
+
+        assert.fail("Should have rejected a plaintext bid");
+      } catch (error) {
+        assert.include(error.toString(), "InvalidInput");
+        console.log("✓ Sealed auctions only accept committed bids");
+      }
+    });
+
     it("Test 57: Finalizes sealed bid auction", async () => {
       console.log("\n=== TEST 57: Finalize sealed bid ===");
 
-      console.log("  Note: Skipped - requires expired auction");
-      console.log("  Contract would need to:");
-      console.log("  1. Iterate through all bids to find highest");
-      console.log("  2. Award coupon to highest bidder");
-      console.log("  3. Refund all other bidders");
-      console.log("  4. Transfer funds to seller minus marketplace fee");
-      console.log("  Enhancement: Requires additional finalization logic");
-      console.log("✓ Sealed bid structure supports future implementation");
+      console.log("  Note: Skipped - requires the bidding and reveal windows to elapse");
+      console.log("  Flow once they have:");
+      console.log("  1. Bidders call revealBid(amount, salt) during the reveal window");
+      console.log("  2. finalizeAuction awards the coupon to the highest reveal");
+      console.log("     (Vickrey: at the second-highest price, never below reserve)");
+      console.log("  3. Each bidder calls withdrawBid for the unused part of their deposit");
+      console.log("  4. Unrevealed deposits are refunded or forfeited per the auction policy");
     });
   });
 
@@ -1109,12 +1428,15 @@ describe("Auctions", () => {
           new BN(1 * LAMPORTS_PER_SOL),
           new BN(0.8 * LAMPORTS_PER_SOL),
           new BN(3600),
-          false,
-          new BN(0.1 * LAMPORTS_PER_SOL)
+          auctionTerms({
+            minBidIncrement: new BN(0.1 * LAMPORTS_PER_SOL),
+          })
         )
         .accounts({
+          ...(await auctionVaultAccounts(cancelAuctionPDA, cancelCouponPDA)),
           auction: cancelAuctionPDA,
           coupon: cancelCouponPDA,
+          promotion: promotionPDA,
           userStats: userStatsPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
@@ -1126,6 +1448,7 @@ describe("Auctions", () => {
       await program.methods
         .cancelAuction()
         .accounts({
+          ...(await cancelAuctionAccounts(cancelAuctionPDA)),
           auction: cancelAuctionPDA,
           seller: accounts.user1.publicKey,
         })
@@ -1135,10 +1458,173 @@ describe("Auctions", () => {
       // Verify auction account is closed
       const auctionExists = await accountExists(connection, cancelAuctionPDA);
       assert.isFalse(auctionExists, "Auction account should be closed");
+
+      // Verify the NFT went back to the seller
+      const cancelledCoupon = await program.account.coupon.fetch(cancelCouponPDA);
+      assert.isFalse(cancelledCoupon.isEscrowed);
+      const sellerNft = await connection.getTokenAccountBalance(
+        getAssociatedTokenAddressSync(cancelCouponMint.publicKey, accounts.user1.publicKey)
+      );
+      assert.equal(sellerNft.value.amount, "1");
       
       console.log("✓ Auction cancelled successfully");
     });
 
+    it("Buys an English auction at the buy-now price", async () => {
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      const [buyNowCouponPDA] = derivePDA(
+        [
+          Buffer.from("coupon"),
+          promotionPDA.toBuffer(),
+          u32ToLeBytes(promotion.currentSupply),
+        ],
+        program.programId
+      );
+      const [sellerStatsPDA] = derivePDA(
+        [Buffer.from("user_stats"), accounts.user1.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const buyNowCouponMint = Keypair.generate();
+      const [metadataPDA] = deriveMetadataPDA(buyNowCouponMint.publicKey);
+      const [masterEditionPDA] = deriveMasterEditionPDA(buyNowCouponMint.publicKey);
+
+      await program.methods
+        .mintCoupon(new BN(6))
+        .accounts({
+          coupon: buyNowCouponPDA,
+          nftMint: buyNowCouponMint.publicKey,
+          tokenAccount: getAssociatedTokenAddressSync(buyNowCouponMint.publicKey, accounts.user1.publicKey),
+          metadata: metadataPDA,
+          masterEdition: masterEditionPDA,
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          marketplace: accounts.marketplacePDA,
+          recipient: accounts.user1.publicKey,
+          userStats: sellerStatsPDA,
+          payer: accounts.user1.publicKey,
+          authority: accounts.merchant1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          systemProgram: SystemProgram.programId,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+        })
+        .preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+        .signers([accounts.user1, buyNowCouponMint, accounts.merchant1])
+        .rpc();
+
+      const auctionId = new BN(500);
+      const [buyNowAuctionPDA] = derivePDA(
+        [
+          Buffer.from("auction"),
+          buyNowCouponPDA.toBuffer(),
+          u64ToLeBytes(auctionId),
+        ],
+        program.programId
+      );
+      const [escrowPDA] = derivePDA(
+        [Buffer.from("auction_escrow"), buyNowAuctionPDA.toBuffer()],
+        program.programId
+      );
+      const buyNowPrice = new BN(2 * LAMPORTS_PER_SOL);
+
+      await program.methods
+        .createAuction(
+          auctionId,
+          { english: {} },
+          new BN(1 * LAMPORTS_PER_SOL),
+          new BN(0.8 * LAMPORTS_PER_SOL),
+          new BN(3600),
+          auctionTerms({
+            minBidIncrement: new BN(0.1 * LAMPORTS_PER_SOL),
+            buyNowPrice: buyNowPrice,
+          })
+        )
+        .accounts({
+          ...(await auctionVaultAccounts(buyNowAuctionPDA, buyNowCouponPDA)),
+          auction: buyNowAuctionPDA,
+          coupon: buyNowCouponPDA,
+          promotion: promotionPDA,
+          userStats: sellerStatsPDA,
+          seller: accounts.user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.user1])
+        .rpc();
+
+      // user2 takes the lead with a regular bid
+      const [bidPDA] = derivePDA(
+        [
+          Buffer.from("bid"),
+          buyNowAuctionPDA.toBuffer(),
+          accounts.user2.publicKey.toBuffer(),
+          u32ToLeBytes(0),
+        ],
+        program.programId
+      );
+      const bidAmount = new BN(1.1 * LAMPORTS_PER_SOL);
+      await program.methods
+        .placeBid(bidAmount)
+        .accounts({
+          bid: bidPDA,
+          auction: buyNowAuctionPDA,
+          escrow: escrowPDA,
+          bidder: accounts.user2.publicKey,
+        })
+        .signers([accounts.user2])
+        .rpc();
+
+      const buyer = accounts.marketplaceAuthority;
+      const bidRent = await connection.getBalance(bidPDA);
+      const user2Before = await connection.getBalance(accounts.user2.publicKey);
+      const sellerBefore = await connection.getBalance(accounts.user1.publicKey);
+      const { nftMint, auctionVault } = await auctionVaultAccounts(buyNowAuctionPDA, buyNowCouponPDA);
+
+      await program.methods
+        .buyNow()
+        .accounts({
+          ...(await royaltyAccounts(buyNowCouponPDA)),
+          auction: buyNowAuctionPDA,
+          coupon: buyNowCouponPDA,
+          escrow: escrowPDA,
+          seller: accounts.user1.publicKey,
+          winningBid: bidPDA,
+          previousBidder: accounts.user2.publicKey,
+          buyer: buyer.publicKey,
+          nftMint,
+          auctionVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+      // High bidder refunded and their bid closed
+      const user2After = await connection.getBalance(accounts.user2.publicKey);
+      assert.equal(user2After - user2Before, bidAmount.toNumber() + bidRent);
+      assert.isFalse(await accountExists(connection, bidPDA));
+
+      // Seller paid through the usual fee split (plus the vault rent)
+      const marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      const fee = buyNowPrice.toNumber() * marketplace.feeBasisPoints / 10_000;
+      const sellerAfter = await connection.getBalance(accounts.user1.publicKey);
+      assert.isAtLeast(sellerAfter - sellerBefore, buyNowPrice.toNumber() - fee);
+
+      // Buyer holds the NFT and the auction is closed out
+      const buyerNft = await connection.getTokenAccountBalance(
+        getAssociatedTokenAddressSync(nftMint, buyer.publicKey)
+      );
+      assert.equal(buyerNft.value.amount, "1");
+      const coupon = await program.account.coupon.fetch(buyNowCouponPDA);
+      assert.equal(coupon.owner.toString(), buyer.publicKey.toString());
+      const auction = await program.account.couponAuction.fetch(buyNowAuctionPDA);
+      assert.isTrue(auction.isFinalized);
+      assert.equal(auction.currentBid.toString(), buyNowPrice.toString());
+
+      console.log("✓ Buy-now settled and refunded the high bidder");
+    });
+
     it("Test 59: Fails to cancel auction after bids", async () => {
       console.log("\n=== TEST 59: Cancel with bids ===");
 
@@ -1157,6 +1643,7 @@ describe("Auctions", () => {
         await program.methods
           .cancelAuction()
           .accounts({
+            ...(await cancelAuctionAccounts(auctionPDA)),
             auction: auctionPDA,
             seller: accounts.user1.publicKey,
           })
@@ -1220,12 +1707,15 @@ describe("Auctions", () => {
             new BN(1 * LAMPORTS_PER_SOL),
             new BN(0.8 * LAMPORTS_PER_SOL),
             new BN(3600),
-            false,
-            new BN(0.1 * LAMPORTS_PER_SOL)
+            auctionTerms({
+              minBidIncrement: new BN(0.1 * LAMPORTS_PER_SOL),
+            })
           )
           .accounts({
+            ...(await auctionVaultAccounts(notOwnedAuctionPDA, couponPDA)),
             auction: notOwnedAuctionPDA,
             coupon: couponPDA,
+            promotion: promotionPDA,
             userStats: userStatsPDA,
             seller: accounts.user2.publicKey,
             systemProgram: SystemProgram.programId,
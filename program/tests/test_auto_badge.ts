@@ -18,6 +18,7 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_METADATA_PROGRAM_ID
 } from "./setup-devnet";
+import { createPromotion } from "./setup";
 import { getAssociatedTokenAddressSync } from "@solana/spl-token";
 
 describe("Auto Badge Awarding System", () => {
@@ -42,6 +43,7 @@ describe("Auto Badge Awarding System", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -78,15 +80,7 @@ describe("Auto Badge Awarding System", () => {
 
     const promotionExists = await accountExists(connection, promotionPDA);
     if (!promotionExists) {
-      await program.methods
-        .createPromotion(
-          50,
-          100,
-          getExpiryTimestamp(30),
-          "test",
-          "Badge test promotion",
-          new BN(5 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, { category: "test", description: "Badge test promotion" })
         .accounts({
           promotion: promotionPDA,
           merchant: merchantPDA,
@@ -209,14 +203,13 @@ describe("Auto Badge Awarding System", () => {
           metadata: badgeMetadata,
           masterEdition: badgeMasterEdition,
           payer: accounts.user1.publicKey,
-          authority: accounts.marketplaceAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
           sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
           systemProgram: SystemProgram.programId,
           rent: web3.SYSVAR_RENT_PUBKEY,
         })
-        .signers([accounts.user1, badgeMint, accounts.marketplaceAuthority])
+        .signers([accounts.user1, badgeMint])
         .rpc();
 
       const badge = await program.account.badgeNft.fetch(badgePDA);
@@ -253,14 +246,13 @@ describe("Auto Badge Awarding System", () => {
             metadata: badgeMetadata,
             masterEdition: badgeMasterEdition,
             payer: accounts.user1.publicKey,
-            authority: accounts.marketplaceAuthority.publicKey,
             tokenProgram: TOKEN_PROGRAM_ID,
             tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
             sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
             systemProgram: SystemProgram.programId,
             rent: web3.SYSVAR_RENT_PUBKEY,
           })
-          .signers([accounts.user1, badgeMint, accounts.marketplaceAuthority])
+          .signers([accounts.user1, badgeMint])
           .rpc();
         
         assert.fail("Should have thrown an error for duplicate badge");
@@ -301,14 +293,13 @@ describe("Auto Badge Awarding System", () => {
               metadata: badgeMetadata,
               masterEdition: badgeMasterEdition,
               payer: accounts.user1.publicKey,
-              authority: accounts.marketplaceAuthority.publicKey,
               tokenProgram: TOKEN_PROGRAM_ID,
               tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
               sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
               systemProgram: SystemProgram.programId,
               rent: web3.SYSVAR_RENT_PUBKEY,
             })
-            .signers([accounts.user1, badgeMint, accounts.marketplaceAuthority])
+            .signers([accounts.user1, badgeMint])
             .rpc();
           
           assert.fail("Should have thrown an error for unqualified badge");
@@ -322,6 +313,52 @@ describe("Auto Badge Awarding System", () => {
         console.log("⚠ User already qualifies for TenRedemptions badge, skipping test");
       }
     });
+
+    it("Fails to award SavedOneSol before the user has saved 1 SOL", async () => {
+      const [badgePDA] = derivePDA(
+        [
+          Buffer.from("badge"),
+          accounts.user1.publicKey.toBuffer(),
+          Buffer.from([7]), // SavedOneSol = 7
+        ],
+        program.programId
+      );
+
+      const badgeMint = Keypair.generate();
+      const [badgeMetadata] = deriveMetadataPDA(badgeMint.publicKey);
+      const [badgeMasterEdition] = deriveMasterEditionPDA(badgeMint.publicKey);
+
+      const userStats = await program.account.userStats.fetch(userStatsPDA);
+
+      if (userStats.totalSavings.lt(new BN(LAMPORTS_PER_SOL))) {
+        try {
+          await program.methods
+            .autoAwardBadge({ savedOneSol: {} })
+            .accounts({
+              badgeNft: badgePDA,
+              userStats: userStatsPDA,
+              user: accounts.user1.publicKey,
+              mint: badgeMint.publicKey,
+              metadata: badgeMetadata,
+              masterEdition: badgeMasterEdition,
+              payer: accounts.user1.publicKey,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+              sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+              systemProgram: SystemProgram.programId,
+              rent: web3.SYSVAR_RENT_PUBKEY,
+            })
+            .signers([accounts.user1, badgeMint])
+            .rpc();
+
+          assert.fail("Should have thrown an error for unqualified badge");
+        } catch (error: any) {
+          expect(error.toString()).to.include("InvalidInput");
+        }
+      } else {
+        console.log("⚠ User already saved 1 SOL, skipping test");
+      }
+    });
   });
 
   describe("Reputation Points Award", () => {
@@ -329,7 +366,7 @@ describe("Auto Badge Awarding System", () => {
       const userStatsBefore = await program.account.userStats.fetch(userStatsPDA);
       const reputationBefore = userStatsBefore.reputationScore.toNumber();
 
-      // Award EarlyAdopter badge (manually awarded, always qualifies)
+      // EarlyAdopter is a manual badge, issued by the marketplace authority
       const [badgePDA] = derivePDA(
         [
           Buffer.from("badge"),
@@ -344,15 +381,15 @@ describe("Auto Badge Awarding System", () => {
       const [badgeMasterEdition] = deriveMasterEditionPDA(badgeMint.publicKey);
 
       await program.methods
-        .autoAwardBadge({ earlyAdopter: {} })
+        .mintBadge({ earlyAdopter: {} })
         .accounts({
           badgeNft: badgePDA,
-          userStats: userStatsPDA,
-          user: accounts.user1.publicKey,
           mint: badgeMint.publicKey,
           metadata: badgeMetadata,
           masterEdition: badgeMasterEdition,
-          payer: accounts.user1.publicKey,
+          userStats: userStatsPDA,
+          marketplace: accounts.marketplacePDA,
+          user: accounts.user1.publicKey,
           authority: accounts.marketplaceAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
@@ -381,6 +418,7 @@ describe("Auto Badge Awarding System", () => {
         EarlyAdopter: 500,
         MerchantPartner: 300,
         CommunityModerator: 1000,
+        SavedOneSol: 100,
       };
 
       console.log("✓ Badge reputation values:");
@@ -520,14 +558,13 @@ describe("Auto Badge Awarding System", () => {
           metadata: user2BadgeMetadata,
           masterEdition: user2BadgeMasterEdition,
           payer: accounts.user2.publicKey,
-          authority: accounts.marketplaceAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID,
           tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
           sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
           systemProgram: SystemProgram.programId,
           rent: web3.SYSVAR_RENT_PUBKEY,
         })
-        .signers([accounts.user2, user2BadgeMint, accounts.marketplaceAuthority])
+        .signers([accounts.user2, user2BadgeMint])
         .rpc();
 
       const user1Stats = await program.account.userStats.fetch(userStatsPDA);
@@ -13,6 +13,7 @@ import {
   TOKEN_PROGRAM_ID,
   TOKEN_METADATA_PROGRAM_ID
 } from "./setup-devnet";
+import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
 
 describe("Badge System", () => {
   const provider = anchor.AnchorProvider.env();
@@ -44,6 +45,7 @@ describe("Badge System", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -191,6 +193,54 @@ describe("Badge System", () => {
       }
     });
 
+    it("Fails to mint a badge without the marketplace authority", async () => {
+      const badgeType = { communityModerator: {} };
+      const [selfBadgePDA] = derivePDA(
+        [
+          Buffer.from("badge"),
+          accounts.user1.publicKey.toBuffer(),
+          Buffer.from([6]), // CommunityModerator = 6
+        ],
+        program.programId
+      );
+      const selfMint = Keypair.generate();
+      const [selfMetadata] = deriveMetadataPDA(selfMint.publicKey);
+      const [selfMasterEdition] = derivePDA(
+        [
+          Buffer.from("metadata"),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          selfMint.publicKey.toBuffer(),
+          Buffer.from("edition"),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+
+      try {
+        await program.methods
+          .mintBadge(badgeType)
+          .accounts({
+            badgeNft: selfBadgePDA,
+            mint: selfMint.publicKey,
+            metadata: selfMetadata,
+            masterEdition: selfMasterEdition,
+            marketplace: accounts.marketplacePDA,
+            user: accounts.user1.publicKey,
+            authority: accounts.user1.publicKey, // User approving their own badge
+            tokenProgram: TOKEN_PROGRAM_ID,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          })
+          .signers([accounts.user1, selfMint])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("NotMarketplaceAuthority");
+      }
+    });
+
     it("Verifies badge metadata is set correctly", async () => {
       const badge = await program.account.badgeNft.fetch(badgePDA);
       
@@ -335,6 +385,14 @@ describe("Badge System", () => {
       // Verify master edition account exists
       const masterEditionAccount = await connection.getAccountInfo(badgeMasterEditionPDA);
       assert.isNotNull(masterEditionAccount, "Master edition account should exist");
+
+      // Badges are soulbound: the holder's token account is frozen
+      const badgeTokenAccount = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(badge.mint, badge.user)
+      );
+      assert.equal(badgeTokenAccount.amount.toString(), "1");
+      assert.isTrue(badgeTokenAccount.isFrozen);
     });
 
     it("Multiple users can have complete badge collections", async () => {
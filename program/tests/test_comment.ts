@@ -13,6 +13,7 @@ import {
   LAMPORTS_PER_SOL,
   airdrop
 } from "./setup-devnet";
+import { createPromotion } from "./setup";
 
 describe("Comment System", () => {
   const provider = anchor.AnchorProvider.env();
@@ -37,6 +38,7 @@ describe("Comment System", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -75,15 +77,7 @@ describe("Comment System", () => {
     // Create promotion only if it doesn't exist
     const promotionExists = await accountExists(connection, promotionPDA);
     if (!promotionExists) {
-      await program.methods
-        .createPromotion(
-          50,
-          100,
-          getExpiryTimestamp(30),
-          "food",
-          "Test promotion for comments",
-          new BN(5 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, { description: "Test promotion for comments" })
         .accounts({
           promotion: promotionPDA,
           merchant: accounts.merchant1PDA,
@@ -18,7 +18,8 @@ import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
   // createConnection, // NEW
   // getRpcUrl, 
-  TOKEN_METADATA_PROGRAM_ID
+  TOKEN_METADATA_PROGRAM_ID,
+  createPromotion
 } from "./setup";
 import { getAssociatedTokenAddressSync } from "@solana/spl-token";
 
@@ -66,6 +67,7 @@ describe("Coupon Operations", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -110,15 +112,7 @@ describe("Coupon Operations", () => {
     
     if (!promotionExists) {
       console.log("Creating promotion...");
-      await program.methods
-        .createPromotion(
-          50,
-          100,
-          getExpiryTimestamp(30),
-          "food",
-          "Test promotion",
-          new BN(5 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program)
         .accounts({
           promotion: promotionPDA,
           merchant: accounts.merchant1PDA,
@@ -210,6 +204,8 @@ describe("Coupon Operations", () => {
       assert.equal(coupon.discountPercentage, 50);
       assert.equal(coupon.isRedeemed, false);
       assert.ok(coupon.mint);
+      assert.equal(coupon.serialNumber, promotion.currentSupply + 1);
+      assert.equal(coupon.metadataUri, `https://example.com/coupons/${coupon.serialNumber}.json`);
 
       const promotionAfter = await program.account.promotion.fetch(promotionPDA);
       assert.equal(promotionAfter.currentSupply, promotion.currentSupply + 1);
@@ -301,8 +297,12 @@ describe("Coupon Operations", () => {
 
       const promotionExists = await accountExists(connection, limitedPromotionPDA);
       if (!promotionExists) {
-        await program.methods
-          .createPromotion(50, 1, getExpiryTimestamp(30), "test", "Limited supply test", new BN(1 * LAMPORTS_PER_SOL))
+        await createPromotion(program, {
+          maxSupply: 1,
+          category: "test",
+          description: "Limited supply test",
+          price: new BN(1 * LAMPORTS_PER_SOL),
+        })
           .accounts({
             promotion: limitedPromotionPDA,
             merchant: accounts.merchant1PDA,
@@ -421,8 +421,13 @@ describe("Coupon Operations", () => {
 
       const promotionExists = await accountExists(connection, expiredPromotionPDA);
       if (!promotionExists) {
-        await program.methods
-          .createPromotion(50, 10, shortExpiry, "test", "Expiry test", new BN(1 * LAMPORTS_PER_SOL))
+        await createPromotion(program, {
+          maxSupply: 10,
+          expiryTimestamp: shortExpiry,
+          category: "test",
+          description: "Expiry test",
+          price: new BN(1 * LAMPORTS_PER_SOL),
+        })
           .accounts({
             promotion: expiredPromotionPDA,
             merchant: accounts.merchant1PDA,
@@ -484,23 +489,462 @@ describe("Coupon Operations", () => {
     });
   });
 
+  describe("Coupon Purchase", () => {
+    it("Buyer pays the promotion price and receives a program-minted coupon", async () => {
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      const marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
+
+      const [purchasedCouponPDA] = derivePDA(
+        [Buffer.from("coupon"), promotionPDA.toBuffer(), u32ToLeBytes(promotion.currentSupply)],
+        program.programId
+      );
+      const [couponAuthorityPDA] = derivePDA(
+        [Buffer.from("coupon_authority")],
+        program.programId
+      );
+      const [buyerStatsPDA] = derivePDA(
+        [Buffer.from("user_stats"), accounts.user2.publicKey.toBuffer()],
+        program.programId
+      );
+      const purchaseMint = Keypair.generate();
+      const [purchaseMetadata] = deriveMetadataPDA(purchaseMint.publicKey);
+      const [purchaseMasterEdition] = deriveMasterEditionPDA(purchaseMint.publicKey);
+      const buyerTokenAccount = getAssociatedTokenAddressSync(
+        purchaseMint.publicKey,
+        accounts.user2.publicKey
+      );
+
+      const merchantBalanceBefore = await connection.getBalance(accounts.merchant1.publicKey);
+      const treasuryBalanceBefore = await connection.getBalance(accounts.treasuryPDA);
+
+      const purchaseAccounts = {
+        coupon: purchasedCouponPDA,
+        nftMint: purchaseMint.publicKey,
+        tokenAccount: buyerTokenAccount,
+        metadata: purchaseMetadata,
+        masterEdition: purchaseMasterEdition,
+        promotion: promotionPDA,
+        merchant: accounts.merchant1PDA,
+        marketplace: accounts.marketplacePDA,
+        couponAuthority: couponAuthorityPDA,
+        userStats: buyerStatsPDA,
+        merchantAuthority: accounts.merchant1.publicKey,
+        revenueConfig: null,
+        treasury: accounts.treasuryPDA,
+        buyer: accounts.user2.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+        rent: web3.SYSVAR_RENT_PUBKEY,
+      };
+
+      // The buyer's maximum guards against a reprice landing first
+      if (promotion.price.gtn(0)) {
+        try {
+          await program.methods
+            .purchaseCoupon(promotion.price.subn(1))
+            .accounts(purchaseAccounts)
+            .signers([accounts.user2, purchaseMint])
+            .rpc();
+          assert.fail("Should have thrown an error");
+        } catch (error: any) {
+          expect(error.message).to.include("PriceAboveMax");
+        }
+      }
+
+      await program.methods
+        .purchaseCoupon(promotion.price)
+        .accounts(purchaseAccounts)
+        .signers([accounts.user2, purchaseMint])
+        .rpc();
+
+      const coupon = await program.account.coupon.fetch(purchasedCouponPDA);
+      assert.equal(coupon.owner.toString(), accounts.user2.publicKey.toString());
+      assert.equal(coupon.mint.toString(), purchaseMint.publicKey.toString());
+
+      const price = promotion.price.toNumber();
+      const fee = Math.floor((price * marketplace.feeBasisPoints) / 10000);
+      const merchantBalanceAfter = await connection.getBalance(accounts.merchant1.publicKey);
+      const treasuryBalanceAfter = await connection.getBalance(accounts.treasuryPDA);
+      assert.equal(merchantBalanceAfter - merchantBalanceBefore, price - fee);
+      assert.equal(treasuryBalanceAfter - treasuryBalanceBefore, fee);
+
+      const promotionAfter = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotionAfter.currentSupply, promotion.currentSupply + 1);
+    });
+  });
+
+  describe("Core Coupons", () => {
+    const MPL_CORE_PROGRAM_ID = new PublicKey("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+    let corePromotionPDA: PublicKey;
+    let coreCouponPDA: PublicKey;
+    let coreAsset: Keypair;
+
+    before(async () => {
+      const merchant = await program.account.merchant.fetch(accounts.merchant1PDA);
+      [corePromotionPDA] = derivePDA(
+        [
+          Buffer.from("promotion"),
+          accounts.merchant1PDA.toBuffer(),
+          u64ToLeBytes(merchant.totalCouponsCreated),
+        ],
+        program.programId
+      );
+
+      await createPromotion(program, {
+        discountPercentage: 25,
+        maxSupply: 100000,
+        category: "events",
+        description: "Mass issuance promotion",
+        price: new BN(0),
+        metadataBaseUri: "https://example.com/core-coupons",
+        nftName: "Core Coupon",
+        nftSymbol: "CC",
+        issuanceMode: { core: {} },
+      })
+        .accounts({
+          promotion: corePromotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+    });
+
+    it("Rejects transfer restrictions and royalties on a Core promotion", async () => {
+      const promotionAccounts = {
+        promotion: corePromotionPDA,
+        merchant: accounts.merchant1PDA,
+        authority: accounts.merchant1.publicKey,
+      };
+
+      try {
+        await program.methods
+          .updatePromotion(null, null, null, null, false, false)
+          .accounts(promotionAccounts)
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("CoreTermsUnsupported");
+      }
+
+      try {
+        await program.methods
+          .setResaleTerms(500, null)
+          .accounts(promotionAccounts)
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("CoreTermsUnsupported");
+      }
+    });
+
+    it("Rejects a geofence on a Core promotion", async () => {
+      const merchant = await program.account.merchant.fetch(accounts.merchant1PDA);
+      const [fencedPromotionPDA] = derivePDA(
+        [
+          Buffer.from("promotion"),
+          accounts.merchant1PDA.toBuffer(),
+          u64ToLeBytes(merchant.totalCouponsCreated),
+        ],
+        program.programId
+      );
+
+      try {
+        await createPromotion(program, {
+          price: new BN(0),
+          issuanceMode: { core: {} },
+          geofence: { latitude: 6.5244, longitude: 3.3792, radiusMeters: 500 },
+        })
+          .accounts({
+            promotion: fencedPromotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("CoreTermsUnsupported");
+      }
+    });
+
+    it("Mints a coupon as a single Core asset", async () => {
+      const promotion = await program.account.promotion.fetch(corePromotionPDA);
+      [coreCouponPDA] = derivePDA(
+        [Buffer.from("coupon"), corePromotionPDA.toBuffer(), u32ToLeBytes(promotion.currentSupply)],
+        program.programId
+      );
+      coreAsset = Keypair.generate();
+
+      await program.methods
+        .mintCoreCoupon(new BN(1))
+        .accounts({
+          coupon: coreCouponPDA,
+          asset: coreAsset.publicKey,
+          promotion: corePromotionPDA,
+          merchant: accounts.merchant1PDA,
+          recipient: accounts.user2.publicKey,
+          payer: accounts.merchant1.publicKey,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1, coreAsset])
+        .rpc();
+
+      const coupon = await program.account.coupon.fetch(coreCouponPDA);
+      assert.equal(coupon.owner.toString(), accounts.user2.publicKey.toString());
+      assert.equal(coupon.mint.toString(), coreAsset.publicKey.toString());
+      assert.equal(coupon.discountPercentage, 25);
+      assert.equal(coupon.metadataUri, "https://example.com/core-coupons/1.json");
+
+      const asset = await connection.getAccountInfo(coreAsset.publicKey);
+      assert.equal(asset.owner.toString(), MPL_CORE_PROGRAM_ID.toString());
+      assert.equal(new PublicKey(asset.data.subarray(1, 33)).toString(), accounts.user2.publicKey.toString());
+    });
+
+    it("Rejects Token Metadata minting on a Core promotion", async () => {
+      const promotion = await program.account.promotion.fetch(corePromotionPDA);
+      const [wrongCouponPDA] = derivePDA(
+        [Buffer.from("coupon"), corePromotionPDA.toBuffer(), u32ToLeBytes(promotion.currentSupply)],
+        program.programId
+      );
+      const wrongMint = Keypair.generate();
+
+      try {
+        await program.methods
+          .mintCoupon(new BN(2))
+          .accounts({
+            coupon: wrongCouponPDA,
+            nftMint: wrongMint.publicKey,
+            tokenAccount: getAssociatedTokenAddressSync(wrongMint.publicKey, accounts.user2.publicKey),
+            metadata: deriveMetadataPDA(wrongMint.publicKey)[0],
+            masterEdition: deriveMasterEditionPDA(wrongMint.publicKey)[0],
+            promotion: corePromotionPDA,
+            merchant: accounts.merchant1PDA,
+            marketplace: accounts.marketplacePDA,
+            recipient: accounts.user2.publicKey,
+            payer: accounts.merchant1.publicKey,
+            authority: accounts.merchant1.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+            systemProgram: SystemProgram.programId,
+            rent: web3.SYSVAR_RENT_PUBKEY,
+          })
+          .signers([accounts.merchant1, wrongMint])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("WrongIssuanceMode");
+      }
+    });
+
+    it("Rejects redemption tickets for a Core coupon", async () => {
+      const nonce = new BN(1);
+      const [ticketPDA] = derivePDA(
+        [
+          Buffer.from("ticket"),
+          coreCouponPDA.toBuffer(),
+          accounts.user2.publicKey.toBuffer(),
+          nonce.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .generateRedemptionTicket(nonce, null, null)
+          .accounts({
+            ticket: ticketPDA,
+            coupon: coreCouponPDA,
+            promotion: corePromotionPDA,
+            user: accounts.user2.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.user2])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("WrongIssuanceMode");
+      }
+    });
+
+    it("Redeems the Core coupon and burns the asset", async () => {
+      await program.methods
+        .redeemCoreCoupon(new BN(1), new BN(0))
+        .accounts({
+          coupon: coreCouponPDA,
+          asset: coreAsset.publicKey,
+          promotion: corePromotionPDA,
+          merchant: accounts.merchant1PDA,
+          user: accounts.user2.publicKey,
+          merchantAuthority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.user2, accounts.merchant1])
+        .rpc();
+
+      const coupon = await program.account.coupon.fetch(coreCouponPDA);
+      assert.equal(coupon.isRedeemed, true);
+      assert.isAbove(coupon.redeemedAt.toNumber(), 0);
+
+      const asset = await connection.getAccountInfo(coreAsset.publicKey);
+      assert.isTrue(asset === null || asset.data.length <= 1);
+    });
+  });
+
   describe("Coupon Transfer", () => {
     it("Transfers coupon to another user", async () => {
       const couponBefore = await program.account.coupon.fetch(couponPDA);
       assert.equal(couponBefore.owner.toString(), accounts.user1.publicKey.toString());
 
+      const user2TokenAccount = getAssociatedTokenAddressSync(
+        couponMint.publicKey,
+        accounts.user2.publicKey
+      );
+
       await program.methods
         .transferCoupon()
         .accounts({
           coupon: couponPDA,
+          promotion: promotionPDA,
           newOwner: accounts.user2.publicKey,
+          nftMint: couponMint.publicKey,
+          fromTokenAccount: tokenAccount,
+          newOwnerTokenAccount: user2TokenAccount,
           fromAuthority: accounts.user1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         })
         .signers([accounts.user1])
         .rpc();
 
       const couponAfter = await program.account.coupon.fetch(couponPDA);
       assert.equal(couponAfter.owner.toString(), accounts.user2.publicKey.toString());
+
+      const user2Nft = await connection.getTokenAccountBalance(user2TokenAccount);
+      assert.equal(user2Nft.value.amount, "1");
+    });
+
+    it("Locks the transfer policy once coupons are minted", async () => {
+      try {
+        await program.methods
+          .updatePromotion(null, null, null, null, false, false)
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("TransferPolicyLocked");
+      }
+
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.transferable, true);
+      assert.equal(promotion.resaleAllowed, true);
+    });
+
+    it("Blocks transfers when the promotion disallows them", async () => {
+      const merchant = await program.account.merchant.fetch(accounts.merchant1PDA);
+      const [lockedPromotionPDA] = derivePDA(
+        [
+          Buffer.from("promotion"),
+          accounts.merchant1PDA.toBuffer(),
+          u64ToLeBytes(merchant.totalCouponsCreated),
+        ],
+        program.programId
+      );
+
+      await createPromotion(program, {
+        description: "Non-transferable promotion",
+        transferable: false,
+        resaleAllowed: false,
+      })
+        .accounts({
+          promotion: lockedPromotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      const lockedPromotion = await program.account.promotion.fetch(lockedPromotionPDA);
+      assert.equal(lockedPromotion.transferable, false);
+      assert.equal(lockedPromotion.resaleAllowed, false);
+
+      const [lockedCouponPDA] = derivePDA(
+        [Buffer.from("coupon"), lockedPromotionPDA.toBuffer(), u32ToLeBytes(0)],
+        program.programId
+      );
+      const lockedMint = Keypair.generate();
+      const lockedTokenAccount = getAssociatedTokenAddressSync(
+        lockedMint.publicKey,
+        accounts.user1.publicKey
+      );
+
+      await program.methods
+        .mintCoupon(new BN(1))
+        .accounts({
+          coupon: lockedCouponPDA,
+          nftMint: lockedMint.publicKey,
+          tokenAccount: lockedTokenAccount,
+          metadata: deriveMetadataPDA(lockedMint.publicKey)[0],
+          masterEdition: deriveMasterEditionPDA(lockedMint.publicKey)[0],
+          promotion: lockedPromotionPDA,
+          merchant: accounts.merchant1PDA,
+          marketplace: accounts.marketplacePDA,
+          recipient: accounts.user1.publicKey,
+          userStats: userStatsPDA,
+          payer: accounts.user1.publicKey,
+          authority: accounts.merchant1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          systemProgram: SystemProgram.programId,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([accounts.user1, lockedMint, accounts.merchant1])
+        .rpc();
+
+      try {
+        await program.methods
+          .transferCoupon()
+          .accounts({
+            coupon: lockedCouponPDA,
+            promotion: lockedPromotionPDA,
+            newOwner: accounts.user2.publicKey,
+            nftMint: lockedMint.publicKey,
+            fromTokenAccount: lockedTokenAccount,
+            newOwnerTokenAccount: getAssociatedTokenAddressSync(
+              lockedMint.publicKey,
+              accounts.user2.publicKey
+            ),
+            fromAuthority: accounts.user1.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.user1])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("CouponNotTransferable");
+      }
     });
 
     it("Fails when non-owner tries to transfer", async () => {
@@ -509,8 +953,18 @@ describe("Coupon Operations", () => {
           .transferCoupon()
           .accounts({
             coupon: couponPDA,
+            promotion: promotionPDA,
             newOwner: accounts.merchant1.publicKey,
+            nftMint: couponMint.publicKey,
+            fromTokenAccount: tokenAccount,
+            newOwnerTokenAccount: getAssociatedTokenAddressSync(
+              couponMint.publicKey,
+              accounts.merchant1.publicKey
+            ),
             fromAuthority: accounts.user1.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
           })
           .signers([accounts.user1])
           .rpc();
@@ -528,8 +982,18 @@ describe("Coupon Operations", () => {
         .transferCoupon()
         .accounts({
           coupon: couponPDA,
+          promotion: promotionPDA,
           newOwner: accounts.user1.publicKey,
+          nftMint: couponMint.publicKey,
+          fromTokenAccount: getAssociatedTokenAddressSync(
+            couponMint.publicKey,
+            accounts.user2.publicKey
+          ),
+          newOwnerTokenAccount: tokenAccount,
           fromAuthority: accounts.user2.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         })
         .signers([accounts.user2])
         .rpc();
@@ -546,12 +1010,19 @@ describe("Coupon Operations", () => {
 
       const merchantBefore = await program.account.merchant.fetch(accounts.merchant1PDA);
       const redeemedCountBefore = merchantBefore.totalCouponsRedeemed.toNumber();
+      const userStatsBefore = await program.account.userStats.fetch(userStatsPDA);
+
+      // 50% off a 4 SOL bill
+      const orderAmount = new BN(4 * LAMPORTS_PER_SOL);
+      const expectedSavings = 2 * LAMPORTS_PER_SOL;
 
       await program.methods
-        .redeemCoupon()
+        .redeemCoupon(new BN(1), orderAmount)
         .accounts({
           coupon: couponPDA,
           nftMint: couponMint.publicKey,
+          promotion: promotionPDA,
+          metadata: deriveMetadataPDA(couponMint.publicKey)[0],
           tokenAccount: tokenAccount,
           merchant: accounts.merchant1PDA,
           userStats: userStatsPDA,
@@ -566,23 +1037,37 @@ describe("Coupon Operations", () => {
       const couponAfter = await program.account.coupon.fetch(couponPDA);
       assert.equal(couponAfter.isRedeemed, true);
       assert.isAbove(couponAfter.redeemedAt.toNumber(), 0);
+      assert.equal(
+        couponAfter.metadataUri,
+        `https://example.com/coupons/${couponAfter.serialNumber}-redeemed.json`
+      );
 
       const merchantAfter = await program.account.merchant.fetch(accounts.merchant1PDA);
       assert.equal(merchantAfter.totalCouponsRedeemed.toNumber(), redeemedCountBefore + 1);
+      assert.equal(
+        merchantAfter.totalSavingsGiven.toNumber() - merchantBefore.totalSavingsGiven.toNumber(),
+        expectedSavings
+      );
 
       // Verify UserStats was updated
       const userStatsAfter = await program.account.userStats.fetch(userStatsPDA);
       assert.isAbove(userStatsAfter.totalRedemptions, 0);
+      assert.equal(
+        userStatsAfter.totalSavings.toNumber() - userStatsBefore.totalSavings.toNumber(),
+        expectedSavings
+      );
       console.log("UserStats - Redemptions:", userStatsAfter.totalRedemptions, "Reputation:", userStatsAfter.reputationScore.toString());
     });
 
     it("Fails to redeem already redeemed coupon", async () => {
       try {
         await program.methods
-          .redeemCoupon()
+          .redeemCoupon(new BN(1), new BN(0))
           .accounts({
             coupon: couponPDA,
             nftMint: couponMint.publicKey,
+            promotion: promotionPDA,
+            metadata: deriveMetadataPDA(couponMint.publicKey)[0],
             tokenAccount: tokenAccount,
             merchant: accounts.merchant1PDA,
             userStats: userStatsPDA,
@@ -602,4 +1087,114 @@ describe("Coupon Operations", () => {
       }
     });
   });
+
+  describe("Multi-use Coupons", () => {
+    let punchPromotionPDA: PublicKey;
+    let punchCouponPDA: PublicKey;
+    let punchAsset: Keypair;
+
+    function redeemUses(uses: number) {
+      return program.methods
+        .redeemCoreCoupon(new BN(uses), new BN(0))
+        .accounts({
+          coupon: punchCouponPDA,
+          asset: punchAsset.publicKey,
+          promotion: punchPromotionPDA,
+          merchant: accounts.merchant1PDA,
+          user: accounts.user2.publicKey,
+          merchantAuthority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.user2, accounts.merchant1])
+        .rpc();
+    }
+
+    before(async () => {
+      const merchant = await program.account.merchant.fetch(accounts.merchant1PDA);
+      [punchPromotionPDA] = derivePDA(
+        [
+          Buffer.from("promotion"),
+          accounts.merchant1PDA.toBuffer(),
+          u64ToLeBytes(merchant.totalCouponsCreated),
+        ],
+        program.programId
+      );
+
+      await createPromotion(program, {
+        discountPercentage: 100,
+        description: "5 coffees",
+        price: new BN(0),
+        metadataBaseUri: "https://example.com/core-coupons",
+        nftName: "Coffee Card",
+        nftSymbol: "CC",
+        issuanceMode: { core: {} },
+        redemptionModel: { multiUse: { uses: 5 } },
+      })
+        .accounts({
+          promotion: punchPromotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      [punchCouponPDA] = derivePDA(
+        [Buffer.from("coupon"), punchPromotionPDA.toBuffer(), u32ToLeBytes(0)],
+        program.programId
+      );
+      punchAsset = Keypair.generate();
+
+      await program.methods
+        .mintCoreCoupon(new BN(1))
+        .accounts({
+          coupon: punchCouponPDA,
+          asset: punchAsset.publicKey,
+          promotion: punchPromotionPDA,
+          merchant: accounts.merchant1PDA,
+          recipient: accounts.user2.publicKey,
+          payer: accounts.merchant1.publicKey,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1, punchAsset])
+        .rpc();
+    });
+
+    it("Mints a coupon with the promotion's uses", async () => {
+      const coupon = await program.account.coupon.fetch(punchCouponPDA);
+      assert.equal(coupon.usesRemaining, 5);
+      assert.equal(coupon.balanceRemaining.toNumber(), 0);
+    });
+
+    it("Keeps the asset after a partial redemption", async () => {
+      await redeemUses(2);
+
+      const coupon = await program.account.coupon.fetch(punchCouponPDA);
+      assert.equal(coupon.usesRemaining, 3);
+      assert.isFalse(coupon.isRedeemed);
+
+      const asset = await connection.getAccountInfo(punchAsset.publicKey);
+      assert.isNotNull(asset);
+      assert.isAbove(asset.data.length, 1);
+    });
+
+    it("Fails to redeem more uses than remain", async () => {
+      try {
+        await redeemUses(4);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("InvalidRedemptionAmount");
+      }
+    });
+
+    it("Burns the asset once the last use is redeemed", async () => {
+      await redeemUses(3);
+
+      const coupon = await program.account.coupon.fetch(punchCouponPDA);
+      assert.equal(coupon.usesRemaining, 0);
+      assert.isTrue(coupon.isRedeemed);
+
+      const asset = await connection.getAccountInfo(punchAsset.publicKey);
+      assert.isTrue(asset === null || asset.data.length <= 1);
+    });
+  });
 });
\ No newline at end of file
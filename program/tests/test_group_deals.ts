@@ -13,6 +13,7 @@ import {
   u64ToLeBytes,
   LAMPORTS_PER_SOL,
   getCurrentTimestamp,
+  createPromotion,
 } from "./setup";
 
 describe("Group Deals", () => {
@@ -40,6 +41,7 @@ describe("Group Deals", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -77,15 +79,11 @@ describe("Group Deals", () => {
     const promotionExists = await accountExists(connection, promotionPDA);
     if (!promotionExists) {
       console.log("Creating promotion...");
-      await program.methods
-        .createPromotion(
-          20, // 20% base discount
-          100, // max supply
-          getExpiryTimestamp(60),
-          "food",
-          "Group deal promotion",
-          new BN(5 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, {
+        discountPercentage: 20,
+        expiryTimestamp: getExpiryTimestamp(60),
+        description: "Group deal promotion",
+      })
         .accounts({
           promotion: promotionPDA,
           merchant: accounts.merchant1PDA,
@@ -1168,6 +1166,7 @@ describe("Group Deals", () => {
           marketplace: accounts.marketplacePDA,
           escrowVault: finalizeEscrowVaultPDA,
           merchantAuthority: accounts.merchant1.publicKey,
+          treasury: accounts.treasuryPDA,
           authority: accounts.merchant1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -1291,6 +1290,7 @@ describe("Group Deals", () => {
             marketplace: accounts.marketplacePDA,
             escrowVault: partialEscrowVaultPDA,
             merchantAuthority: accounts.merchant1.publicKey,
+            treasury: accounts.treasuryPDA,
             authority: accounts.merchant1.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -1388,6 +1388,7 @@ describe("Group Deals", () => {
             marketplace: accounts.marketplacePDA,
             escrowVault: earlyEscrowVaultPDA,
             merchantAuthority: accounts.merchant1.publicKey,
+            treasury: accounts.treasuryPDA,
             authority: accounts.merchant1.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -1741,6 +1742,7 @@ describe("Group Deals", () => {
           marketplace: accounts.marketplacePDA,
           escrowVault: mintEscrowVaultPDA,
           merchantAuthority: accounts.merchant1.publicKey,
+          treasury: accounts.treasuryPDA,
           authority: accounts.merchant1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -39,6 +39,7 @@ describe("Marketplace Initialization", () => {
       .initialize()
       .accounts({
         marketplace: accounts.marketplacePDA,
+        treasury: accounts.treasuryPDA,
         authority: accounts.marketplaceAuthority.publicKey,
         systemProgram: SystemProgram.programId,
       })
@@ -86,6 +87,7 @@ describe("Marketplace Initialization", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -97,4 +99,233 @@ describe("Marketplace Initialization", () => {
       expect(error.message).to.include("already in use");
     }
   });
+
+  describe("Governance", () => {
+    const newAuthority = anchor.web3.Keypair.generate();
+
+    before(async function() {
+      // Governance tests need the key that actually controls the marketplace
+      if (marketplaceWasAlreadyInitialized) {
+        this.skip();
+      }
+    });
+
+    it("Updates the marketplace fee", async () => {
+      await program.methods
+        .updateMarketplaceConfig(300, null)
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      const marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      assert.equal(marketplace.feeBasisPoints, 300);
+      assert.isFalse(marketplace.isPaused);
+    });
+
+    it("Fails to set a fee above the maximum", async () => {
+      try {
+        await program.methods
+          .updateMarketplaceConfig(5000, null)
+          .accounts({
+            marketplace: accounts.marketplacePDA,
+            authority: accounts.marketplaceAuthority.publicKey,
+          })
+          .signers([accounts.marketplaceAuthority])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidFee");
+      }
+    });
+
+    it("Fails to update config from a non-authority key", async () => {
+      try {
+        await program.methods
+          .updateMarketplaceConfig(100, null)
+          .accounts({
+            marketplace: accounts.marketplacePDA,
+            authority: accounts.user1.publicKey,
+          })
+          .signers([accounts.user1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NotMarketplaceAuthority");
+      }
+    });
+
+    it("Pauses and unpauses trading", async () => {
+      await program.methods
+        .updateMarketplaceConfig(null, true)
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      let marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      assert.isTrue(marketplace.isPaused);
+
+      // Restore fee and unpause so the other suites keep working
+      await program.methods
+        .updateMarketplaceConfig(250, false)
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      assert.isFalse(marketplace.isPaused);
+      assert.equal(marketplace.feeBasisPoints, 250);
+    });
+
+    it("Rejects accept_authority from a key that was not proposed", async () => {
+      await program.methods
+        .proposeAuthority(newAuthority.publicKey)
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      try {
+        await program.methods
+          .acceptAuthority()
+          .accounts({
+            marketplace: accounts.marketplacePDA,
+            newAuthority: accounts.user1.publicKey,
+          })
+          .signers([accounts.user1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NotPendingAuthority");
+      }
+    });
+
+    it("Hands authority over and back in two steps", async () => {
+      await program.methods
+        .acceptAuthority()
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          newAuthority: newAuthority.publicKey,
+        })
+        .signers([newAuthority])
+        .rpc();
+
+      let marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      assert.equal(marketplace.authority.toString(), newAuthority.publicKey.toString());
+      assert.isNull(marketplace.pendingAuthority);
+
+      // Hand it back so the rest of the suite keeps the original authority
+      await program.methods
+        .proposeAuthority(accounts.marketplaceAuthority.publicKey)
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          authority: newAuthority.publicKey,
+        })
+        .signers([newAuthority])
+        .rpc();
+
+      await program.methods
+        .acceptAuthority()
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          newAuthority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      assert.equal(
+        marketplace.authority.toString(),
+        accounts.marketplaceAuthority.publicKey.toString()
+      );
+    });
+
+    it("Refuses to withdraw the treasury below rent exemption", async () => {
+      const balance = await connection.getBalance(accounts.treasuryPDA);
+
+      try {
+        await program.methods
+          .withdrawTreasury(new anchor.BN(balance))
+          .accounts({
+            marketplace: accounts.marketplacePDA,
+            treasury: accounts.treasuryPDA,
+            destination: accounts.marketplaceAuthority.publicKey,
+            authority: accounts.marketplaceAuthority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.marketplaceAuthority])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InsufficientTreasuryBalance");
+      }
+    });
+
+    it("Withdraws collected fees to a chosen destination", async () => {
+      const destination = anchor.web3.Keypair.generate().publicKey;
+
+      // Seed the treasury as if fees had been collected. A fresh destination
+      // only accepts a rent-exempt amount, so withdraw exactly that.
+      const withdrawAmount = await connection.getMinimumBalanceForRentExemption(0);
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: provider.wallet.publicKey,
+            toPubkey: accounts.treasuryPDA,
+            lamports: withdrawAmount,
+          })
+        )
+      );
+
+      const before = await program.account.marketplace.fetch(accounts.marketplacePDA);
+
+      await program.methods
+        .withdrawTreasury(new anchor.BN(withdrawAmount))
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
+          destination,
+          authority: accounts.marketplaceAuthority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      assert.equal(await connection.getBalance(destination), withdrawAmount);
+      const after = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      assert.equal(
+        after.totalFeesWithdrawn.sub(before.totalFeesWithdrawn).toNumber(),
+        withdrawAmount
+      );
+    });
+
+    it("Fails to withdraw from a non-authority key", async () => {
+      try {
+        await program.methods
+          .withdrawTreasury(new anchor.BN(1))
+          .accounts({
+            marketplace: accounts.marketplacePDA,
+            treasury: accounts.treasuryPDA,
+            destination: accounts.user1.publicKey,
+            authority: accounts.user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.user1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NotMarketplaceAuthority");
+      }
+    });
+  });
 });
\ No newline at end of file
@@ -15,9 +15,16 @@ import {
   LAMPORTS_PER_SOL,
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
-  TOKEN_METADATA_PROGRAM_ID
+  TOKEN_METADATA_PROGRAM_ID,
+  createPromotion
 } from "./setup";
-import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+import {
+  getAssociatedTokenAddressSync,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
 
 describe("Marketplace Listing & Trading", () => {
   const provider = anchor.AnchorProvider.env();
@@ -87,6 +94,44 @@ describe("Marketplace Listing & Trading", () => {
     return { couponPDA, mint: newMint };
   }
 
+  // NFT escrow accounts: listings hold the coupon NFT in a [listing_vault, mint] PDA
+  async function listingAccounts(coupon: PublicKey) {
+    const couponAccount = await program.account.coupon.fetch(coupon);
+    const [listingVault] = derivePDA(
+      [Buffer.from("listing_vault"), couponAccount.mint.toBuffer()],
+      program.programId
+    );
+    return {
+      nftMint: couponAccount.mint,
+      sellerTokenAccount: getAssociatedTokenAddressSync(couponAccount.mint, couponAccount.owner),
+      listingVault,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+  }
+
+  async function buyListingAccounts(coupon: PublicKey, buyer: PublicKey) {
+    const { nftMint, listingVault } = await listingAccounts(coupon);
+    const { promotion } = await program.account.coupon.fetch(coupon);
+    const { merchant } = await program.account.promotion.fetch(promotion);
+    const { authority: merchantAuthority } = await program.account.merchant.fetch(merchant);
+    return {
+      promotion,
+      merchant,
+      merchantAuthority,
+      nftMint,
+      listingVault,
+      buyerTokenAccount: getAssociatedTokenAddressSync(nftMint, buyer),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    };
+  }
+
+  async function cancelListingAccounts(listing: PublicKey) {
+    const { coupon } = await program.account.listing.fetch(listing);
+    const { promotion } = await program.account.coupon.fetch(coupon);
+    return { coupon, promotion, ...(await listingAccounts(coupon)) };
+  }
+
   before(async () => {
     accounts = await setupTestAccounts(program, connection);
     
@@ -99,6 +144,7 @@ describe("Marketplace Listing & Trading", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -140,15 +186,7 @@ describe("Marketplace Listing & Trading", () => {
       await program.account.promotion.fetch(promotionPDA);
       console.log("✓ Promotion already exists");
     } catch (e) {
-      await program.methods
-        .createPromotion(
-          50,
-          100,
-          getExpiryTimestamp(30),
-          "food",
-          "Test promotion",
-          new BN(5 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program)
         .accounts({
           promotion: promotionPDA,
           merchant: accounts.merchant1PDA,
@@ -211,10 +249,12 @@ describe("Marketplace Listing & Trading", () => {
   describe("List Coupon for Sale", () => {
     it("Lists coupon for sale", async () => {
       await program.methods
-        .listForSale(listingPrice)
+        .listForSale(listingPrice, null)
         .accounts({
+          ...(await listingAccounts(couponPDA)),
           listing: listingPDA,
           coupon: couponPDA,
+          promotion: promotionPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -228,6 +268,13 @@ describe("Marketplace Listing & Trading", () => {
       assert.equal(listing.price.toString(), listingPrice.toString());
       assert.equal(listing.isActive, true);
       assert.isAbove(listing.createdAt.toNumber(), 0);
+
+      // Royalty is fixed at listing time
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(listing.royaltyBasisPoints, promotion.royaltyBasisPoints);
+
+      const listedCoupon = await program.account.coupon.fetch(couponPDA);
+      assert.isTrue(listedCoupon.isEscrowed);
     });
 
     it("Fails to list with zero price", async () => {
@@ -243,10 +290,12 @@ describe("Marketplace Listing & Trading", () => {
 
       try {
         await program.methods
-          .listForSale(new BN(0))
+          .listForSale(new BN(0), null)
           .accounts({
+            ...(await listingAccounts(testCouponPDA)),
             listing: testListingPDA,
             coupon: testCouponPDA,
+            promotion: promotionPDA,
             seller: accounts.user1.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -259,6 +308,52 @@ describe("Marketplace Listing & Trading", () => {
       }
     });
 
+    it("Fails to list above the promotion's resale cap", async () => {
+      const setResaleTerms = (maxResaleBps: number | null) =>
+        program.methods
+          .setResaleTerms(0, maxResaleBps)
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+
+      const { couponPDA: cappedCouponPDA } = await mintCouponForTest(
+        accounts.user1,
+        new BN(3)
+      );
+      const [cappedListingPDA] = derivePDA(
+        [Buffer.from("listing"), cappedCouponPDA.toBuffer()],
+        program.programId
+      );
+
+      // Resale capped at face value (5 SOL)
+      await setResaleTerms(10_000);
+
+      try {
+        await program.methods
+          .listForSale(new BN(6 * LAMPORTS_PER_SOL), null)
+          .accounts({
+            ...(await listingAccounts(cappedCouponPDA)),
+            listing: cappedListingPDA,
+            coupon: cappedCouponPDA,
+            promotion: promotionPDA,
+            seller: accounts.user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.user1])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("ResalePriceTooHigh");
+      } finally {
+        await setResaleTerms(null);
+      }
+    });
+
     it("Fails to list when not owner", async () => {
       const { couponPDA: user2CouponPDA } = await mintCouponForTest(
         accounts.user2,
@@ -277,11 +372,13 @@ describe("Marketplace Listing & Trading", () => {
         });
 
         await program.methods
-          .listForSale(listingPrice)
+          .listForSale(listingPrice, null)
           .preInstructions([modifyComputeUnits]) // FIX: Add this
           .accounts({
+            ...(await listingAccounts(user2CouponPDA)),
             listing: user2ListingPDA,
             coupon: user2CouponPDA,
+            promotion: promotionPDA,
             seller: accounts.user1.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -306,10 +403,12 @@ describe("Marketplace Listing & Trading", () => {
       );
 
       await program.methods
-        .redeemCoupon()
+        .redeemCoupon(new BN(1), new BN(0))
         .accounts({
           coupon: redeemedCouponPDA,
           nftMint: newMint.publicKey,
+          promotion: promotionPDA,
+          metadata: deriveMetadataPDA(newMint.publicKey)[0],
           tokenAccount: tokenAccount,
           merchant: accounts.merchant1PDA,
           user: accounts.user1.publicKey,
@@ -326,10 +425,12 @@ describe("Marketplace Listing & Trading", () => {
 
       try {
         await program.methods
-          .listForSale(listingPrice)
+          .listForSale(listingPrice, null)
           .accounts({
+            ...(await listingAccounts(redeemedCouponPDA)),
             listing: redeemedListingPDA,
             coupon: redeemedCouponPDA,
+            promotion: promotionPDA,
             seller: accounts.user1.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -348,13 +449,8 @@ describe("Marketplace Listing & Trading", () => {
       const sellerBalanceBefore = await connection.getBalance(accounts.user1.publicKey);
       const buyerBalanceBefore = await connection.getBalance(accounts.user2.publicKey);
 
-      // FIX: Get actual marketplace authority
       const marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
-      const actualMarketplaceAuthority = marketplace.authority;
-      
-      const marketplaceAuthorityBalanceBefore = await connection.getBalance(
-        actualMarketplaceAuthority
-      );
+      const treasuryBalanceBefore = await connection.getBalance(accounts.treasuryPDA);
 
       const fee = listingPrice.mul(new BN(marketplace.feeBasisPoints)).div(new BN(10000));
       const sellerAmount = listingPrice.sub(fee);
@@ -362,12 +458,13 @@ describe("Marketplace Listing & Trading", () => {
       await program.methods
         .buyListing()
         .accounts({
+          ...(await buyListingAccounts(couponPDA, accounts.user2.publicKey)),
           listing: listingPDA,
           coupon: couponPDA,
           marketplace: accounts.marketplacePDA,
           seller: accounts.user1.publicKey,
           buyer: accounts.user2.publicKey,
-          marketplaceAuthority: actualMarketplaceAuthority, // FIX: Use actual authority
+          treasury: accounts.treasuryPDA,
           systemProgram: SystemProgram.programId,
         })
         .signers([accounts.user2])
@@ -375,37 +472,40 @@ describe("Marketplace Listing & Trading", () => {
 
       const coupon = await program.account.coupon.fetch(couponPDA);
       assert.equal(coupon.owner.toString(), accounts.user2.publicKey.toString());
+      assert.isFalse(coupon.isEscrowed);
 
-      const listing = await program.account.listing.fetch(listingPDA);
-      assert.equal(listing.isActive, false);
+      // The listing is closed once sold
+      const listing = await program.account.listing.fetchNullable(listingPDA);
+      assert.isNull(listing);
 
       const sellerBalanceAfter = await connection.getBalance(accounts.user1.publicKey);
-      const marketplaceAuthorityBalanceAfter = await connection.getBalance(
-        actualMarketplaceAuthority
-      );
+      const treasuryBalanceAfter = await connection.getBalance(accounts.treasuryPDA);
 
       const sellerDiff = sellerBalanceAfter - sellerBalanceBefore;
       assert.approximately(sellerDiff, sellerAmount.toNumber(), LAMPORTS_PER_SOL * 0.01);
 
-      const feeDiff = marketplaceAuthorityBalanceAfter - marketplaceAuthorityBalanceBefore;
-      assert.approximately(feeDiff, fee.toNumber(), LAMPORTS_PER_SOL * 0.01);
+      const feeDiff = treasuryBalanceAfter - treasuryBalanceBefore;
+      assert.equal(feeDiff, fee.toNumber());
+
+      const marketplaceAfter = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      assert.equal(
+        marketplaceAfter.totalFeesCollected.sub(marketplace.totalFeesCollected).toNumber(),
+        fee.toNumber()
+      );
     });
 
     it("Fails to buy inactive listing", async () => {
-      // FIX: Get actual marketplace authority
-      const marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
-      const actualMarketplaceAuthority = marketplace.authority;
-
       try {
         await program.methods
           .buyListing()
           .accounts({
+            ...(await buyListingAccounts(couponPDA, accounts.user1.publicKey)),
             listing: listingPDA,
             coupon: couponPDA,
             marketplace: accounts.marketplacePDA,
             seller: accounts.user2.publicKey, // FIX: Now owned by user2
             buyer: accounts.user1.publicKey,
-            marketplaceAuthority: actualMarketplaceAuthority, // FIX: Use actual authority
+            treasury: accounts.treasuryPDA,
             systemProgram: SystemProgram.programId,
           })
           .signers([accounts.user1])
@@ -418,7 +518,8 @@ describe("Marketplace Listing & Trading", () => {
         const hasExpectedError = 
           errorStr.includes("listinginactive") || 
           errorStr.includes("inactive") ||
-          errorStr.includes("constraint");
+          errorStr.includes("constraint") ||
+          errorStr.includes("accountnotinitialized"); // vault is closed after the sale
         
         expect(hasExpectedError).to.be.true;
       }
@@ -438,10 +539,12 @@ describe("Marketplace Listing & Trading", () => {
       const veryHighPrice = new BN(1000 * LAMPORTS_PER_SOL);
 
       await program.methods
-        .listForSale(veryHighPrice)
+        .listForSale(veryHighPrice, null)
         .accounts({
+          ...(await listingAccounts(highPriceCouponPDA)),
           listing: highPriceListingPDA,
           coupon: highPriceCouponPDA,
+          promotion: promotionPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -452,20 +555,17 @@ describe("Marketplace Listing & Trading", () => {
       const signature = await connection.requestAirdrop(poorUser.publicKey, 0.1 * LAMPORTS_PER_SOL);
       await connection.confirmTransaction(signature);
 
-      // FIX: Get actual marketplace authority
-      const marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
-      const actualMarketplaceAuthority = marketplace.authority;
-
       try {
         await program.methods
           .buyListing()
           .accounts({
+            ...(await buyListingAccounts(highPriceCouponPDA, poorUser.publicKey)),
             listing: highPriceListingPDA,
             coupon: highPriceCouponPDA,
             marketplace: accounts.marketplacePDA,
             seller: accounts.user1.publicKey,
             buyer: poorUser.publicKey,
-            marketplaceAuthority: actualMarketplaceAuthority, // FIX: Use actual authority
+            treasury: accounts.treasuryPDA,
             systemProgram: SystemProgram.programId,
           })
           .signers([poorUser])
@@ -488,10 +588,12 @@ describe("Marketplace Listing & Trading", () => {
       );
 
       await program.methods
-        .listForSale(listingPrice)
+        .listForSale(listingPrice, null)
         .accounts({
+          ...(await listingAccounts(newCouponPDA)),
           listing: newListingPDA,
           coupon: newCouponPDA,
+          promotion: promotionPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -500,19 +602,29 @@ describe("Marketplace Listing & Trading", () => {
 
       const listingBefore = await program.account.listing.fetch(newListingPDA);
       assert.equal(listingBefore.isActive, true);
+      const promotionBefore = await program.account.promotion.fetch(promotionPDA);
+      assert.isAbove(promotionBefore.openEscrows, 0);
 
       // Cancel the listing
       await program.methods
         .cancelListing()
         .accounts({
+          ...(await cancelListingAccounts(newListingPDA)),
           listing: newListingPDA,
           seller: accounts.user1.publicKey,
         })
         .signers([accounts.user1])
         .rpc();
 
-      const listingAfter = await program.account.listing.fetch(newListingPDA);
-      assert.equal(listingAfter.isActive, false);
+      const listingAfter = await program.account.listing.fetchNullable(newListingPDA);
+      assert.isNull(listingAfter);
+
+      const couponAfter = await program.account.coupon.fetch(newCouponPDA);
+      assert.isFalse(couponAfter.isEscrowed);
+
+      // The promotion no longer counts the coupon as escrowed
+      const promotionAfter = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotionAfter.openEscrows, promotionBefore.openEscrows - 1);
     });
 
     it("Fails to cancel listing by non-seller", async () => {
@@ -524,10 +636,12 @@ describe("Marketplace Listing & Trading", () => {
       );
 
       await program.methods
-        .listForSale(listingPrice)
+        .listForSale(listingPrice, null)
         .accounts({
+          ...(await listingAccounts(newCouponPDA)),
           listing: newListingPDA,
           coupon: newCouponPDA,
+          promotion: promotionPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -538,6 +652,7 @@ describe("Marketplace Listing & Trading", () => {
         await program.methods
           .cancelListing()
           .accounts({
+            ...(await cancelListingAccounts(newListingPDA)),
             listing: newListingPDA,
             seller: accounts.user2.publicKey, // Wrong seller
           })
@@ -559,20 +674,26 @@ describe("Marketplace Listing & Trading", () => {
       );
 
       await program.methods
-        .listForSale(listingPrice)
+        .listForSale(listingPrice, null)
         .accounts({
+          ...(await listingAccounts(newCouponPDA)),
           listing: newListingPDA,
           coupon: newCouponPDA,
+          promotion: promotionPDA,
           seller: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
         .signers([accounts.user1])
         .rpc();
 
+      // Resolve the accounts while the listing still exists
+      const cancelAccounts = await cancelListingAccounts(newListingPDA);
+
       // Cancel the listing first
       await program.methods
         .cancelListing()
         .accounts({
+          ...cancelAccounts,
           listing: newListingPDA,
           seller: accounts.user1.publicKey,
         })
@@ -584,6 +705,7 @@ describe("Marketplace Listing & Trading", () => {
         await program.methods
           .cancelListing()
           .accounts({
+            ...cancelAccounts,
             listing: newListingPDA,
             seller: accounts.user1.publicKey,
           })
@@ -592,8 +714,156 @@ describe("Marketplace Listing & Trading", () => {
         
         assert.fail("Should have thrown an error");
       } catch (error) {
-        expect(error.message).to.include("ListingInactive");
+        // Cancelling closes the listing account
+        expect(error.message).to.include("AccountNotInitialized");
+      }
+    });
+  });
+
+  describe("SPL Token Payments", () => {
+    let paymentMint: PublicKey;
+
+    before(async () => {
+      // 6 decimals, like USDC
+      paymentMint = await createMint(
+        connection,
+        accounts.marketplaceAuthority,
+        accounts.marketplaceAuthority.publicKey,
+        null,
+        6
+      );
+    });
+
+    it("Fails to list with a mint that is not allow-listed", async () => {
+      const { couponPDA: splCouponPDA } = await mintCouponForTest(accounts.user1, new BN(6));
+      const [splListingPDA] = derivePDA(
+        [Buffer.from("listing"), splCouponPDA.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .listForSale(new BN(10_000_000), paymentMint)
+          .accounts({
+            ...(await listingAccounts(splCouponPDA)),
+            listing: splListingPDA,
+            coupon: splCouponPDA,
+            promotion: promotionPDA,
+            seller: accounts.user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.user1])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PaymentMintNotAccepted");
+      }
+    });
+
+    it("Lists and sells a coupon for an allow-listed token", async function () {
+      const marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      if (!marketplace.authority.equals(accounts.marketplaceAuthority.publicKey)) {
+        this.skip();
       }
+
+      const treasuryPaymentAccount = getAssociatedTokenAddressSync(
+        paymentMint,
+        accounts.treasuryPDA,
+        true
+      );
+
+      await program.methods
+        .addPaymentMint()
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
+          paymentMint,
+          treasuryPaymentAccount,
+          authority: accounts.marketplaceAuthority.publicKey,
+          paymentTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      const sellerPaymentAccount = (
+        await getOrCreateAssociatedTokenAccount(connection, accounts.user1, paymentMint, accounts.user1.publicKey)
+      ).address;
+      const buyerPaymentAccount = (
+        await getOrCreateAssociatedTokenAccount(connection, accounts.user2, paymentMint, accounts.user2.publicKey)
+      ).address;
+      await mintTo(
+        connection,
+        accounts.marketplaceAuthority,
+        paymentMint,
+        buyerPaymentAccount,
+        accounts.marketplaceAuthority,
+        100_000_000
+      );
+
+      const { couponPDA: splCouponPDA } = await mintCouponForTest(accounts.user1, new BN(7));
+      const [splListingPDA] = derivePDA(
+        [Buffer.from("listing"), splCouponPDA.toBuffer()],
+        program.programId
+      );
+      const price = new BN(20_000_000); // 20 tokens
+
+      await program.methods
+        .listForSale(price, paymentMint)
+        .accounts({
+          ...(await listingAccounts(splCouponPDA)),
+          listing: splListingPDA,
+          coupon: splCouponPDA,
+          promotion: promotionPDA,
+          seller: accounts.user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.user1])
+        .rpc();
+
+      const listing = await program.account.listing.fetch(splListingPDA);
+      assert.equal(listing.paymentMint.toString(), paymentMint.toString());
+
+      await program.methods
+        .buyListing()
+        .accounts({
+          ...(await buyListingAccounts(splCouponPDA, accounts.user2.publicKey)),
+          listing: splListingPDA,
+          coupon: splCouponPDA,
+          marketplace: accounts.marketplacePDA,
+          seller: accounts.user1.publicKey,
+          buyer: accounts.user2.publicKey,
+          treasury: accounts.treasuryPDA,
+          paymentMint,
+          buyerPaymentAccount,
+          sellerPaymentAccount,
+          treasuryPaymentAccount,
+          paymentTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.user2])
+        .rpc();
+
+      const fee = price.mul(new BN(marketplace.feeBasisPoints)).div(new BN(10000));
+      const seller = await getAccount(connection, sellerPaymentAccount);
+      const treasury = await getAccount(connection, treasuryPaymentAccount);
+      assert.equal(seller.amount.toString(), price.sub(fee).toString());
+      assert.equal(treasury.amount.toString(), fee.toString());
+
+      const coupon = await program.account.coupon.fetch(splCouponPDA);
+      assert.equal(coupon.owner.toString(), accounts.user2.publicKey.toString());
+
+      await program.methods
+        .removePaymentMint(paymentMint)
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      const marketplaceAfter = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      assert.isFalse(marketplaceAfter.acceptedMints.some((m) => m.equals(paymentMint)));
     });
   });
-});
\ No newline at end of file
+});
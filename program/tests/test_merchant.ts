@@ -1,5 +1,5 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Program } from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
 import { DiscountPlatform } from "../target/types/discount_platform";
 import { SystemProgram, Keypair } from "@solana/web3.js";
 import { assert, expect } from "chai";
@@ -8,7 +8,10 @@ import {
   TestAccounts, 
   airdrop,
   derivePDA,
-  accountExists
+  accountExists,
+  getExpiryTimestamp,
+  u64ToLeBytes,
+  createPromotion
 } from "./setup";
 
 describe("Merchant Registration", () => {
@@ -36,6 +39,7 @@ describe("Merchant Registration", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -146,4 +150,555 @@ describe("Merchant Registration", () => {
       expect(error.message).to.include("InvalidCoordinates");
     }
   });
+
+  describe("Merchant Management", () => {
+    let owner: Keypair;
+    let merchantPDA: anchor.web3.PublicKey;
+
+    before(async () => {
+      // Fresh merchant so deactivation doesn't leak into the shared merchants
+      owner = Keypair.generate();
+      await airdrop(connection, owner.publicKey);
+
+      [merchantPDA] = derivePDA(
+        [Buffer.from("merchant"), owner.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerMerchant("Corner Cafe", "cafe", null, null)
+        .accounts({
+          merchant: merchantPDA,
+          marketplace: accounts.marketplacePDA,
+          authority: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    });
+
+    it("Updates the merchant profile", async () => {
+      await program.methods
+        .updateMerchant("Corner Bistro", "restaurant", merchantLatitude, merchantLongitude)
+        .accounts({
+          merchant: merchantPDA,
+          authority: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+      const merchant = await program.account.merchant.fetch(merchantPDA);
+      assert.equal(merchant.name, "Corner Bistro");
+      assert.equal(merchant.category, "restaurant");
+      assert.equal(merchant.hasPhysicalLocation, true);
+      assert.approximately(merchant.location.latitude / 1_000_000, merchantLatitude, 0.000001);
+    });
+
+    it("Rejects profile updates from another signer", async () => {
+      try {
+        await program.methods
+          .updateMerchant("Hijacked", null, null, null)
+          .accounts({
+            merchant: merchantPDA,
+            authority: accounts.merchant2.publicKey,
+          })
+          .signers([accounts.merchant2])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("NotMerchantAuthority");
+      }
+    });
+
+    it("Blocks promotion creation while the merchant is inactive", async () => {
+      await program.methods
+        .setMerchantActive(false)
+        .accounts({
+          merchant: merchantPDA,
+          marketplace: accounts.marketplacePDA,
+          authority: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+      const merchant = await program.account.merchant.fetch(merchantPDA);
+      assert.equal(merchant.isActive, false);
+
+      const [promotionPDA] = derivePDA(
+        [
+          Buffer.from("promotion"),
+          merchantPDA.toBuffer(),
+          u64ToLeBytes(merchant.totalCouponsCreated),
+        ],
+        program.programId
+      );
+
+      try {
+        await createPromotion(program, {
+          discountPercentage: 10,
+          maxSupply: 10,
+          description: "Inactive",
+          price: new BN(0),
+        })
+          .accounts({
+            promotion: promotionPDA,
+            merchant: merchantPDA,
+            authority: owner.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("MerchantInactive");
+      }
+
+      await program.methods
+        .setMerchantActive(true)
+        .accounts({
+          merchant: merchantPDA,
+          marketplace: accounts.marketplacePDA,
+          authority: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+    });
+
+    it("Only the marketplace authority can lift a suspension", async () => {
+      await program.methods
+        .setMerchantActive(false)
+        .accounts({
+          merchant: merchantPDA,
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      let merchant = await program.account.merchant.fetch(merchantPDA);
+      assert.equal(merchant.suspendedByMarketplace, true);
+
+      try {
+        await program.methods
+          .setMerchantActive(true)
+          .accounts({
+            merchant: merchantPDA,
+            marketplace: accounts.marketplacePDA,
+            authority: owner.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("MerchantSuspended");
+      }
+
+      await program.methods
+        .setMerchantActive(true)
+        .accounts({
+          merchant: merchantPDA,
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      merchant = await program.account.merchant.fetch(merchantPDA);
+      assert.equal(merchant.isActive, true);
+      assert.equal(merchant.suspendedByMarketplace, false);
+    });
+
+    it("Transfers the merchant authority", async () => {
+      const newOwner = Keypair.generate();
+
+      await program.methods
+        .transferMerchantAuthority()
+        .accounts({
+          merchant: merchantPDA,
+          authority: owner.publicKey,
+          newAuthority: newOwner.publicKey,
+        })
+        .signers([owner, newOwner])
+        .rpc();
+
+      const merchant = await program.account.merchant.fetch(merchantPDA);
+      assert.equal(merchant.authority.toString(), newOwner.publicKey.toString());
+
+      try {
+        await program.methods
+          .updateMerchant("Old Owner", null, null, null)
+          .accounts({
+            merchant: merchantPDA,
+            authority: owner.publicKey,
+          })
+          .signers([owner])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("NotMerchantAuthority");
+      }
+    });
+  });
+
+  describe("Merchant Verification", () => {
+    const attestation = Array.from(Buffer.alloc(32, 7));
+    const verifier = Keypair.generate();
+
+    it("Marketplace authority verifies a merchant", async () => {
+      const expiresAt = getExpiryTimestamp(365);
+
+      await program.methods
+        .verifyMerchant({ verified: {} }, attestation, expiresAt)
+        .accounts({
+          merchant: accounts.merchant2PDA,
+          marketplace: accounts.marketplacePDA,
+          verifier: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      const merchant = await program.account.merchant.fetch(accounts.merchant2PDA);
+      assert.deepEqual(merchant.verificationTier, { verified: {} });
+      assert.deepEqual(Array.from(merchant.attestationHash), attestation);
+      assert.equal(merchant.verificationExpiresAt.toString(), expiresAt.toString());
+      assert.equal(
+        merchant.verifiedBy.toString(),
+        accounts.marketplaceAuthority.publicKey.toString()
+      );
+    });
+
+    it("Rejects verification from an undelegated key", async () => {
+      try {
+        await program.methods
+          .verifyMerchant({ premium: {} }, attestation, getExpiryTimestamp(365))
+          .accounts({
+            merchant: accounts.merchant2PDA,
+            marketplace: accounts.marketplacePDA,
+            verifier: verifier.publicKey,
+          })
+          .signers([verifier])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("NotVerifier");
+      }
+    });
+
+    it("Delegated verifier upgrades and then revokes verification", async () => {
+      await program.methods
+        .addVerifier(verifier.publicKey)
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      await program.methods
+        .verifyMerchant({ premium: {} }, attestation, getExpiryTimestamp(365))
+        .accounts({
+          merchant: accounts.merchant2PDA,
+          marketplace: accounts.marketplacePDA,
+          verifier: verifier.publicKey,
+        })
+        .signers([verifier])
+        .rpc();
+
+      let merchant = await program.account.merchant.fetch(accounts.merchant2PDA);
+      assert.deepEqual(merchant.verificationTier, { premium: {} });
+      assert.equal(merchant.verifiedBy.toString(), verifier.publicKey.toString());
+
+      await program.methods
+        .revokeMerchantVerification()
+        .accounts({
+          merchant: accounts.merchant2PDA,
+          marketplace: accounts.marketplacePDA,
+          verifier: verifier.publicKey,
+        })
+        .signers([verifier])
+        .rpc();
+
+      merchant = await program.account.merchant.fetch(accounts.merchant2PDA);
+      assert.deepEqual(merchant.verificationTier, { unverified: {} });
+
+      await program.methods
+        .removeVerifier(verifier.publicKey)
+        .accounts({
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.marketplaceAuthority.publicKey,
+        })
+        .signers([accounts.marketplaceAuthority])
+        .rpc();
+
+      const marketplace = await program.account.marketplace.fetch(accounts.marketplacePDA);
+      assert.isFalse(
+        marketplace.verifiers.some((key) => key.equals(verifier.publicKey))
+      );
+    });
+  });
+
+  describe("Merchant Staff", () => {
+    let owner: Keypair;
+    let merchantPDA: anchor.web3.PublicKey;
+    let promotionPDA: anchor.web3.PublicKey;
+    const manager = Keypair.generate();
+    const cashier = Keypair.generate();
+
+    const staffPDA = (staff: anchor.web3.PublicKey) =>
+      derivePDA(
+        [Buffer.from("merchant_staff"), merchantPDA.toBuffer(), staff.toBuffer()],
+        program.programId
+      )[0];
+
+    before(async () => {
+      owner = Keypair.generate();
+      await Promise.all([
+        airdrop(connection, owner.publicKey),
+        airdrop(connection, manager.publicKey),
+        airdrop(connection, cashier.publicKey),
+      ]);
+
+      [merchantPDA] = derivePDA(
+        [Buffer.from("merchant"), owner.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerMerchant("Chain Store", "retail", null, null)
+        .accounts({
+          merchant: merchantPDA,
+          marketplace: accounts.marketplacePDA,
+          authority: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    });
+
+    it("Adds a manager and a cashier with a store location", async () => {
+      await program.methods
+        .addStaff(manager.publicKey, { manager: {} }, null, null)
+        .accounts({
+          staffMember: staffPDA(manager.publicKey),
+          merchant: merchantPDA,
+          authorityStaff: null,
+          authority: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+      await program.methods
+        .addStaff(cashier.publicKey, { cashier: {} }, merchantLatitude, merchantLongitude)
+        .accounts({
+          staffMember: staffPDA(cashier.publicKey),
+          merchant: merchantPDA,
+          authorityStaff: null,
+          authority: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+      const entry = await program.account.merchantStaff.fetch(staffPDA(cashier.publicKey));
+      assert.equal(entry.merchant.toString(), merchantPDA.toString());
+      assert.deepEqual(entry.role, { cashier: {} });
+      assert.equal(entry.isActive, true);
+      assert.equal(entry.hasStoreLocation, true);
+    });
+
+    it("Manager creates a promotion", async () => {
+      const merchant = await program.account.merchant.fetch(merchantPDA);
+      [promotionPDA] = derivePDA(
+        [
+          Buffer.from("promotion"),
+          merchantPDA.toBuffer(),
+          u64ToLeBytes(merchant.totalCouponsCreated),
+        ],
+        program.programId
+      );
+
+      await createPromotion(program, {
+        discountPercentage: 15,
+        category: "retail",
+        description: "Staff promo",
+        price: new BN(0),
+      })
+        .accounts({
+          promotion: promotionPDA,
+          merchant: merchantPDA,
+          staff: staffPDA(manager.publicKey),
+          authority: manager.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([manager])
+        .rpc();
+
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.merchant.toString(), merchantPDA.toString());
+    });
+
+    it("Cashier cannot pause a promotion", async () => {
+      try {
+        await program.methods
+          .pausePromotion()
+          .accounts({
+            promotion: promotionPDA,
+            merchant: merchantPDA,
+            staff: staffPDA(cashier.publicKey),
+            authority: cashier.publicKey,
+          })
+          .signers([cashier])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("StaffRoleNotPermitted");
+      }
+    });
+
+    it("Deactivated manager loses access", async () => {
+      await program.methods
+        .updateStaff(null, false, null, null)
+        .accounts({
+          staffMember: staffPDA(manager.publicKey),
+          merchant: merchantPDA,
+          authorityStaff: null,
+          authority: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+      try {
+        await program.methods
+          .pausePromotion()
+          .accounts({
+            promotion: promotionPDA,
+            merchant: merchantPDA,
+            staff: staffPDA(manager.publicKey),
+            authority: manager.publicKey,
+          })
+          .signers([manager])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("NotMerchantAuthority");
+      }
+    });
+
+    it("Removes a staff member", async () => {
+      await program.methods
+        .removeStaff()
+        .accounts({
+          staffMember: staffPDA(cashier.publicKey),
+          merchant: merchantPDA,
+          authorityStaff: null,
+          authority: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+      assert.equal(await accountExists(connection, staffPDA(cashier.publicKey)), false);
+    });
+  });
+
+  describe("Revenue Split", () => {
+    let owner: Keypair;
+    let merchantPDA: anchor.web3.PublicKey;
+    let revenueConfigPDA: anchor.web3.PublicKey;
+    const landlord = Keypair.generate().publicKey;
+    const charity = Keypair.generate().publicKey;
+
+    before(async () => {
+      owner = Keypair.generate();
+      await airdrop(connection, owner.publicKey);
+
+      [merchantPDA] = derivePDA(
+        [Buffer.from("merchant"), owner.publicKey.toBuffer()],
+        program.programId
+      );
+      [revenueConfigPDA] = derivePDA(
+        [Buffer.from("revenue_config"), merchantPDA.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .registerMerchant("Franchise", "restaurant", null, null)
+        .accounts({
+          merchant: merchantPDA,
+          marketplace: accounts.marketplacePDA,
+          authority: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    });
+
+    it("Rejects shares that don't add up to 100%", async () => {
+      try {
+        await program.methods
+          .setRevenueConfig([
+            { recipient: owner.publicKey, shareBps: 8000 },
+            { recipient: landlord, shareBps: 1000 },
+          ])
+          .accounts({
+            revenueConfig: revenueConfigPDA,
+            merchant: merchantPDA,
+            authority: owner.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([owner])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidRevenueSplit");
+      }
+    });
+
+    it("Sets and removes a three-way split", async () => {
+      await program.methods
+        .setRevenueConfig([
+          { recipient: owner.publicKey, shareBps: 8000 },
+          { recipient: landlord, shareBps: 1500 },
+          { recipient: charity, shareBps: 500 },
+        ])
+        .accounts({
+          revenueConfig: revenueConfigPDA,
+          merchant: merchantPDA,
+          authority: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+
+      const config = await program.account.revenueConfig.fetch(revenueConfigPDA);
+      assert.equal(config.recipients.length, 3);
+      assert.equal(config.recipients[1].recipient.toString(), landlord.toString());
+      assert.equal(config.recipients[2].shareBps, 500);
+
+      let merchant = await program.account.merchant.fetch(merchantPDA);
+      assert.equal(merchant.hasRevenueSplit, true);
+
+      await program.methods
+        .closeRevenueConfig()
+        .accounts({
+          revenueConfig: revenueConfigPDA,
+          merchant: merchantPDA,
+          authority: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+
+      merchant = await program.account.merchant.fetch(merchantPDA);
+      assert.equal(merchant.hasRevenueSplit, false);
+      assert.equal(await accountExists(connection, revenueConfigPDA), false);
+    });
+  });
 });
\ No newline at end of file
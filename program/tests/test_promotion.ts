@@ -11,7 +11,8 @@ import {
   derivePDA,
   accountExists,
   LAMPORTS_PER_SOL,
-  u64ToLeBytes
+  u64ToLeBytes,
+  createPromotion
 } from "./setup";
 
 describe("Promotion Creation", () => {
@@ -40,6 +41,7 @@ describe("Promotion Creation", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -88,15 +90,14 @@ describe("Promotion Creation", () => {
 
     const expiryTimestamp = getExpiryTimestamp(30);
 
-    await program.methods
-      .createPromotion(
-        discountPercentage,
-        maxSupply,
-        expiryTimestamp,
-        promotionCategory,
-        promotionDescription,
-        price
-      )
+    await createPromotion(program, {
+      discountPercentage,
+      maxSupply,
+      expiryTimestamp,
+      category: promotionCategory,
+      description: promotionDescription,
+      price,
+    })
       .accounts({
         promotion: promotionPDA,
         merchant: accounts.merchant1PDA,
@@ -119,6 +120,9 @@ describe("Promotion Creation", () => {
     assert.equal(promotion.isLocationBased, false);
     assert.equal(promotion.radiusMeters, 0);
     assert.equal(promotion.geoCellId.toNumber(), 0);
+    assert.equal(promotion.metadataBaseUri, "https://example.com/coupons");
+    assert.equal(promotion.nftName, "Test Coupon");
+    assert.equal(promotion.nftSymbol, "TC");
   });
 
   it("Creates multiple promotions for same merchant", async () => {
@@ -137,15 +141,13 @@ describe("Promotion Creation", () => {
       const exists = await accountExists(connection, promo);
       if (exists) continue;
 
-      await program.methods
-        .createPromotion(
-          25 + (i + 1) * 5,
-          50 + (i + 1) * 10,
-          getExpiryTimestamp(30),
-          `category${i + 1}`,
-          `Description ${i + 1}`,
-          new BN((i + 2) * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, {
+        discountPercentage: 25 + (i + 1) * 5,
+        maxSupply: 50 + (i + 1) * 10,
+        category: `category${i + 1}`,
+        description: `Description ${i + 1}`,
+        price: new BN((i + 2) * LAMPORTS_PER_SOL),
+      })
         .accounts({
           promotion: promo,
           merchant: accounts.merchant1PDA,
@@ -176,8 +178,13 @@ describe("Promotion Creation", () => {
     }
 
     try {
-      await program.methods
-        .createPromotion(0, maxSupply, getExpiryTimestamp(30), promotionCategory, promotionDescription, price)
+      await createPromotion(program, {
+        discountPercentage: 0,
+        maxSupply,
+        category: promotionCategory,
+        description: promotionDescription,
+        price,
+      })
         .accounts({
           promotion: promo,
           merchant: accounts.merchant1PDA,
@@ -202,8 +209,13 @@ describe("Promotion Creation", () => {
     );
 
     try {
-      await program.methods
-        .createPromotion(101, maxSupply, getExpiryTimestamp(30), promotionCategory, promotionDescription, price)
+      await createPromotion(program, {
+        discountPercentage: 101,
+        maxSupply,
+        category: promotionCategory,
+        description: promotionDescription,
+        price,
+      })
         .accounts({
           promotion: promo,
           merchant: accounts.merchant1PDA,
@@ -229,8 +241,12 @@ describe("Promotion Creation", () => {
     );
 
     try {
-      await program.methods
-        .createPromotion(50, 0, getExpiryTimestamp(30), promotionCategory, promotionDescription, price)
+      await createPromotion(program, {
+        maxSupply: 0,
+        category: promotionCategory,
+        description: promotionDescription,
+        price,
+      })
         .accounts({
           promotion: promo,
           merchant: accounts.merchant1PDA,
@@ -258,8 +274,13 @@ describe("Promotion Creation", () => {
     const pastTimestamp = new BN(getCurrentTimestamp() - 3600);
 
     try {
-      await program.methods
-        .createPromotion(50, maxSupply, pastTimestamp, promotionCategory, promotionDescription, price)
+      await createPromotion(program, {
+        maxSupply,
+        expiryTimestamp: pastTimestamp,
+        category: promotionCategory,
+        description: promotionDescription,
+        price,
+      })
         .accounts({
           promotion: promo,
           merchant: accounts.merchant1PDA,
@@ -287,8 +308,12 @@ describe("Promotion Creation", () => {
     const longCategory = "A".repeat(31);
 
     try {
-      await program.methods
-        .createPromotion(50, maxSupply, getExpiryTimestamp(30), longCategory, promotionDescription, price)
+      await createPromotion(program, {
+        maxSupply,
+        category: longCategory,
+        description: promotionDescription,
+        price,
+      })
         .accounts({
           promotion: promo,
           merchant: accounts.merchant1PDA,
@@ -316,8 +341,12 @@ describe("Promotion Creation", () => {
     const longDescription = "A".repeat(201);
 
     try {
-      await program.methods
-        .createPromotion(50, maxSupply, getExpiryTimestamp(30), promotionCategory, longDescription, price)
+      await createPromotion(program, {
+        maxSupply,
+        category: promotionCategory,
+        description: longDescription,
+        price,
+      })
         .accounts({
           promotion: promo,
           merchant: accounts.merchant1PDA,
@@ -334,6 +363,68 @@ describe("Promotion Creation", () => {
     }
   });
 
+  it("Fails with NFT symbol too long", async () => {
+    const merchant = await program.account.merchant.fetch(accounts.merchant1PDA);
+    
+    const [promo] = derivePDA(
+      [Buffer.from("promotion"), accounts.merchant1PDA.toBuffer(), u64ToLeBytes(merchant.totalCouponsCreated)],
+      program.programId
+    );
+
+    try {
+      await createPromotion(program, {
+        maxSupply,
+        category: promotionCategory,
+        description: promotionDescription,
+        price,
+        nftSymbol: "A".repeat(11),
+      })
+        .accounts({
+          promotion: promo,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+      
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.toString()).to.include("InvalidNftMetadata");
+    }
+  });
+
+  it("Fails with an empty stored-value balance", async () => {
+    const merchant = await program.account.merchant.fetch(accounts.merchant1PDA);
+    
+    const [promo] = derivePDA(
+      [Buffer.from("promotion"), accounts.merchant1PDA.toBuffer(), u64ToLeBytes(merchant.totalCouponsCreated)],
+      program.programId
+    );
+
+    try {
+      await createPromotion(program, {
+        maxSupply,
+        category: promotionCategory,
+        description: promotionDescription,
+        price,
+        redemptionModel: { storedValue: { balance: new BN(0) } },
+      })
+        .accounts({
+          promotion: promo,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+      
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.toString()).to.include("InvalidRedemptionModel");
+    }
+  });
+
   it("Fails when non-authority tries to create promotion", async () => {
     const merchant = await program.account.merchant.fetch(accounts.merchant1PDA);
     
@@ -343,8 +434,12 @@ describe("Promotion Creation", () => {
     );
 
     try {
-      await program.methods
-        .createPromotion(50, maxSupply, getExpiryTimestamp(30), promotionCategory, promotionDescription, price)
+      await createPromotion(program, {
+        maxSupply,
+        category: promotionCategory,
+        description: promotionDescription,
+        price,
+      })
         .accounts({
           promotion: promo,
           merchant: accounts.merchant1PDA,
@@ -374,8 +469,13 @@ describe("Promotion Creation", () => {
       return;
     }
 
-    await program.methods
-      .createPromotion(1, maxSupply, getExpiryTimestamp(30), promotionCategory, "Minimum discount test", price)
+    await createPromotion(program, {
+      discountPercentage: 1,
+      maxSupply,
+      category: promotionCategory,
+      description: "Minimum discount test",
+      price,
+    })
       .accounts({
         promotion: promo,
         merchant: accounts.merchant1PDA,
@@ -403,8 +503,13 @@ describe("Promotion Creation", () => {
       return;
     }
 
-    await program.methods
-      .createPromotion(100, maxSupply, getExpiryTimestamp(30), promotionCategory, "Maximum discount test", price)
+    await createPromotion(program, {
+      discountPercentage: 100,
+      maxSupply,
+      category: promotionCategory,
+      description: "Maximum discount test",
+      price,
+    })
       .accounts({
         promotion: promo,
         merchant: accounts.merchant1PDA,
@@ -432,8 +537,12 @@ describe("Promotion Creation", () => {
       return;
     }
 
-    await program.methods
-      .createPromotion(50, 1, getExpiryTimestamp(30), promotionCategory, "Limited supply test", price)
+    await createPromotion(program, {
+      maxSupply: 1,
+      category: promotionCategory,
+      description: "Limited supply test",
+      price,
+    })
       .accounts({
         promotion: promo,
         merchant: accounts.merchant1PDA,
@@ -446,4 +555,301 @@ describe("Promotion Creation", () => {
     const promotion = await program.account.promotion.fetch(promo);
     assert.equal(promotion.maxSupply, 1);
   });
+  describe("Promotion Lifecycle", () => {
+    let promotionPDA: anchor.web3.PublicKey;
+
+    before(async () => {
+      const merchant = await program.account.merchant.fetch(accounts.merchant1PDA);
+      [promotionPDA] = derivePDA(
+        [Buffer.from("promotion"), accounts.merchant1PDA.toBuffer(), u64ToLeBytes(merchant.totalCouponsCreated)],
+        program.programId
+      );
+    });
+
+    it("Updates expiry, supply and description", async () => {
+      const before = await program.account.promotion.fetch(promotionPDA);
+      const newExpiry = getExpiryTimestamp(60);
+      const newSupply = before.maxSupply + 50;
+
+      await program.methods
+        .updatePromotion(newExpiry, newSupply, null, "Updated: 50% off all pizzas", null, null)
+        .accounts({
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.expiryTimestamp.toString(), newExpiry.toString());
+      assert.equal(promotion.maxSupply, newSupply);
+      assert.equal(promotion.description, "Updated: 50% off all pizzas");
+      assert.equal(promotion.price.toString(), before.price.toString());
+    });
+
+    it("Fails to lower supply below coupons already minted", async () => {
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      if (promotion.currentSupply === 0) {
+        console.log("  ✓ No coupons minted yet, skipping");
+        return;
+      }
+
+      try {
+        await program.methods
+          .updatePromotion(null, promotion.currentSupply - 1, null, null, null, null)
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidSupply");
+      }
+    });
+
+    it("Fails to move expiry earlier once coupons are minted", async () => {
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      if (promotion.currentSupply === 0) {
+        console.log("  ✓ No coupons minted yet, skipping");
+        return;
+      }
+
+      try {
+        await program.methods
+          .updatePromotion(promotion.expiryTimestamp.subn(3600), null, null, null, null, null)
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidExpiry");
+      }
+    });
+
+    it("Updates the coupon transfer policy", async () => {
+      await program.methods
+        .updatePromotion(null, null, null, null, true, false)
+        .accounts({
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      let promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.transferable, true);
+      assert.equal(promotion.resaleAllowed, false);
+
+      // Resale without transfers is contradictory
+      try {
+        await program.methods
+          .updatePromotion(null, null, null, null, false, true)
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("CouponNotTransferable");
+      }
+
+      await program.methods
+        .updatePromotion(null, null, null, null, null, true)
+        .accounts({
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.resaleAllowed, true);
+    });
+
+    it("Sets the resale royalty and price cap", async () => {
+      await program.methods
+        .setResaleTerms(500, 15_000)
+        .accounts({
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      let promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.royaltyBasisPoints, 500);
+      assert.equal(promotion.maxResaleBps, 15_000);
+
+      try {
+        await program.methods
+          .setResaleTerms(5_000, null)
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidRoyalty");
+      }
+
+      await program.methods
+        .setResaleTerms(0, null)
+        .accounts({
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.royaltyBasisPoints, 0);
+      assert.isNull(promotion.maxResaleBps);
+    });
+
+    it("Fails to change the royalty once coupons are minted", async () => {
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      if (promotion.currentSupply === 0) {
+        console.log("  ✓ No coupons minted yet, skipping");
+        return;
+      }
+
+      try {
+        await program.methods
+          .setResaleTerms(promotion.royaltyBasisPoints + 100, null)
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("RoyaltyLocked");
+      }
+    });
+
+    it("Sets and clears the discount cap", async () => {
+      const setCap = (cap: BN | null) =>
+        program.methods
+          .setDiscountCap(cap)
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+
+      await setCap(new BN(LAMPORTS_PER_SOL));
+      let promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.maxDiscountCap.toNumber(), LAMPORTS_PER_SOL);
+
+      try {
+        await setCap(new BN(0));
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidDiscount");
+      }
+
+      await setCap(null);
+      promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.isNull(promotion.maxDiscountCap);
+    });
+
+    it("Fails when non-authority updates a promotion", async () => {
+      try {
+        await program.methods
+          .updatePromotion(null, null, new BN(1), null, null, null)
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.user1.publicKey,
+          })
+          .signers([accounts.user1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("NotMerchantAuthority");
+      }
+    });
+
+    it("Pauses and resumes a promotion", async () => {
+      await program.methods
+        .pausePromotion()
+        .accounts({
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      let promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.isActive, false);
+
+      try {
+        await program.methods
+          .pausePromotion()
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("PromotionInactive");
+      }
+
+      await program.methods
+        .resumePromotion()
+        .accounts({
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.isActive, true);
+    });
+
+    it("Fails to close a live promotion", async () => {
+      try {
+        await program.methods
+          .closePromotion()
+          .accounts({
+            promotion: promotionPDA,
+            merchant: accounts.merchant1PDA,
+            authority: accounts.merchant1.publicKey,
+          })
+          .signers([accounts.merchant1])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("PromotionStillLive");
+      }
+    });
+  });
 });
\ No newline at end of file
@@ -10,7 +10,8 @@ import {
   derivePDA,
   accountExists,
   u64ToLeBytes,
-  LAMPORTS_PER_SOL
+  LAMPORTS_PER_SOL,
+  createPromotion
 } from "./setup";
 
 describe("Rating System", () => {
@@ -35,6 +36,7 @@ describe("Rating System", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -72,15 +74,7 @@ describe("Rating System", () => {
     // Check and create promotion if needed
     const promotionExists = await accountExists(connection, promotionPDA);
     if (!promotionExists) {
-      await program.methods
-        .createPromotion(
-          50,
-          100,
-          getExpiryTimestamp(30),
-          "food",
-          "Test promotion for rating",
-          new BN(5 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, { description: "Test promotion for rating" })
         .accounts({
           promotion: promotionPDA,
           merchant: accounts.merchant1PDA,
@@ -221,15 +215,13 @@ describe("Rating System", () => {
     // Check if promotion exists, skip creation if it does
     const exists = await accountExists(connection, promotion2PDA);
     if (!exists) {
-      await program.methods
-        .createPromotion(
-          30,
-          50,
-          getExpiryTimestamp(30),
-          "electronics",
-          "Second promotion",
-          new BN(3 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, {
+        discountPercentage: 30,
+        maxSupply: 50,
+        category: "electronics",
+        description: "Second promotion",
+        price: new BN(3 * LAMPORTS_PER_SOL),
+      })
         .accounts({
           promotion: promotion2PDA,
           merchant: accounts.merchant1PDA,
@@ -402,15 +394,13 @@ describe("Rating System", () => {
 
     const exists = await accountExists(connection, promotion2PDA);
     if (!exists) {
-      await program.methods
-        .createPromotion(
-          25,
-          75,
-          getExpiryTimestamp(30),
-          "services",
-          "Third promotion",
-          new BN(2 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, {
+        discountPercentage: 25,
+        maxSupply: 75,
+        category: "services",
+        description: "Third promotion",
+        price: new BN(2 * LAMPORTS_PER_SOL),
+      })
         .accounts({
           promotion: promotion2PDA,
           merchant: accounts.merchant1PDA,
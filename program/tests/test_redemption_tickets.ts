@@ -1,9 +1,10 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN, web3 } from "@coral-xyz/anchor";
 import { DiscountPlatform } from "../target/types/discount_platform";
-import { SystemProgram, Keypair, PublicKey } from "@solana/web3.js";
+import { SystemProgram, Keypair, PublicKey, Ed25519Program } from "@solana/web3.js";
 import { getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { assert, expect } from "chai";
+import { createHash } from "crypto";
 import { 
   setupTestAccounts, 
   TestAccounts,
@@ -19,6 +20,7 @@ import {
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_METADATA_PROGRAM_ID,
+  createPromotion,
 } from "./setup";
 
 describe("Redemption Tickets", () => {
@@ -49,6 +51,7 @@ describe("Redemption Tickets", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -86,15 +89,12 @@ describe("Redemption Tickets", () => {
     const promotionExists = await accountExists(connection, promotionPDA);
     if (!promotionExists) {
       console.log("Creating promotion...");
-      await program.methods
-        .createPromotion(
-          50,
-          100,
-          getExpiryTimestamp(7),
-          "Test Promotion",
-          "Test promotion for redemption tickets",
-          new BN(20 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, {
+        expiryTimestamp: getExpiryTimestamp(7),
+        category: "Test Promotion",
+        description: "Test promotion for redemption tickets",
+        price: new BN(20 * LAMPORTS_PER_SOL),
+      })
         .accounts({
           promotion: promotionPDA,
           merchant: accounts.merchant1PDA,
@@ -191,6 +191,7 @@ describe("Redemption Tickets", () => {
         .accounts({
           ticket: ticketPDA,
           coupon: couponPDA,
+          promotion: promotionPDA,
           user: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -239,6 +240,7 @@ describe("Redemption Tickets", () => {
         .accounts({
           ticket: ticketPDA,
           coupon: couponPDA,
+          promotion: promotionPDA,
           user: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -281,6 +283,7 @@ describe("Redemption Tickets", () => {
           .accounts({
             ticket: ticketPDA,
             coupon: couponPDA,
+            promotion: promotionPDA,
             user: accounts.user1.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -316,6 +319,7 @@ describe("Redemption Tickets", () => {
           .accounts({
             ticket: ticketPDA,
             coupon: couponPDA,
+            promotion: promotionPDA,
             user: accounts.user2.publicKey,
             systemProgram: SystemProgram.programId,
           })
@@ -418,6 +422,7 @@ describe("Redemption Tickets", () => {
         .accounts({
           ticket: redeemTicketPDA,
           coupon: newCouponPDA,
+          promotion: promotionPDA,
           user: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -444,11 +449,13 @@ describe("Redemption Tickets", () => {
   const userStatsBefore = await program.account.userStats.fetch(userStatsPDA);
 
   await program.methods
-    .verifyAndRedeemTicket(ticketHash)
+    .verifyAndRedeemTicket(ticketHash, new BN(1), new BN(0))
     .accounts({
       ticket: redeemTicketPDA,
       coupon: couponPDA,
       nftMint: couponMint.publicKey,
+      promotion: promotionPDA,
+      metadata: deriveMetadataPDA(couponMint.publicKey)[0],
       tokenAccount: tokenAccount,
       merchant: accounts.merchant1PDA,
       userStats: userStatsPDA,
@@ -460,7 +467,7 @@ describe("Redemption Tickets", () => {
     .signers([accounts.merchant1, accounts.user1])  // ← Both must sign!
     .rpc();
 
-  const ticket = await program.account.redemptionTicket.fetch(redeemTicketPDA);
+  const ticketInfo = await connection.getAccountInfo(redeemTicketPDA);
   const coupon = await program.account.coupon.fetch(couponPDA);
   const merchantAfter = await program.account.merchant.fetch(accounts.merchant1PDA);
   const userStatsAfter = await program.account.userStats.fetch(userStatsPDA);
@@ -469,7 +476,7 @@ describe("Redemption Tickets", () => {
   const tokenAccountInfo = await connection.getAccountInfo(tokenAccount);
 
   // Assertions
-  assert.isTrue(ticket.isConsumed, "Ticket is consumed");
+  assert.isNull(ticketInfo, "Consumed ticket closed, rent back to user");
   assert.isTrue(coupon.isRedeemed, "Coupon is redeemed");
   assert.isTrue(coupon.redeemedAt > 0, "Redeemed timestamp set");
   assert.equal(
@@ -573,6 +580,7 @@ describe("Redemption Tickets", () => {
         .accounts({
           ticket: testTicketPDA,
           coupon: testCouponPDA,
+          promotion: promotionPDA,
           user: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -588,11 +596,13 @@ describe("Redemption Tickets", () => {
 
   try {
     await program.methods
-      .verifyAndRedeemTicket(wrongHash)
+      .verifyAndRedeemTicket(wrongHash, new BN(1), new BN(0))
       .accounts({
         ticket: testTicketPDA,
         coupon: testCouponPDA,
         nftMint: testCouponMint.publicKey,
+        promotion: promotionPDA,
+        metadata: deriveMetadataPDA(testCouponMint.publicKey)[0],
         tokenAccount: testTokenAccount,
         merchant: accounts.merchant1PDA,
         userStats: userStatsPDA,
@@ -717,6 +727,7 @@ describe("Redemption Tickets", () => {
     .accounts({
       ticket: wrongMerchantTicketPDA,
       coupon: wrongMerchantCouponPDA,
+      promotion: promotionPDA,
       user: accounts.user1.publicKey,
       systemProgram: SystemProgram.programId,
     })
@@ -734,11 +745,13 @@ describe("Redemption Tickets", () => {
   // TEST SCENARIO 1: Try to redeem with wrong merchant PDA
   try {
     await program.methods
-      .verifyAndRedeemTicket(wrongMerchantTicket.ticketHash)
+      .verifyAndRedeemTicket(wrongMerchantTicket.ticketHash, new BN(1), new BN(0))
       .accounts({
         ticket: wrongMerchantTicketPDA,
         coupon: wrongMerchantCouponPDA,
         nftMint: wrongMerchantCouponMint.publicKey,
+        promotion: promotionPDA,
+        metadata: deriveMetadataPDA(wrongMerchantCouponMint.publicKey)[0],
         tokenAccount: wrongMerchantTokenAccount,
         merchant: merchant2PDA,  // ← Wrong merchant PDA
         userStats: userStatsPDA,
@@ -766,11 +779,13 @@ describe("Redemption Tickets", () => {
   // TEST SCENARIO 2: Try to redeem with correct merchant PDA but wrong authority signature
   try {
     await program.methods
-      .verifyAndRedeemTicket(wrongMerchantTicket.ticketHash)
+      .verifyAndRedeemTicket(wrongMerchantTicket.ticketHash, new BN(1), new BN(0))
       .accounts({
         ticket: wrongMerchantTicketPDA,
         coupon: wrongMerchantCouponPDA,
         nftMint: wrongMerchantCouponMint.publicKey,
+        promotion: promotionPDA,
+        metadata: deriveMetadataPDA(wrongMerchantCouponMint.publicKey)[0],
         tokenAccount: wrongMerchantTokenAccount,
         merchant: accounts.merchant1PDA,  // ← Correct merchant PDA
         userStats: userStatsPDA,
@@ -881,6 +896,7 @@ describe("Redemption Tickets", () => {
         .accounts({
           ticket: cancelTicketPDA2,
           coupon: cancelCouponPDA,
+          promotion: promotionPDA,
           user: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -982,6 +998,7 @@ describe("Redemption Tickets", () => {
         .accounts({
           ticket: notOwnedTicketPDA,
           coupon: notOwnedCouponPDA,
+          promotion: promotionPDA,
           user: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -1095,6 +1112,7 @@ describe("Redemption Tickets", () => {
         .accounts({
           ticket: ticket1PDA,
           coupon: multiTicketCouponPDA,
+          promotion: promotionPDA,
           user: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -1107,6 +1125,7 @@ describe("Redemption Tickets", () => {
         .accounts({
           ticket: ticket2PDA,
           coupon: multiTicketCouponPDA,
+          promotion: promotionPDA,
           user: accounts.user1.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -1131,4 +1150,393 @@ describe("Redemption Tickets", () => {
       console.log(`  Both tickets reference same coupon: ${multiTicketCouponPDA.toString()}`);
     });
   });
+
+  describe("Signed Offline Tickets", () => {
+    let signedCouponPDA: PublicKey;
+    let signedCouponMint: Keypair;
+
+    const orderAmount = new BN(3 * LAMPORTS_PER_SOL);
+
+    // Message the holder signs: sha256("signed_ticket" | coupon | user | nonce | expires_at | order_amount)
+    function signedTicketHash(coupon: PublicKey, user: PublicKey, nonce: BN, expiresAt: BN): Buffer {
+      return createHash("sha256")
+        .update(Buffer.from("signed_ticket"))
+        .update(coupon.toBuffer())
+        .update(user.toBuffer())
+        .update(nonce.toArrayLike(Buffer, "le", 8))
+        .update(expiresAt.toTwos(64).toArrayLike(Buffer, "le", 8))
+        .update(orderAmount.toArrayLike(Buffer, "le", 8))
+        .digest();
+    }
+
+    function redeemSignedTicket(nonce: BN, expiresAt: BN, signer: Keypair, billed: BN = orderAmount) {
+      const message = signedTicketHash(signedCouponPDA, accounts.user1.publicKey, nonce, expiresAt);
+      const sigVerifyIx = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message,
+      });
+
+      return program.methods
+        .redeemSignedTicket(nonce, expiresAt, billed)
+        .accounts({
+          coupon: signedCouponPDA,
+          promotion: promotionPDA,
+          nftMint: signedCouponMint.publicKey,
+          metadata: deriveMetadataPDA(signedCouponMint.publicKey)[0],
+          merchant: accounts.merchant1PDA,
+          userStats: userStatsPDA,
+          merchantAuthority: accounts.merchant1.publicKey,
+        })
+        .preInstructions([sigVerifyIx])
+        .signers([accounts.merchant1]) // No holder signature at submission
+        .rpc();
+    }
+
+    before(async () => {
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      [signedCouponPDA] = derivePDA(
+        [
+          Buffer.from("coupon"),
+          promotionPDA.toBuffer(),
+          u32ToLeBytes(promotion.currentSupply),
+        ],
+        program.programId
+      );
+
+      signedCouponMint = Keypair.generate();
+      const computeBudgetIx = web3.ComputeBudgetProgram.setComputeUnitLimit({
+        units: 400_000,
+      });
+
+      await program.methods
+        .mintCoupon(new BN(3))
+        .accounts({
+          coupon: signedCouponPDA,
+          nftMint: signedCouponMint.publicKey,
+          tokenAccount: getAssociatedTokenAddressSync(
+            signedCouponMint.publicKey,
+            accounts.user1.publicKey
+          ),
+          metadata: deriveMetadataPDA(signedCouponMint.publicKey)[0],
+          masterEdition: deriveMasterEditionPDA(signedCouponMint.publicKey)[0],
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          marketplace: accounts.marketplacePDA,
+          recipient: accounts.user1.publicKey,
+          userStats: userStatsPDA,
+          payer: accounts.user1.publicKey,
+          authority: accounts.merchant1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          systemProgram: SystemProgram.programId,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+        })
+        .preInstructions([computeBudgetIx])
+        .signers([accounts.user1, signedCouponMint, accounts.merchant1])
+        .rpc();
+    });
+
+    it("Test 16: Fails to redeem a ticket signed by someone other than the holder", async () => {
+      const expiresAt = new BN(getCurrentTimestamp() + 300);
+
+      try {
+        await redeemSignedTicket(new BN(1), expiresAt, accounts.user2);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("InvalidTicketSignature");
+      }
+    });
+
+    it("Test 17: Redeems a holder-signed ticket without the holder signing the transaction", async () => {
+      const expiresAt = new BN(getCurrentTimestamp() + 300);
+      const merchantBefore = await program.account.merchant.fetch(accounts.merchant1PDA);
+
+      // The merchant can't submit a bigger bill than the holder signed for
+      try {
+        await redeemSignedTicket(new BN(2), expiresAt, accounts.user1, orderAmount.muln(2));
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("InvalidTicketSignature");
+      }
+
+      await redeemSignedTicket(new BN(2), expiresAt, accounts.user1);
+
+      const coupon = await program.account.coupon.fetch(signedCouponPDA);
+      const merchantAfter = await program.account.merchant.fetch(accounts.merchant1PDA);
+      assert.isTrue(coupon.isRedeemed);
+      assert.isTrue(coupon.metadataUri.endsWith("-redeemed.json"));
+      assert.equal(
+        merchantAfter.totalCouponsRedeemed.toNumber(),
+        merchantBefore.totalCouponsRedeemed.toNumber() + 1
+      );
+
+      // 50% off the signed 3 SOL bill
+      assert.equal(
+        merchantAfter.totalSavingsGiven.toNumber() - merchantBefore.totalSavingsGiven.toNumber(),
+        orderAmount.toNumber() / 2
+      );
+
+      // The holder keeps the NFT, now showing the redeemed state
+      const nftBalance = await connection.getTokenAccountBalance(
+        getAssociatedTokenAddressSync(signedCouponMint.publicKey, accounts.user1.publicKey)
+      );
+      assert.equal(nftBalance.value.amount, "1");
+    });
+
+    it("Test 18: Fails to redeem the same coupon twice", async () => {
+      const expiresAt = new BN(getCurrentTimestamp() + 300);
+
+      try {
+        await redeemSignedTicket(new BN(3), expiresAt, accounts.user1);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("CouponAlreadyRedeemed");
+      }
+    });
+  });
+
+  describe("Ticket Validity & Cleanup", () => {
+    function setTicketValidity(seconds: number) {
+      return program.methods
+        .setTicketValidity(seconds)
+        .accounts({
+          promotion: promotionPDA,
+          merchant: accounts.merchant1PDA,
+          authority: accounts.merchant1.publicKey,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+    }
+
+    async function findActiveCoupon(): Promise<PublicKey> {
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      for (let i = promotion.currentSupply - 1; i >= 0; i--) {
+        const [pda] = derivePDA(
+          [Buffer.from("coupon"), promotionPDA.toBuffer(), u32ToLeBytes(i)],
+          program.programId
+        );
+        const coupon = await program.account.coupon.fetchNullable(pda);
+        if (coupon && !coupon.isRedeemed && coupon.owner.equals(accounts.user1.publicKey)) {
+          return pda;
+        }
+      }
+      throw new Error("No unredeemed coupon for user1");
+    }
+
+    it("Test 19: Uses the promotion's ticket validity window", async () => {
+      const coupon = await findActiveCoupon();
+      const nonce = new BN(getCurrentTimestamp() + 1_000);
+      const [ticketPDA] = derivePDA(
+        [
+          Buffer.from("ticket"),
+          coupon.toBuffer(),
+          accounts.user1.publicKey.toBuffer(),
+          u64ToLeBytes(nonce),
+        ],
+        program.programId
+      );
+
+      await setTicketValidity(900);
+
+      try {
+        await program.methods
+          .generateRedemptionTicket(nonce, null, null)
+          .accounts({
+            ticket: ticketPDA,
+            coupon,
+            promotion: promotionPDA,
+            user: accounts.user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.user1])
+          .rpc();
+
+        const ticket = await program.account.redemptionTicket.fetch(ticketPDA);
+        assert.equal(ticket.expiresAt.toNumber() - ticket.createdAt.toNumber(), 900);
+
+        // Still valid, so the crank can't close it yet
+        try {
+          await program.methods
+            .closeExpiredTicket()
+            .accounts({
+              ticket: ticketPDA,
+              user: accounts.user1.publicKey,
+              cranker: accounts.user2.publicKey,
+            })
+            .signers([accounts.user2])
+            .rpc();
+          assert.fail("Should have thrown an error");
+        } catch (error: any) {
+          expect(error.toString()).to.include("TicketStillValid");
+        }
+      } finally {
+        await setTicketValidity(300);
+      }
+    });
+
+    it("Test 20: Fails to set a ticket validity outside the allowed range", async () => {
+      try {
+        await setTicketValidity(10);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("InvalidTicketValidity");
+      }
+    });
+  });
+
+  describe("Geofenced Redemption", () => {
+    // 500 m around Lagos, Nigeria
+    const geofence = { latitude: 6.5244, longitude: 3.3792, radiusMeters: 500 };
+    let geoPromotionPDA: PublicKey;
+    let geoCouponPDA: PublicKey;
+    let geoCouponMint: Keypair;
+
+    async function generateTicket(nonce: BN, latitude: number, longitude: number): Promise<PublicKey> {
+      const [ticketPDA] = derivePDA(
+        [
+          Buffer.from("ticket"),
+          geoCouponPDA.toBuffer(),
+          accounts.user1.publicKey.toBuffer(),
+          u64ToLeBytes(nonce),
+        ],
+        program.programId
+      );
+
+      await program.methods
+        .generateRedemptionTicket(nonce, latitude, longitude)
+        .accounts({
+          ticket: ticketPDA,
+          coupon: geoCouponPDA,
+          promotion: geoPromotionPDA,
+          user: accounts.user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.user1])
+        .rpc();
+
+      return ticketPDA;
+    }
+
+    async function redeemTicket(ticketPDA: PublicKey) {
+      const ticket = await program.account.redemptionTicket.fetch(ticketPDA);
+
+      return program.methods
+        .verifyAndRedeemTicket(ticket.ticketHash, new BN(1), new BN(0))
+        .accounts({
+          ticket: ticketPDA,
+          coupon: geoCouponPDA,
+          nftMint: geoCouponMint.publicKey,
+          promotion: geoPromotionPDA,
+          metadata: deriveMetadataPDA(geoCouponMint.publicKey)[0],
+          tokenAccount: getAssociatedTokenAddressSync(geoCouponMint.publicKey, accounts.user1.publicKey),
+          merchant: accounts.merchant1PDA,
+          userStats: userStatsPDA,
+          user: accounts.user1.publicKey,
+          merchantAuthority: accounts.merchant1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.merchant1, accounts.user1])
+        .rpc();
+    }
+
+    before(async () => {
+      const merchant = await program.account.merchant.fetch(accounts.merchant1PDA);
+      [geoPromotionPDA] = derivePDA(
+        [
+          Buffer.from("promotion"),
+          accounts.merchant1PDA.toBuffer(),
+          u64ToLeBytes(merchant.totalCouponsCreated),
+        ],
+        program.programId
+      );
+
+      await createPromotion(program, {
+        discountPercentage: 20,
+        maxSupply: 10,
+        expiryTimestamp: getExpiryTimestamp(7),
+        category: "Test Promotion",
+        description: "In-store only",
+        price: new BN(0),
+        geofence,
+      })
+        .accounts({
+          promotion: geoPromotionPDA,
+          merchant: accounts.merchant1PDA,
+          marketplace: accounts.marketplacePDA,
+          authority: accounts.merchant1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.merchant1])
+        .rpc();
+
+      [geoCouponPDA] = derivePDA(
+        [Buffer.from("coupon"), geoPromotionPDA.toBuffer(), u32ToLeBytes(0)],
+        program.programId
+      );
+      geoCouponMint = Keypair.generate();
+
+      await program.methods
+        .mintCoupon(new BN(1))
+        .accounts({
+          coupon: geoCouponPDA,
+          nftMint: geoCouponMint.publicKey,
+          tokenAccount: getAssociatedTokenAddressSync(
+            geoCouponMint.publicKey,
+            accounts.user1.publicKey
+          ),
+          metadata: deriveMetadataPDA(geoCouponMint.publicKey)[0],
+          masterEdition: deriveMasterEditionPDA(geoCouponMint.publicKey)[0],
+          promotion: geoPromotionPDA,
+          merchant: accounts.merchant1PDA,
+          marketplace: accounts.marketplacePDA,
+          recipient: accounts.user1.publicKey,
+          userStats: userStatsPDA,
+          payer: accounts.user1.publicKey,
+          authority: accounts.merchant1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          sysvarInstructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          systemProgram: SystemProgram.programId,
+          rent: web3.SYSVAR_RENT_PUBKEY,
+        })
+        .preInstructions([web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+        .signers([accounts.user1, geoCouponMint, accounts.merchant1])
+        .rpc();
+    });
+
+    it("Test 21: Stores the geofence on the promotion", async () => {
+      const promotion = await program.account.promotion.fetch(geoPromotionPDA);
+
+      assert.isTrue(promotion.isLocationBased);
+      assert.equal(promotion.radiusMeters, geofence.radiusMeters);
+      assert.equal(promotion.location.latitude, Math.trunc(geofence.latitude * 1_000_000));
+      assert.equal(promotion.location.longitude, Math.trunc(geofence.longitude * 1_000_000));
+    });
+
+    it("Test 22: Fails to redeem a ticket generated outside the geofence", async () => {
+      // About 1.1 km north of the promotion's location
+      const ticketPDA = await generateTicket(new BN(1), geofence.latitude + 0.01, geofence.longitude);
+
+      try {
+        await redeemTicket(ticketPDA);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.toString()).to.include("LocationNotSupported");
+      }
+    });
+
+    it("Test 23: Redeems a ticket generated inside the geofence", async () => {
+      // About 220 m east of the promotion's location
+      const ticketPDA = await generateTicket(new BN(2), geofence.latitude, geofence.longitude + 0.002);
+
+      await redeemTicket(ticketPDA);
+
+      const coupon = await program.account.coupon.fetch(geoCouponPDA);
+      assert.isTrue(coupon.isRedeemed);
+    });
+  });
 });
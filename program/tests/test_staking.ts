@@ -17,7 +17,8 @@ import {
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   TOKEN_METADATA_PROGRAM_ID,
-  airdrop
+  airdrop,
+  createPromotion
 } from "./setup";
 import { getAssociatedTokenAddressSync, createAssociatedTokenAccountInstruction } from "@solana/spl-token";
 
@@ -49,6 +50,7 @@ describe("Staking System", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -85,15 +87,12 @@ describe("Staking System", () => {
 
     const promotionExists = await accountExists(connection, promotionPDA);
     if (!promotionExists) {
-      await program.methods
-        .createPromotion(
-          50,
-          100,
-          getExpiryTimestamp(60),
-          "staking",
-          "Staking test promotion",
-          new BN(10 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, {
+        expiryTimestamp: getExpiryTimestamp(60),
+        category: "staking",
+        description: "Staking test promotion",
+        price: new BN(10 * LAMPORTS_PER_SOL),
+      })
         .accounts({
           promotion: promotionPDA,
           merchant: merchantPDA,
@@ -349,4 +348,92 @@ describe("Staking System", () => {
       console.log("✓ Staking pool creation timestamp is valid");
     });
   });
+
+  describe("Stake and Claim", () => {
+    it("Stakes a coupon and points its metadata at the staked URI", async () => {
+      const [metadataPDA] = deriveMetadataPDA(couponMint.publicKey);
+      const [couponAuthorityPDA] = derivePDA([Buffer.from("coupon_authority")], program.programId);
+      const couponBefore = await program.account.coupon.fetch(couponPDA);
+      const promotionBefore = await program.account.promotion.fetch(promotionPDA);
+
+      await program.methods
+        .stakeCoupon(new BN(1))
+        .accounts({
+          stakeAccount: stakeAccountPDA,
+          stakingPool: stakingPoolPDA,
+          coupon: couponPDA,
+          promotion: promotionPDA,
+          nftMint: couponMint.publicKey,
+          metadata: metadataPDA,
+          couponAuthority: couponAuthorityPDA,
+          userTokenAccount,
+          stakeVault: stakeVaultPDA,
+          user: accounts.user1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          sysvarInstructions: web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([accounts.user1])
+        .rpc();
+
+      const coupon = await program.account.coupon.fetch(couponPDA);
+      assert.notEqual(coupon.metadataUri, couponBefore.metadataUri);
+      assert.isTrue(coupon.metadataUri.endsWith("-staked.json"));
+      assert.isTrue(coupon.isEscrowed);
+
+      // The Metaplex metadata account carries the same URI
+      const metadataInfo = await connection.getAccountInfo(metadataPDA);
+      assert.isTrue(metadataInfo!.data.includes(Buffer.from(coupon.metadataUri)));
+
+      const vault = await connection.getTokenAccountBalance(stakeVaultPDA);
+      assert.equal(vault.value.amount, "1");
+
+      const promotion = await program.account.promotion.fetch(promotionPDA);
+      assert.equal(promotion.openEscrows, promotionBefore.openEscrows + 1);
+
+      const stakeAccount = await program.account.stakeAccount.fetch(stakeAccountPDA);
+      assert.isTrue(stakeAccount.isActive);
+      assert.equal(stakeAccount.durationDays.toNumber(), 1);
+      console.log("✓ Coupon staked, metadata URI:", coupon.metadataUri);
+    });
+
+    it("Refuses to claim before the stake unlocks", async () => {
+      // Metadata accounts are optional; the lock check runs regardless
+      try {
+        await program.methods
+          .claimRewards()
+          .accounts({
+            stakeAccount: stakeAccountPDA,
+            stakingPool: stakingPoolPDA,
+            coupon: couponPDA,
+            promotion: promotionPDA,
+            nftMint: couponMint.publicKey,
+            metadata: null,
+            couponAuthority: null,
+            stakeVault: stakeVaultPDA,
+            userTokenAccount,
+            user: accounts.user1.publicKey,
+            rewardPool: accounts.marketplaceAuthority.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            tokenMetadataProgram: null,
+            sysvarInstructions: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([accounts.user1, accounts.marketplaceAuthority])
+          .rpc();
+
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.message).to.include("InvalidExpiry");
+      }
+
+      const coupon = await program.account.coupon.fetch(couponPDA);
+      assert.isTrue(coupon.isEscrowed);
+      assert.isTrue(coupon.metadataUri.endsWith("-staked.json"));
+      const vault = await connection.getTokenAccountBalance(stakeVaultPDA);
+      assert.equal(vault.value.amount, "1");
+      console.log("✓ Early claim rejected, NFT stays in the stake vault");
+    });
+  });
 });
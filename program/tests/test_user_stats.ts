@@ -16,7 +16,8 @@ import {
   LAMPORTS_PER_SOL,
   TOKEN_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
-  TOKEN_METADATA_PROGRAM_ID
+  TOKEN_METADATA_PROGRAM_ID,
+  createPromotion
 } from "./setup";
 import { getAssociatedTokenAddressSync } from "@solana/spl-token";
 
@@ -42,6 +43,7 @@ describe("UserStats System", () => {
         .initialize()
         .accounts({
           marketplace: accounts.marketplacePDA,
+          treasury: accounts.treasuryPDA,
           authority: accounts.marketplaceAuthority.publicKey,
           systemProgram: SystemProgram.programId,
         })
@@ -78,15 +80,7 @@ describe("UserStats System", () => {
 
     const promotionExists = await accountExists(connection, promotionPDA);
     if (!promotionExists) {
-      await program.methods
-        .createPromotion(
-          50,
-          100,
-          getExpiryTimestamp(30),
-          "test",
-          "UserStats test promotion",
-          new BN(5 * LAMPORTS_PER_SOL)
-        )
+      await createPromotion(program, { category: "test", description: "UserStats test promotion" })
         .accounts({
           promotion: promotionPDA,
           merchant: merchantPDA,
@@ -382,6 +376,7 @@ describe("UserStats System", () => {
         EarlyAdopter: 4,
         MerchantPartner: 5,
         CommunityModerator: 6,
+        SavedOneSol: 7,
       };
 
       Object.entries(badgeTypes).forEach(([name, value]) => {